@@ -0,0 +1,455 @@
+//! Host side of the `fot:*` imports.
+//!
+//! Mirrors the lowering in `fot-wit-abi`: strings arrive as `(ptr, len)`,
+//! and string or `result` return values are written into the guest's return
+//! area. Returned strings are copied into memory obtained from the guest's
+//! `alloc` export, which hands ownership to the guest.
+
+use wasmtime::{Caller, Extern, Linker, Memory};
+
+use crate::CallState;
+
+type Result<T> = wasmtime::Result<T>;
+
+fn memory(caller: &mut Caller<'_, CallState>) -> Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => Err(wasmtime::Error::msg("agent does not export `memory`")),
+    }
+}
+
+fn read_str(caller: &mut Caller<'_, CallState>, ptr: u32, len: u32) -> Result<String> {
+    let memory = memory(caller)?;
+    let mut bytes = vec![0u8; len as usize];
+    memory.read(&*caller, ptr as usize, &mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+fn write(caller: &mut Caller<'_, CallState>, offset: u32, bytes: &[u8]) -> Result<()> {
+    let memory = memory(caller)?;
+    memory.write(&mut *caller, offset as usize, bytes)?;
+    Ok(())
+}
+
+/// Copy `value` into a fresh guest allocation and return `(ptr, len)`.
+fn lower_str(caller: &mut Caller<'_, CallState>, value: &str) -> Result<(u32, u32)> {
+    let len = u32::try_from(value.len())?;
+    let alloc = match caller.get_export("alloc") {
+        Some(Extern::Func(func)) => func.typed::<u32, u32>(&*caller)?,
+        _ => return Err(wasmtime::Error::msg("agent does not export `alloc`")),
+    };
+    let ptr = alloc.call(&mut *caller, len)?;
+    write(caller, ptr, value.as_bytes())?;
+    Ok((ptr, len))
+}
+
+/// Return area with a discriminant at 0 and a payload at `offset`.
+fn ret_area(is_err: bool, offset: usize, payload: &[u8]) -> [u8; 16] {
+    let mut area = [0u8; 16];
+    area[0] = is_err as u8;
+    area[offset..offset + payload.len()].copy_from_slice(payload);
+    area
+}
+
+fn ret_string(caller: &mut Caller<'_, CallState>, ret: u32, value: &str) -> Result<()> {
+    let (ptr, len) = lower_str(caller, value)?;
+    let mut area = [0u8; 8];
+    area[..4].copy_from_slice(&ptr.to_le_bytes());
+    area[4..].copy_from_slice(&len.to_le_bytes());
+    write(caller, ret, &area)
+}
+
+fn ret_result_string(
+    caller: &mut Caller<'_, CallState>,
+    ret: u32,
+    result: std::result::Result<String, u32>,
+) -> Result<()> {
+    let area = match result {
+        Ok(value) => {
+            let (ptr, len) = lower_str(caller, &value)?;
+            let mut payload = [0u8; 8];
+            payload[..4].copy_from_slice(&ptr.to_le_bytes());
+            payload[4..].copy_from_slice(&len.to_le_bytes());
+            ret_area(false, 4, &payload)
+        }
+        Err(code) => ret_area(true, 4, &code.to_le_bytes()),
+    };
+    write(caller, ret, &area[..12])
+}
+
+fn ret_result_unit(
+    caller: &mut Caller<'_, CallState>,
+    ret: u32,
+    result: std::result::Result<(), u32>,
+) -> Result<()> {
+    let area = match result {
+        Ok(()) => ret_area(false, 4, &[]),
+        Err(code) => ret_area(true, 4, &code.to_le_bytes()),
+    };
+    write(caller, ret, &area[..8])
+}
+
+fn ret_result_u64(
+    caller: &mut Caller<'_, CallState>,
+    ret: u32,
+    result: std::result::Result<u64, u32>,
+) -> Result<()> {
+    let area = match result {
+        Ok(value) => ret_area(false, 8, &value.to_le_bytes()),
+        Err(code) => ret_area(true, 8, &code.to_le_bytes()),
+    };
+    write(caller, ret, &area)
+}
+
+fn ret_result_f64(
+    caller: &mut Caller<'_, CallState>,
+    ret: u32,
+    result: std::result::Result<f64, u32>,
+) -> Result<()> {
+    ret_result_u64(caller, ret, result.map(f64::to_bits))
+}
+
+pub(crate) fn add_to_linker(linker: &mut Linker<CallState>) -> Result<()> {
+    add_graph(linker)?;
+    add_events(linker)?;
+    add_metrics(linker)?;
+    add_files(linker)
+}
+
+fn add_graph(linker: &mut Linker<CallState>) -> Result<()> {
+    let module = fot_graph::MODULE;
+
+    linker.func_wrap(
+        module,
+        "graph_read",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let query = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.graph.graph_read(&query);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "graph_write",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let mutation = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.graph.graph_write(&mutation);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(module, "begin_txn", |caller: Caller<'_, CallState>| {
+        caller.data().backends.graph.begin_txn()
+    })?;
+    linker.func_wrap(
+        module,
+        "commit_txn",
+        |mut caller: Caller<'_, CallState>, txn: u64, ret: u32| {
+            let result = caller.data().backends.graph.commit_txn(txn);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "rollback_txn",
+        |mut caller: Caller<'_, CallState>, txn: u64, ret: u32| {
+            let result = caller.data().backends.graph.rollback_txn(txn);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "auth_ctx",
+        |mut caller: Caller<'_, CallState>, ret: u32| {
+            let ctx = caller.data().auth.clone();
+            ret_string(&mut caller, ret, &ctx)
+        },
+    )?;
+    Ok(())
+}
+
+fn add_events(linker: &mut Linker<CallState>) -> Result<()> {
+    let module = fot_events::MODULE;
+
+    linker.func_wrap(
+        module,
+        "publish",
+        |mut caller: Caller<'_, CallState>,
+         topic_ptr: u32,
+         topic_len: u32,
+         message_ptr: u32,
+         message_len: u32,
+         ret: u32| {
+            let topic = read_str(&mut caller, topic_ptr, topic_len)?;
+            let message = read_str(&mut caller, message_ptr, message_len)?;
+            let result = caller.data().backends.events.publish(&topic, &message);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "subscribe",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let topic = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.events.subscribe(&topic);
+            ret_result_u64(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "unsubscribe",
+        |mut caller: Caller<'_, CallState>, subscription: u64, ret: u32| {
+            let result = caller.data().backends.events.unsubscribe(subscription);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "notify",
+        |mut caller: Caller<'_, CallState>,
+         user_ptr: u32,
+         user_len: u32,
+         message_ptr: u32,
+         message_len: u32,
+         ret: u32| {
+            let user_id = read_str(&mut caller, user_ptr, user_len)?;
+            let message = read_str(&mut caller, message_ptr, message_len)?;
+            let result = caller.data().backends.events.notify(&user_id, &message);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "emit_resonance",
+        |mut caller: Caller<'_, CallState>,
+         agent_ptr: u32,
+         agent_len: u32,
+         frequency: f64,
+         amplitude: f64,
+         context_ptr: u32,
+         context_len: u32,
+         ret: u32| {
+            let agent_id = read_str(&mut caller, agent_ptr, agent_len)?;
+            let context = read_str(&mut caller, context_ptr, context_len)?;
+            let result = caller
+                .data()
+                .backends
+                .events
+                .emit_resonance(&agent_id, frequency, amplitude, &context);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "broadcast_harmonic",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let data = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.events.broadcast_harmonic(&data);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "subscribe_resonance",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let range = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.events.subscribe_resonance(&range);
+            ret_result_u64(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "measure_collective_resonance",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let cluster = read_str(&mut caller, ptr, len)?;
+            let result = caller
+                .data()
+                .backends
+                .events
+                .measure_collective_resonance(&cluster);
+            ret_result_f64(&mut caller, ret, result)
+        },
+    )?;
+    Ok(())
+}
+
+fn add_metrics(linker: &mut Linker<CallState>) -> Result<()> {
+    let module = fot_metrics::MODULE;
+
+    linker.func_wrap(
+        module,
+        "record_virtue",
+        |mut caller: Caller<'_, CallState>,
+         user_ptr: u32,
+         user_len: u32,
+         virtue_ptr: u32,
+         virtue_len: u32,
+         delta: f64,
+         ret: u32| {
+            let user_id = read_str(&mut caller, user_ptr, user_len)?;
+            let virtue = read_str(&mut caller, virtue_ptr, virtue_len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .record_virtue(&user_id, &virtue, delta);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "record_usage",
+        |mut caller: Caller<'_, CallState>,
+         user_ptr: u32,
+         user_len: u32,
+         action_ptr: u32,
+         action_len: u32,
+         duration: u64,
+         ret: u32| {
+            let user_id = read_str(&mut caller, user_ptr, user_len)?;
+            let action = read_str(&mut caller, action_ptr, action_len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .record_usage(&user_id, &action, duration);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "get_stats",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let user_id = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.metrics.get_stats(&user_id);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "export_metrics",
+        |mut caller: Caller<'_, CallState>, ret: u32| {
+            let result = caller.data().backends.metrics.export_metrics();
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "record_resonance",
+        |mut caller: Caller<'_, CallState>,
+         agent_ptr: u32,
+         agent_len: u32,
+         context_ptr: u32,
+         context_len: u32,
+         amplitude: f64,
+         ret: u32| {
+            let agent_id = read_str(&mut caller, agent_ptr, agent_len)?;
+            let context = read_str(&mut caller, context_ptr, context_len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .record_resonance(&agent_id, &context, amplitude);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "measure_harmonic_coherence",
+        |mut caller: Caller<'_, CallState>,
+         agents_ptr: u32,
+         agents_len: u32,
+         context_ptr: u32,
+         context_len: u32,
+         ret: u32| {
+            let agent_ids = read_str(&mut caller, agents_ptr, agents_len)?;
+            let context = read_str(&mut caller, context_ptr, context_len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .measure_harmonic_coherence(&agent_ids, &context);
+            ret_result_f64(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "calculate_truth_field",
+        |mut caller: Caller<'_, CallState>,
+         claim_ptr: u32,
+         claim_len: u32,
+         contexts_ptr: u32,
+         contexts_len: u32,
+         ret: u32| {
+            let claim = read_str(&mut caller, claim_ptr, claim_len)?;
+            let contexts = read_str(&mut caller, contexts_ptr, contexts_len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .calculate_truth_field(&claim, &contexts);
+            ret_result_f64(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "get_resonance_spectrum",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let domain = read_str(&mut caller, ptr, len)?;
+            let result = caller
+                .data()
+                .backends
+                .metrics
+                .get_resonance_spectrum(&domain);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    Ok(())
+}
+
+fn add_files(linker: &mut Linker<CallState>) -> Result<()> {
+    let module = fot_files::MODULE;
+
+    linker.func_wrap(
+        module,
+        "fetch_dataset",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let url = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.files.fetch_dataset(&url);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "cache_get",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let key = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.files.cache_get(&key);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "cache_set",
+        |mut caller: Caller<'_, CallState>,
+         key_ptr: u32,
+         key_len: u32,
+         value_ptr: u32,
+         value_len: u32,
+         ret: u32| {
+            let key = read_str(&mut caller, key_ptr, key_len)?;
+            let value = read_str(&mut caller, value_ptr, value_len)?;
+            let result = caller.data().backends.files.cache_set(&key, &value);
+            ret_result_unit(&mut caller, ret, result)
+        },
+    )?;
+    linker.func_wrap(
+        module,
+        "open_local",
+        |mut caller: Caller<'_, CallState>, ptr: u32, len: u32, ret: u32| {
+            let path = read_str(&mut caller, ptr, len)?;
+            let result = caller.data().backends.files.open_local(&path);
+            ret_result_string(&mut caller, ret, result)
+        },
+    )?;
+    Ok(())
+}
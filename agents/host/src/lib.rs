@@ -0,0 +1,290 @@
+//! Runs FoT agent modules under wasmtime.
+//!
+//! An [`AgentHost`] compiles agent `.wasm` files once, then instantiates a
+//! fresh store per [`AgentHost::invoke`] with its own fuel budget and memory
+//! cap. The `fot:*` imports the module declares are served by the pluggable
+//! [`Backends`], which use the same traits agents are written against
+//! natively, except `auth_ctx`: each invocation answers it with the caller
+//! passed to [`AgentHost::invoke`], so one host can serve many users.
+
+mod imports;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use fot_agent_sdk::abi::LEN_PREFIX;
+use fot_agent_sdk::host::{Events, Files, Graph, Metrics};
+use fot_agent_sdk::memory::{MemoryEvents, MemoryFiles, MemoryGraph, MemoryMetrics};
+use fot_agent_sdk::protocol::AuthContext;
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+/// Implementations behind the graph, events, metrics and files imports.
+#[derive(Clone)]
+pub struct Backends {
+    pub graph: Arc<dyn Graph>,
+    pub events: Arc<dyn Events>,
+    pub metrics: Arc<dyn Metrics>,
+    pub files: Arc<dyn Files>,
+}
+
+impl Backends {
+    /// Fresh in-memory backends, for local development and tests.
+    pub fn memory() -> Self {
+        Backends {
+            graph: Arc::new(MemoryGraph::default()),
+            events: Arc::new(MemoryEvents::default()),
+            metrics: Arc::new(MemoryMetrics::default()),
+            files: Arc::new(MemoryFiles::default()),
+        }
+    }
+}
+
+/// Per-invocation resource limits.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Fuel available to a single `invoke`; roughly one unit per instruction.
+    pub fuel: u64,
+    /// Upper bound on the agent's linear memory, in bytes.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            fuel: 50_000_000,
+            max_memory_bytes: 32 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum HostError {
+    /// No agent is loaded under this name.
+    UnknownAgent(String),
+    /// The module failed to compile or is missing required exports.
+    InvalidModule {
+        agent: String,
+        message: String,
+    },
+    /// The agent used up its fuel budget.
+    OutOfFuel {
+        agent: String,
+    },
+    /// The agent trapped or an import failed fatally.
+    Trap {
+        agent: String,
+        message: String,
+    },
+    /// The reply buffer was out of bounds or not UTF-8.
+    InvalidReply {
+        agent: String,
+        message: String,
+    },
+    Io(std::io::Error),
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostError::UnknownAgent(agent) => write!(f, "no agent named `{agent}` is loaded"),
+            HostError::InvalidModule { agent, message } => {
+                write!(f, "agent `{agent}` is not a valid module: {message}")
+            }
+            HostError::OutOfFuel { agent } => write!(f, "agent `{agent}` ran out of fuel"),
+            HostError::Trap { agent, message } => write!(f, "agent `{agent}` trapped: {message}"),
+            HostError::InvalidReply { agent, message } => {
+                write!(f, "agent `{agent}` returned an invalid reply: {message}")
+            }
+            HostError::Io(e) => write!(f, "failed to read agent module: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HostError {}
+
+/// Store data for one invocation.
+pub(crate) struct CallState {
+    backends: Backends,
+    /// The `auth_ctx` JSON for this call's caller.
+    auth: String,
+    limits: StoreLimits,
+}
+
+pub struct AgentHost {
+    engine: Engine,
+    linker: Linker<CallState>,
+    agents: HashMap<String, Module>,
+    backends: Backends,
+    limits: Limits,
+}
+
+impl AgentHost {
+    pub fn new(backends: Backends, limits: Limits) -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).expect("fuel-metered engine config is valid");
+
+        let mut linker = Linker::new(&engine);
+        imports::add_to_linker(&mut linker).expect("fot imports are registered once");
+
+        AgentHost {
+            engine,
+            linker,
+            agents: HashMap::new(),
+            backends,
+            limits,
+        }
+    }
+
+    pub fn backends(&self) -> &Backends {
+        &self.backends
+    }
+
+    /// Compile `wasm` (binary or text format) and register it as `name`.
+    pub fn load(&mut self, name: &str, wasm: impl AsRef<[u8]>) -> Result<(), HostError> {
+        let module = Module::new(&self.engine, wasm).map_err(|e| HostError::InvalidModule {
+            agent: name.to_string(),
+            message: format!("{e:#}"),
+        })?;
+        self.agents.insert(name.to_string(), module);
+        Ok(())
+    }
+
+    /// Read and [`load`](Self::load) an agent module from disk.
+    pub fn load_file(&mut self, name: &str, path: impl AsRef<Path>) -> Result<(), HostError> {
+        let wasm = std::fs::read(path).map_err(HostError::Io)?;
+        self.load(name, wasm)
+    }
+
+    /// Run one JSON command through `agent` on behalf of `caller` and
+    /// return its JSON reply. `None` runs it unauthenticated.
+    ///
+    /// Agent-level failures come back as `success: false` replies; `Err` is
+    /// reserved for the agent misbehaving (trap, fuel, broken ABI).
+    pub fn invoke(
+        &self,
+        agent: &str,
+        caller: Option<&AuthContext>,
+        input: &str,
+    ) -> Result<String, HostError> {
+        let module = self
+            .agents
+            .get(agent)
+            .ok_or_else(|| HostError::UnknownAgent(agent.to_string()))?;
+
+        let mut store = Store::new(
+            &self.engine,
+            CallState {
+                backends: self.backends.clone(),
+                auth: caller.map_or_else(
+                    || "{}".to_string(),
+                    |caller| serde_json::to_string(caller).expect("auth contexts serialize"),
+                ),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(self.limits.max_memory_bytes)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.limits.fuel)
+            .expect("fuel is enabled on the engine");
+
+        Invocation { agent, store }.run(&self.linker, module, input)
+    }
+}
+
+/// One `invoke` call: the store plus the agent name used in errors.
+struct Invocation<'a> {
+    agent: &'a str,
+    store: Store<CallState>,
+}
+
+impl Invocation<'_> {
+    fn run(
+        mut self,
+        linker: &Linker<CallState>,
+        module: &Module,
+        input: &str,
+    ) -> Result<String, HostError> {
+        let instance = linker
+            .instantiate(&mut self.store, module)
+            .map_err(|e| self.failed(e, true))?;
+        let memory = instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or_else(|| self.invalid_module("agent does not export `memory`".to_string()))?;
+        let alloc = instance
+            .get_typed_func::<u32, u32>(&mut self.store, "alloc")
+            .map_err(|e| self.invalid_module(format!("{e:#}")))?;
+        let dealloc = instance
+            .get_typed_func::<(u32, u32), ()>(&mut self.store, "dealloc")
+            .map_err(|e| self.invalid_module(format!("{e:#}")))?;
+        let run = instance
+            .get_typed_func::<(u32, u32), u32>(&mut self.store, "run")
+            .map_err(|e| self.invalid_module(format!("{e:#}")))?;
+
+        let input_len = u32::try_from(input.len())
+            .map_err(|_| self.invalid_reply("input exceeds 4 GiB".to_string()))?;
+        let input_ptr = alloc
+            .call(&mut self.store, input_len)
+            .map_err(|e| self.failed(e, false))?;
+        memory
+            .write(&mut self.store, input_ptr as usize, input.as_bytes())
+            .map_err(|_| self.invalid_reply(format!("alloc returned out-of-bounds {input_ptr}")))?;
+        let reply_ptr = run
+            .call(&mut self.store, (input_ptr, input_len))
+            .map_err(|e| self.failed(e, false))?;
+        dealloc
+            .call(&mut self.store, (input_ptr, input_len))
+            .map_err(|e| self.failed(e, false))?;
+
+        let mut header = [0u8; LEN_PREFIX];
+        memory
+            .read(&self.store, reply_ptr as usize, &mut header)
+            .map_err(|_| {
+                self.invalid_reply(format!("reply pointer {reply_ptr} is out of bounds"))
+            })?;
+        let reply_len = u32::from_le_bytes(header);
+        let mut payload = vec![0u8; reply_len as usize];
+        memory
+            .read(&self.store, reply_ptr as usize + LEN_PREFIX, &mut payload)
+            .map_err(|_| {
+                self.invalid_reply(format!("reply of {reply_len} bytes is out of bounds"))
+            })?;
+        dealloc
+            .call(&mut self.store, (reply_ptr, LEN_PREFIX as u32 + reply_len))
+            .map_err(|e| self.failed(e, false))?;
+
+        String::from_utf8(payload).map_err(|e| self.invalid_reply(e.to_string()))
+    }
+
+    fn invalid_module(&self, message: String) -> HostError {
+        HostError::InvalidModule {
+            agent: self.agent.to_string(),
+            message,
+        }
+    }
+
+    fn invalid_reply(&self, message: String) -> HostError {
+        HostError::InvalidReply {
+            agent: self.agent.to_string(),
+            message,
+        }
+    }
+
+    /// Map a wasmtime error to a fuel, trap or (while linking) module error.
+    fn failed(&self, error: wasmtime::Error, linking: bool) -> HostError {
+        let agent = self.agent.to_string();
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => HostError::OutOfFuel { agent },
+            None if linking => self.invalid_module(format!("{error:#}")),
+            _ => HostError::Trap {
+                agent,
+                message: format!("{error:#}"),
+            },
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Exercises the host against small hand-written agents in WAT.
+
+use std::sync::Arc;
+
+use fot_agent_host::{AgentHost, Backends, HostError, Limits};
+use fot_agent_sdk::memory::MemoryGraph;
+use fot_agent_sdk::protocol::{AuthContext, Role};
+use serde_json::json;
+
+/// Bump allocator plus the buffer exports every agent provides.
+const PRELUDE: &str = r#"
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (func $alloc (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+  (func (export "dealloc") (param i32 i32))
+"#;
+
+fn agent(imports: &str, run_body: &str) -> String {
+    format!(
+        "(module {imports} {PRELUDE} (func (export \"run\") (param $ptr i32) (param $len i32) (result i32) {run_body}))"
+    )
+}
+
+#[test]
+fn forwards_graph_write_and_returns_the_reply() {
+    // Passes the input to graph_write and replies with the Ok payload.
+    let wat = agent(
+        r#"(import "fot:graph/graph" "graph_write" (func $graph_write (param i32 i32 i32)))"#,
+        r#"(local $out i32) (local $src i32) (local $n i32)
+           (call $graph_write (local.get $ptr) (local.get $len) (i32.const 16))
+           (local.set $src (i32.load (i32.const 20)))
+           (local.set $n (i32.load (i32.const 24)))
+           (local.set $out (call $alloc (i32.add (local.get $n) (i32.const 4))))
+           (i32.store (local.get $out) (local.get $n))
+           (memory.copy (i32.add (local.get $out) (i32.const 4)) (local.get $src) (local.get $n))
+           (local.get $out)"#,
+    );
+
+    let graph = Arc::new(MemoryGraph::default());
+    let backends = Backends {
+        graph: graph.clone(),
+        ..Backends::memory()
+    };
+    let mut host = AgentHost::new(backends, Limits::default());
+    host.load("writer", wat).unwrap();
+
+    let reply = host
+        .invoke("writer", None, r#"{"operation":"create_lesson"}"#)
+        .unwrap();
+    assert_eq!(reply, r#"{"write":1}"#);
+    assert_eq!(graph.writes()[0]["operation"], "create_lesson");
+}
+
+#[test]
+fn each_invocation_sees_its_own_caller() {
+    // Replies with whatever auth_ctx returns.
+    let wat = agent(
+        r#"(import "fot:graph/graph" "auth_ctx" (func $auth_ctx (param i32)))"#,
+        r#"(local $out i32) (local $src i32) (local $n i32)
+           (call $auth_ctx (i32.const 16))
+           (local.set $src (i32.load (i32.const 16)))
+           (local.set $n (i32.load (i32.const 20)))
+           (local.set $out (call $alloc (i32.add (local.get $n) (i32.const 4))))
+           (i32.store (local.get $out) (local.get $n))
+           (memory.copy (i32.add (local.get $out) (i32.const 4)) (local.get $src) (local.get $n))
+           (local.get $out)"#,
+    );
+
+    // The shared graph backend knows someone else; callers must not see it.
+    let graph = Arc::new(MemoryGraph::default());
+    graph.set_auth_ctx(json!({"subject": "mallory", "role": "teacher"}));
+    let backends = Backends {
+        graph,
+        ..Backends::memory()
+    };
+    let mut host = AgentHost::new(backends, Limits::default());
+    host.load("whoami", wat).unwrap();
+
+    let ada = AuthContext {
+        subject: "ada".to_string(),
+        role: Role::Student,
+    };
+    let grace = AuthContext {
+        subject: "grace".to_string(),
+        role: Role::Parent,
+    };
+    let reply = |caller| -> serde_json::Value {
+        serde_json::from_str(&host.invoke("whoami", caller, "{}").unwrap()).unwrap()
+    };
+    assert_eq!(
+        reply(Some(&ada)),
+        json!({"subject": "ada", "role": "student"})
+    );
+    assert_eq!(
+        reply(Some(&grace)),
+        json!({"subject": "grace", "role": "parent"})
+    );
+    assert_eq!(reply(None), json!({}));
+}
+
+#[test]
+fn runaway_agents_run_out_of_fuel() {
+    let wat = agent("", "(loop $spin (br $spin)) (unreachable)");
+    let mut host = AgentHost::new(
+        Backends::memory(),
+        Limits {
+            fuel: 10_000,
+            ..Limits::default()
+        },
+    );
+    host.load("spin", wat).unwrap();
+
+    assert!(matches!(
+        host.invoke("spin", None, "{}"),
+        Err(HostError::OutOfFuel { .. })
+    ));
+}
+
+#[test]
+fn memory_growth_is_capped() {
+    // Traps when memory.grow is refused.
+    let wat = agent(
+        "",
+        "(if (i32.eq (memory.grow (i32.const 64)) (i32.const -1)) (then (unreachable))) (i32.const 0)",
+    );
+    let mut host = AgentHost::new(
+        Backends::memory(),
+        Limits {
+            max_memory_bytes: 2 * 65_536,
+            ..Limits::default()
+        },
+    );
+    host.load("greedy", wat).unwrap();
+
+    assert!(matches!(
+        host.invoke("greedy", None, "{}"),
+        Err(HostError::Trap { .. })
+    ));
+}
+
+#[test]
+fn unknown_agents_are_rejected() {
+    let host = AgentHost::new(Backends::memory(), Limits::default());
+    assert!(matches!(
+        host.invoke("missing", None, "{}"),
+        Err(HostError::UnknownAgent(_))
+    ));
+}
@@ -1,138 +1,221 @@
-use serde_json::json;
-use wasm_bindgen::prelude::*;
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{self, GetChildProgress, IsGuardian, Role};
+use fot_agent_sdk::{export_agent, Agent, AgentError, Host};
 
 #[derive(serde::Deserialize)]
-struct GetChildProgressInput {
-    op: String,
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ParentRequest {
+    GetChildProgress(GetChildProgressInput),
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetChildProgressInput {
     child_id: String,
+    #[serde(default)]
+    window: Window,
+}
+
+/// How far back progress is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Window {
+    LastWeek,
+    /// The last twelve weeks.
+    Term,
+    #[default]
+    All,
+}
+
+impl Window {
+    const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+
+    /// Start of the window, for a window ending at `now`.
+    fn since(self, now: u64) -> Option<u64> {
+        match self {
+            Window::LastWeek => Some(now.saturating_sub(Self::WEEK_SECS)),
+            Window::Term => Some(now.saturating_sub(12 * Self::WEEK_SECS)),
+            Window::All => None,
+        }
+    }
 }
 
 #[derive(serde::Serialize)]
-struct ChildProgressResponse {
-    success: bool,
+pub struct ChildProgressResponse {
     child_id: String,
+    window: Window,
     concepts: Vec<ConceptProgress>,
     virtues: VirtueMetrics,
 }
 
 #[derive(serde::Serialize)]
-struct ConceptProgress {
+pub struct ConceptProgress {
     concept_id: String,
     concept_name: String,
     mastery: f64,
     last_updated: String,
+    /// Mastery gained (or lost) over the window.
+    change: f64,
+    trend: Trend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    Improving,
+    Steady,
+    Declining,
+}
+
+impl Trend {
+    /// Changes smaller than this read as steady.
+    const THRESHOLD: f64 = 0.01;
+
+    fn of(change: f64) -> Self {
+        if change > Self::THRESHOLD {
+            Trend::Improving
+        } else if change < -Self::THRESHOLD {
+            Trend::Declining
+        } else {
+            Trend::Steady
+        }
+    }
+}
+
+impl ConceptProgress {
+    /// Progress as it stands at `now`: mastery is decayed the way the
+    /// student agent reads it, so forgetting shows as a decline.
+    fn at(progress: protocol::ConceptProgress, now: u64) -> Self {
+        let mastery = decay(
+            progress.mastery,
+            progress.last_updated,
+            progress.review,
+            now,
+        );
+        // Mastery starts at zero, so a concept first learned inside the
+        // window counts all of its mastery as progress.
+        let start = progress.baseline.unwrap_or(0.0);
+        let change = mastery - start;
+        ConceptProgress {
+            concept_id: progress.concept_id,
+            concept_name: progress.concept_name,
+            mastery,
+            last_updated: progress
+                .last_updated
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            change,
+            trend: Trend::of(change),
+        }
+    }
 }
 
 #[derive(serde::Serialize)]
-struct VirtueMetrics {
+pub struct VirtueMetrics {
     honesty: f64,
     curiosity: f64,
     patience: f64,
 }
 
-#[wasm_bindgen]
-pub fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
-    // Parse input command
-    let input_bytes = unsafe { std::slice::from_raw_parts(input_ptr, len) };
-    let input_str = String::from_utf8_lossy(input_bytes);
-    
-    match serde_json::from_str::<GetChildProgressInput>(&input_str) {
-        Ok(input) => {
-            if input.op == "get_child_progress" {
-                // REAL HOST FUNCTION CALL - NO SIMULATION
-                let query = json!({
-                    "operation": "get_child_progress",
-                    "child_id": input.child_id,
-                    "timestamp": std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs()
-                });
-                
-                let query_str = serde_json::to_string(&query).unwrap();
-                
-                // CALL REAL HOST FUNCTION
-                match unsafe { 
-                    crate::fot_graph::graph_read(query_str.as_ptr(), query_str.len()) 
-                } {
-                    Ok(result) => {
-                        // Parse real response from graph - NO HARDCODED VALUES
-                        let response = ChildProgressResponse {
-                            success: true,
-                            child_id: input.child_id,
-                            concepts: vec![], // Will be populated from real graph data
-                            virtues: VirtueMetrics {
-                                honesty: 0.0, // Will be populated from real graph data
-                                curiosity: 0.0, // Will be populated from real graph data
-                                patience: 0.0, // Will be populated from real graph data
-                            },
-                        };
-                        
-                        let response_json = serde_json::to_string(&response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                    Err(_) => {
-                        let error_response = ChildProgressResponse {
-                            success: false,
-                            child_id: input.child_id,
-                            concepts: vec![],
-                            virtues: VirtueMetrics {
-                                honesty: 0.0,
-                                curiosity: 0.0,
-                                patience: 0.0,
-                            },
-                        };
-                        
-                        let response_json = serde_json::to_string(&error_response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                }
-            } else {
-                let error_response = ChildProgressResponse {
-                    success: false,
-                    child_id: "".to_string(),
-                    concepts: vec![],
-                    virtues: VirtueMetrics {
-                        honesty: 0.0,
-                        curiosity: 0.0,
-                        patience: 0.0,
-                    },
-                };
-                
-                let response_json = serde_json::to_string(&error_response).unwrap();
-                let response_bytes = response_json.into_bytes();
-                let response_ptr = response_bytes.as_mut_ptr();
-                
-                std::mem::forget(response_bytes);
-                response_ptr
-            }
-        }
-        Err(_) => {
-            let error_response = ChildProgressResponse {
-                success: false,
-                child_id: "".to_string(),
-                concepts: vec![],
-                virtues: VirtueMetrics {
-                    honesty: 0.0,
-                    curiosity: 0.0,
-                    patience: 0.0,
-                },
-            };
-            
-            let response_json = serde_json::to_string(&error_response).unwrap();
-            let response_bytes = response_json.into_bytes();
-            let response_ptr = response_bytes.as_mut_ptr();
-            
-            std::mem::forget(response_bytes);
-            response_ptr
+#[derive(Default)]
+pub struct ParentAgent;
+
+impl Agent for ParentAgent {
+    type Request = ParentRequest;
+    type Response = ChildProgressResponse;
+
+    fn handle(
+        &self,
+        host: &Host,
+        request: ParentRequest,
+    ) -> Result<ChildProgressResponse, AgentError> {
+        match request {
+            ParentRequest::GetChildProgress(input) => get_child_progress(host, input),
         }
     }
 }
+
+export_agent!(ParentAgent);
+
+/// Topic every progress access attempt is published on.
+pub const ACCESS_LOG_TOPIC: &str = "audit.child_progress";
+
+#[derive(serde::Serialize)]
+struct AccessAttempt<'a> {
+    subject: Option<&'a str>,
+    child_id: &'a str,
+    allowed: bool,
+    timestamp: u64,
+}
+
+/// Check that the caller is a parent with a guardian relationship to
+/// `child_id`, logging the attempt either way. Access is refused when the
+/// attempt cannot be logged.
+fn authorize(host: &Host, child_id: &str, now: u64) -> Result<(), AgentError> {
+    let auth = host.auth();
+    let result = auth.as_ref().map_err(Clone::clone).and_then(|auth| {
+        if auth.role != Role::Parent {
+            return Err(AgentError::forbidden(
+                "only parents may read child progress",
+            ));
+        }
+        let guardianship = host.query(IsGuardian {
+            child_id: child_id.to_string(),
+        })?;
+        if !guardianship.guardian {
+            return Err(AgentError::forbidden(format!(
+                "{} is not a guardian of {child_id}",
+                auth.subject
+            )));
+        }
+        Ok(())
+    });
+
+    let attempt = AccessAttempt {
+        subject: auth.as_ref().ok().map(|a| a.subject.as_str()),
+        child_id,
+        allowed: result.is_ok(),
+        timestamp: now,
+    };
+    let message = serde_json::to_string(&attempt).expect("access attempts serialize to JSON");
+    host.events
+        .publish(ACCESS_LOG_TOPIC, &message)
+        .map_err(|code| AgentError::host("publish", code))?;
+    result
+}
+
+fn get_child_progress(
+    host: &Host,
+    input: GetChildProgressInput,
+) -> Result<ChildProgressResponse, AgentError> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    authorize(host, &input.child_id, now)?;
+    let since = input.window.since(now);
+
+    // CALL REAL HOST FUNCTION
+    let progress = host.query(GetChildProgress {
+        child_id: input.child_id,
+        since,
+    })?;
+
+    // Concepts untouched during the window have nothing to report.
+    let concepts = progress
+        .concepts
+        .into_iter()
+        .filter(|c| since.is_none() || !c.history.is_empty())
+        .map(|c| ConceptProgress::at(c, now))
+        .collect();
+
+    Ok(ChildProgressResponse {
+        child_id: progress.child_id,
+        window: input.window,
+        concepts,
+        virtues: VirtueMetrics {
+            honesty: progress.virtues.honesty,
+            curiosity: progress.virtues.curiosity,
+            patience: progress.virtues.patience,
+        },
+    })
+}
@@ -0,0 +1,139 @@
+//! Runs the parent agent natively against the in-memory host.
+
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::test_util::{self, now};
+use parent_agent::{ParentAgent, ACCESS_LOG_TOPIC};
+use serde_json::{json, Value};
+
+fn invoke(host: &MemoryHost, input: &str) -> Value {
+    test_util::invoke(&ParentAgent, &host.host(), input)
+}
+
+fn guardian_host(guardian: bool) -> MemoryHost {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "parent-1", "role": "parent"}));
+    host.graph
+        .respond("is_guardian", json!({ "guardian": guardian }));
+    host
+}
+
+#[test]
+fn reports_graph_progress_with_trend_per_concept() {
+    let host = guardian_host(true);
+    let yesterday = now() - 24 * 60 * 60;
+    host.graph.respond(
+        "get_child_progress",
+        json!({
+            "child_id": "student-1",
+            "concepts": [
+                {
+                    "concept_id": "fot:Photosynthesis",
+                    "concept_name": "Photosynthesis",
+                    "mastery": 0.6,
+                    "last_updated": yesterday,
+                    "baseline": 0.4,
+                    "history": [{"at": yesterday, "mastery": 0.6}],
+                },
+                {
+                    "concept_id": "fot:Metabolism",
+                    "concept_name": "Metabolism",
+                    "mastery": 0.3,
+                    "last_updated": yesterday,
+                    "baseline": 0.5,
+                    "history": [{"at": yesterday, "mastery": 0.3}],
+                },
+                {
+                    "concept_id": "fot:Ecosystem",
+                    "concept_name": "Ecosystem",
+                    "mastery": 0.9,
+                    "baseline": 0.9,
+                    "history": [],
+                },
+            ],
+            "virtues": {"honesty": 0.8, "curiosity": 0.7, "patience": 0.5},
+        }),
+    );
+
+    let reply = invoke(
+        &host,
+        r#"{"op":"get_child_progress","child_id":"student-1","window":"last_week"}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["window"], "last_week");
+    let concepts = reply["concepts"].as_array().unwrap();
+    assert_eq!(concepts.len(), 2, "Ecosystem was not studied this week");
+    assert_eq!(concepts[0]["trend"], "improving");
+    assert_eq!(concepts[1]["trend"], "declining");
+    assert_eq!(reply["virtues"]["honesty"], 0.8);
+}
+
+#[test]
+fn forgotten_mastery_reads_as_declining() {
+    let host = guardian_host(true);
+    // Learned eight weeks ago and untouched since: nearly two half-lives
+    // leave 0.8 at about 0.22.
+    let learned = now() - 8 * 7 * 24 * 60 * 60;
+    host.graph.respond(
+        "get_child_progress",
+        json!({
+            "child_id": "student-1",
+            "concepts": [{
+                "concept_id": "fot:Photosynthesis",
+                "concept_name": "Photosynthesis",
+                "mastery": 0.8,
+                "last_updated": learned,
+                "baseline": 0.5,
+                "history": [{"at": learned, "mastery": 0.8}],
+            }],
+            "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0},
+        }),
+    );
+
+    let reply = invoke(
+        &host,
+        r#"{"op":"get_child_progress","child_id":"student-1","window":"term"}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    let concept = &reply["concepts"][0];
+    let mastery = concept["mastery"].as_f64().unwrap();
+    assert!((0.2..0.25).contains(&mastery), "{mastery}");
+    assert_eq!(concept["trend"], "declining");
+}
+
+#[test]
+fn non_guardians_are_forbidden_and_logged() {
+    let host = guardian_host(false);
+    let reply = invoke(
+        &host,
+        r#"{"op":"get_child_progress","child_id":"student-1"}"#,
+    );
+
+    assert_eq!(reply["success"], false);
+    assert_eq!(reply["error"]["kind"], "forbidden");
+
+    let published = host.events.published();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].0, ACCESS_LOG_TOPIC);
+    let attempt: Value = serde_json::from_str(&published[0].1).unwrap();
+    assert_eq!(attempt["subject"], "parent-1");
+    assert_eq!(attempt["allowed"], false);
+}
+
+#[test]
+fn access_is_refused_when_it_cannot_be_logged() {
+    let host = guardian_host(true);
+    host.events.refuse(ACCESS_LOG_TOPIC, 503);
+    let reply = invoke(
+        &host,
+        r#"{"op":"get_child_progress","child_id":"student-1"}"#,
+    );
+
+    assert_eq!(reply["success"], false);
+    assert_eq!(reply["error"]["kind"], "host");
+    assert_eq!(reply["error"]["import"], "publish");
+    assert!(reply.get("concepts").is_none());
+    assert!(host.events.published().is_empty());
+}
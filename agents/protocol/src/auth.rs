@@ -0,0 +1,27 @@
+//! The caller identity returned by `auth_ctx`.
+
+/// Who is calling, as established by the host.
+///
+/// `auth_ctx` returns `{}` for an unauthenticated caller, which does not
+/// parse as an `AuthContext`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuthContext {
+    /// Agent id of the caller in the graph.
+    pub subject: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Student,
+    Teacher,
+    Parent,
+    Admin,
+}
+
+impl AuthContext {
+    pub fn parse(ctx: &str) -> Option<Self> {
+        serde_json::from_str(ctx).ok()
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+
+/// Error codes returned by host imports.
+///
+/// The numbering follows HTTP status codes so the same value can be passed
+/// straight through by hosts backed by an HTTP service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u32)]
+pub enum ErrorCode {
+    /// The payload was not valid JSON or did not match the operation.
+    InvalidPayload = 400,
+    /// The caller has no identity in `auth_ctx`.
+    Unauthenticated = 401,
+    /// The caller is known but may not perform the operation.
+    Forbidden = 403,
+    /// A node, key or resource the call refers to does not exist.
+    NotFound = 404,
+    /// The call referenced a transaction or subscription that is not open.
+    InvalidHandle = 409,
+    /// The target's current state does not allow the operation, e.g.
+    /// editing an archived lesson.
+    InvalidState = 412,
+    /// The write would break a schema constraint and was rolled back.
+    ConstraintViolation = 422,
+    /// The backend failed for a reason outside the caller's control.
+    Internal = 500,
+    /// The `operation` tag is not one the host implements.
+    UnknownOperation = 501,
+    /// The payload's `version` is newer than the host understands.
+    UnsupportedVersion = 505,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 10] = [
+        ErrorCode::InvalidPayload,
+        ErrorCode::Unauthenticated,
+        ErrorCode::Forbidden,
+        ErrorCode::NotFound,
+        ErrorCode::InvalidHandle,
+        ErrorCode::InvalidState,
+        ErrorCode::ConstraintViolation,
+        ErrorCode::Internal,
+        ErrorCode::UnknownOperation,
+        ErrorCode::UnsupportedVersion,
+    ];
+
+    /// The value passed across the WIT boundary.
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.code() == code)
+    }
+}
+
+impl From<ErrorCode> for u32 {
+    fn from(error: ErrorCode) -> u32 {
+        error.code()
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ErrorCode::InvalidPayload => "invalid payload",
+            ErrorCode::Unauthenticated => "unauthenticated",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::NotFound => "not found",
+            ErrorCode::InvalidHandle => "invalid handle",
+            ErrorCode::InvalidState => "invalid state",
+            ErrorCode::ConstraintViolation => "constraint violation",
+            ErrorCode::Internal => "internal error",
+            ErrorCode::UnknownOperation => "unknown operation",
+            ErrorCode::UnsupportedVersion => "unsupported protocol version",
+        };
+        write!(f, "{text} ({})", self.code())
+    }
+}
+
+impl std::error::Error for ErrorCode {}
@@ -0,0 +1,122 @@
+//! Typed operations exchanged over `graph_read` and `graph_write`.
+//!
+//! Agents build a [`Query`] or [`Mutation`] value instead of a free-form
+//! JSON blob; hosts decode the payload into [`GraphRead`] or [`GraphWrite`].
+//! On the wire every payload is a flat JSON object tagged by `operation` and
+//! stamped with the protocol `version`:
+//!
+//! ```json
+//! {"version": 1, "operation": "update_mastery", "concept": "Photosynthesis", ...}
+//! ```
+//!
+//! Failures travel back as the `u32` codes of [`ErrorCode`]; the caller
+//! identity behind `auth_ctx` is an [`AuthContext`].
+
+mod auth;
+mod error;
+mod ops;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+pub use auth::{AuthContext, Role};
+pub use error::ErrorCode;
+pub use ops::*;
+
+/// Protocol version written by this crate. Hosts accept payloads up to it;
+/// a payload without `version` is treated as version 1.
+pub const VERSION: u32 = 1;
+
+/// A read served by `graph_read`.
+pub trait Query: Into<GraphRead> {
+    type Output: Serialize + DeserializeOwned;
+}
+
+/// A write served by `graph_write`.
+pub trait Mutation: Into<GraphWrite> {
+    type Output: Serialize + DeserializeOwned;
+}
+
+/// The operations one graph import understands.
+pub trait OperationSet: Serialize + DeserializeOwned {
+    /// Every `operation` tag in the set.
+    const OPERATIONS: &'static [&'static str];
+}
+
+/// A versioned operation as it appears on the wire.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Envelope<T> {
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// The open transaction a read or write runs in; without one a write
+    /// commits on its own and a read sees only committed data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txn: Option<u64>,
+    #[serde(flatten)]
+    pub op: T,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl<T: OperationSet> Envelope<T> {
+    pub fn new(op: impl Into<T>) -> Self {
+        Envelope {
+            version: VERSION,
+            txn: None,
+            op: op.into(),
+        }
+    }
+
+    /// Run the operation inside transaction `txn`.
+    pub fn in_txn(self, txn: u64) -> Self {
+        Envelope {
+            txn: Some(txn),
+            ..self
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).expect("operations serialize to JSON")
+    }
+
+    /// Parse a payload, rejecting newer versions and unknown operations
+    /// before looking at the operation's fields.
+    pub fn decode(payload: &str) -> Result<Self, ErrorCode> {
+        let value: Value = serde_json::from_str(payload).map_err(|_| ErrorCode::InvalidPayload)?;
+        check::<T>(&value)?;
+        serde_json::from_value(value).map_err(|_| ErrorCode::InvalidPayload)
+    }
+}
+
+/// Validate only the version and `operation` tag of a payload and return
+/// the tag, for hosts that record operations without interpreting them.
+pub fn operation<T: OperationSet>(payload: &str) -> Result<String, ErrorCode> {
+    let value: Value = serde_json::from_str(payload).map_err(|_| ErrorCode::InvalidPayload)?;
+    check::<T>(&value).map(str::to_string)
+}
+
+fn check<T: OperationSet>(value: &Value) -> Result<&str, ErrorCode> {
+    let version = match value.get("version") {
+        None => first_version(),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or(ErrorCode::InvalidPayload)?,
+    };
+    if version == 0 || version > VERSION {
+        return Err(ErrorCode::UnsupportedVersion);
+    }
+
+    let operation = value
+        .get("operation")
+        .and_then(Value::as_str)
+        .ok_or(ErrorCode::InvalidPayload)?;
+    if T::OPERATIONS.contains(&operation) {
+        Ok(operation)
+    } else {
+        Err(ErrorCode::UnknownOperation)
+    }
+}
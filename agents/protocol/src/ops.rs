@@ -0,0 +1,1248 @@
+//! Operation payloads and their results.
+
+use std::collections::HashSet;
+
+use crate::{Mutation, OperationSet, Query};
+
+/// Declare an operation set: the tagged enum, its `OperationSet` impl, and
+/// `From`/`Query`/`Mutation` impls for each payload struct.
+macro_rules! operations {
+    ($(#[$meta:meta])* $set:ident: $kind:ident {
+        $($name:literal => $op:ident -> $output:ty,)*
+    }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[serde(tag = "operation")]
+        pub enum $set {
+            $(#[serde(rename = $name)] $op($op),)*
+        }
+
+        impl OperationSet for $set {
+            const OPERATIONS: &'static [&'static str] = &[$($name),*];
+        }
+
+        $(
+            impl From<$op> for $set {
+                fn from(op: $op) -> Self {
+                    $set::$op(op)
+                }
+            }
+
+            impl $kind for $op {
+                type Output = $output;
+            }
+        )*
+    };
+}
+
+operations! {
+    /// Operations served by `graph_read`.
+    GraphRead: Query {
+        "get_mastery" => GetMastery -> MasteryState,
+        "due_reviews" => DueReviews -> Vec<DueReview>,
+        "get_child_progress" => GetChildProgress -> ChildProgress,
+        "get_class_progress" => GetClassProgress -> Vec<ChildProgress>,
+        "is_guardian" => IsGuardian -> Guardianship,
+        "get_concept" => GetConcept -> Option<ConceptInfo>,
+        "teaches_class" => TeachesClass -> Teaching,
+        "list_lessons" => ListLessons -> Vec<LessonInfo>,
+        "list_roster" => ListRoster -> Roster,
+        "get_assignment" => GetAssignment -> AssignmentInfo,
+        "get_submission" => GetSubmission -> SubmissionInfo,
+        "list_responses" => ListResponses -> Vec<ItemResponse>,
+        "list_concept_links" => ListConceptLinks -> ConceptMap,
+        "list_followed_links" => ListFollowedLinks -> Vec<FollowedLink>,
+        "activity_history" => ActivityHistory -> ActivityPage,
+        "list_goals" => ListGoals -> Vec<GoalProgress>,
+        "list_questions" => ListQuestions -> Vec<QuestionInfo>,
+        "get_topic" => GetTopic -> Option<TopicManifest>,
+    }
+}
+
+operations! {
+    /// Operations served by `graph_write`.
+    GraphWrite: Mutation {
+        "create_lesson" => CreateLesson -> LessonCreated,
+        "update_lesson" => UpdateLesson -> LessonInfo,
+        "publish_lesson" => PublishLesson -> LessonInfo,
+        "schedule_lesson" => ScheduleLesson -> LessonInfo,
+        "archive_lesson" => ArchiveLesson -> LessonInfo,
+        "create_class" => CreateClass -> ClassInfo,
+        "enroll_student" => EnrollStudent -> RosterEntry,
+        "remove_student" => RemoveStudent -> RosterEntry,
+        "transfer_student" => TransferStudent -> RosterEntry,
+        "create_assignment" => CreateAssignment -> AssignmentInfo,
+        "submit" => Submit -> SubmissionInfo,
+        "record_grade" => RecordGrade -> SubmissionInfo,
+        "calibrate_assignment" => CalibrateAssignment -> AssignmentInfo,
+        "update_mastery" => UpdateMastery -> MasteryUpdated,
+        "set_concept_model" => SetConceptModel -> ConceptInfo,
+        "link_concepts" => LinkConcepts -> ConceptLink,
+        "follow_link" => FollowLink -> FollowedLink,
+        "set_goal" => SetGoal -> Goal,
+        "add_question" => AddQuestion -> QuestionInfo,
+        "install_topic" => InstallTopic -> TopicInstalled,
+        "record_virtues" => RecordVirtues -> VirtuesRecorded,
+    }
+}
+
+/// Look up a Concept by id or label.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetConcept {
+    pub concept: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConceptInfo {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Knowledge-tracing parameters, when the concept has its own.
+    #[serde(default)]
+    pub bkt: Option<BktParams>,
+}
+
+/// Bayesian Knowledge Tracing parameters, stored on the Concept node as
+/// `bkt_prior`, `bkt_learn`, `bkt_slip` and `bkt_guess`.
+///
+/// All are probabilities, and `slip + guess` must stay below 1 so a correct
+/// answer is evidence of mastery.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BktParams {
+    /// Chance the concept is known before any practice.
+    pub prior: f64,
+    /// Chance of learning it at each practice opportunity.
+    pub learn: f64,
+    /// Chance of answering wrongly despite knowing it.
+    pub slip: f64,
+    /// Chance of answering correctly without knowing it.
+    pub guess: f64,
+}
+
+impl Default for BktParams {
+    fn default() -> Self {
+        BktParams {
+            prior: 0.2,
+            learn: 0.15,
+            slip: 0.1,
+            guess: 0.2,
+        }
+    }
+}
+
+/// How one Concept relates to another: the `fot:linkType` of a
+/// `fot:DomainLink`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkType {
+    Analogy,
+    /// The source must be understood before the target.
+    Prerequisite,
+    /// Understanding the target builds on the source, so the source comes
+    /// first as with [`LinkType::Prerequisite`].
+    Entails,
+    Contradicts,
+}
+
+/// A `LINKS` relationship between Concepts, stored with its `link_type` and
+/// `comment`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConceptLink {
+    /// Concept ids.
+    pub source: String,
+    pub target: String,
+    pub link_type: LinkType,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Link two Concepts, replacing the comment of an existing link of the same
+/// type. Teachers and admins only.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LinkConcepts {
+    pub source: String,
+    pub target: String,
+    pub link_type: LinkType,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Concept links of the given types, or of every type when `link_types` is
+/// empty, with the Concepts at either end.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListConceptLinks {
+    #[serde(default)]
+    pub link_types: Vec<LinkType>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConceptMap {
+    /// By id.
+    pub concepts: Vec<ConceptInfo>,
+    pub links: Vec<ConceptLink>,
+}
+
+/// Record that the caller went from `source` to `target` along a link
+/// between them, in either direction.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FollowLink {
+    pub source: String,
+    pub target: String,
+    pub timestamp: u64,
+}
+
+/// A followed link with the student's mastery of both ends at the time, kept
+/// as a `FOLLOWED` relationship from the student to the target Concept.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FollowedLink {
+    /// Concept ids.
+    pub source: String,
+    pub target: String,
+    pub link_type: LinkType,
+    pub followed_at: u64,
+    pub source_mastery: f64,
+    pub target_mastery_before: f64,
+    /// Current mastery of the target; its growth since the link was followed
+    /// measures transfer.
+    pub target_mastery: f64,
+}
+
+/// Links a student has followed, oldest first.
+///
+/// `student_id` defaults to the caller, with the same access as
+/// [`GetMastery`].
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListFollowedLinks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+}
+
+/// Store knowledge-tracing parameters on a Concept. Teachers and admins only.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SetConceptModel {
+    pub concept: String,
+    pub bkt: BktParams,
+}
+
+/// Whether the caller `TEACHES` `class_id`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TeachesClass {
+    pub class_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Teaching {
+    pub teaches: bool,
+}
+
+/// Create a Lesson node for `concept` in `class_id`.
+///
+/// The caller must teach the class and the concept must exist.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CreateLesson {
+    pub concept: String,
+    pub class_id: String,
+    pub content: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LessonCreated {
+    pub id: String,
+}
+
+/// Edit a lesson. The current content is kept in its history under the
+/// current version and the version number goes up, so what students saw
+/// before the edit can still be looked up.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UpdateLesson {
+    pub lesson_id: String,
+    #[serde(default)]
+    pub concept: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Make a draft lesson visible to students.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PublishLesson {
+    pub lesson_id: String,
+    pub timestamp: u64,
+}
+
+/// Set or clear the window in which students can open a lesson.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScheduleLesson {
+    pub lesson_id: String,
+    #[serde(default)]
+    pub opens_at: Option<u64>,
+    #[serde(default)]
+    pub closes_at: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// Retire a lesson. Archived lessons can no longer be edited or published.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveLesson {
+    pub lesson_id: String,
+    pub timestamp: u64,
+}
+
+/// Lessons of `class_id`, optionally only those in `status`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListLessons {
+    pub class_id: String,
+    #[serde(default)]
+    pub status: Option<LessonStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LessonStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LessonInfo {
+    pub id: String,
+    pub class_id: String,
+    pub concept: String,
+    pub content: String,
+    pub status: LessonStatus,
+    /// Starts at 1 and goes up with every edit.
+    pub version: u32,
+    #[serde(default)]
+    pub opens_at: Option<u64>,
+    #[serde(default)]
+    pub closes_at: Option<u64>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// Earlier versions, oldest first.
+    #[serde(default)]
+    pub history: Vec<LessonVersion>,
+}
+
+/// A lesson as it was before an edit.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LessonVersion {
+    pub version: u32,
+    pub concept: String,
+    pub content: String,
+    /// When this version was replaced.
+    pub replaced_at: u64,
+}
+
+/// Create a Class taught by the caller. The id is derived from the name the
+/// same way `fot-cli create-class` does: `class_` plus the lowercased name
+/// with spaces replaced by underscores.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CreateClass {
+    pub name: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ClassInfo {
+    pub id: String,
+    pub name: String,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+/// Put a student in `class_id`, creating the student's Agent node if needed.
+///
+/// A student belongs to one class at a time (the Agent `class_id`
+/// property); moving between classes is `transfer_student`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EnrollStudent {
+    pub class_id: String,
+    pub student_id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RemoveStudent {
+    pub class_id: String,
+    pub student_id: String,
+}
+
+/// Move a student between two classes the caller teaches.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TransferStudent {
+    pub student_id: String,
+    pub from_class_id: String,
+    pub to_class_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RosterEntry {
+    pub student_id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub class_id: String,
+    pub enrolled_at: u64,
+}
+
+/// The students of `class_id`, looked up by `(role, class_id)`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListRoster {
+    pub class_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Roster {
+    pub class: ClassInfo,
+    pub students: Vec<RosterEntry>,
+}
+
+/// Set work for `class_id`, due at `due_at`.
+///
+/// The caller must teach the class and the concept must exist.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CreateAssignment {
+    pub class_id: String,
+    pub concept: String,
+    pub title: String,
+    /// The lesson the assignment follows, if any.
+    #[serde(default)]
+    pub lesson_id: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub due_at: u64,
+    pub late_policy: LatePolicy,
+    pub timestamp: u64,
+}
+
+/// How work handed in after the due date is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct LatePolicy {
+    /// Fraction of the score lost per started day late, in `[0, 1]`.
+    #[serde(default)]
+    pub penalty_per_day: f64,
+    /// Refuse submissions more than this many days late; `None` accepts
+    /// them at any time.
+    #[serde(default)]
+    pub accept_days_late: Option<u32>,
+}
+
+impl LatePolicy {
+    /// The fraction of the score kept for work `days_late` days late.
+    pub fn retained(&self, days_late: u32) -> f64 {
+        (1.0 - self.penalty_per_day * f64::from(days_late)).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AssignmentInfo {
+    pub id: String,
+    pub class_id: String,
+    pub concept: String,
+    pub title: String,
+    #[serde(default)]
+    pub lesson_id: Option<String>,
+    /// Version of the lesson when the assignment was set.
+    #[serde(default)]
+    pub lesson_version: Option<u32>,
+    pub due_at: u64,
+    pub late_policy: LatePolicy,
+    pub created_by: String,
+    pub created_at: u64,
+    /// How demanding the work is, estimated from graded submissions by the
+    /// teacher agent's `calibrate_items`. Grading uses it once there is one.
+    #[serde(default)]
+    pub calibration: Option<ItemCalibration>,
+}
+
+/// Item Response Theory parameters estimated from graded submissions.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ItemCalibration {
+    pub model: IrtModel,
+    /// The ability, on the logit scale, at which a student is as likely to
+    /// succeed as not.
+    pub difficulty: f64,
+    /// How sharply success depends on ability, greater than 0. Always 1
+    /// under [`IrtModel::OnePl`].
+    pub discrimination: f64,
+    /// Graded submissions the estimate is based on.
+    pub responses: usize,
+    pub calibrated_at: u64,
+}
+
+/// Which item parameters calibration estimates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum IrtModel {
+    /// Rasch: difficulty only.
+    #[default]
+    #[serde(rename = "1pl")]
+    OnePl,
+    /// Difficulty and discrimination.
+    #[serde(rename = "2pl")]
+    TwoPl,
+}
+
+/// Graded submissions for the assignments of `class_id`, for its teachers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListResponses {
+    pub class_id: String,
+}
+
+/// One student's graded work on one assignment.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ItemResponse {
+    pub assignment_id: String,
+    pub student_id: String,
+    /// The raw score in `[0, 1]`; late penalties say nothing about ability.
+    pub score: f64,
+}
+
+/// Store calibrated parameters on an assignment. Teachers of its class only.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CalibrateAssignment {
+    pub assignment_id: String,
+    pub calibration: ItemCalibration,
+}
+
+/// Readable by the teachers of the assignment's class and its students.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetAssignment {
+    pub assignment_id: String,
+}
+
+/// Hand in the caller's work for an assignment of their class.
+///
+/// Handing in again replaces an ungraded submission; graded submissions are
+/// final.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Submit {
+    pub assignment_id: String,
+    pub content: String,
+    /// Seconds the student spent on the work.
+    pub time_spent: u64,
+    pub timestamp: u64,
+}
+
+/// Readable by the student who handed it in and the class's teachers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetSubmission {
+    pub submission_id: String,
+}
+
+/// Grade a stored submission. The late policy of its assignment is applied
+/// to `raw_score` by the graph, so every grader penalises the same way.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordGrade {
+    pub submission_id: String,
+    /// In `[0, 1]`, before any late penalty.
+    pub raw_score: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SubmissionInfo {
+    pub id: String,
+    pub assignment_id: String,
+    pub student_id: String,
+    pub content: String,
+    pub time_spent: u64,
+    pub submitted_at: u64,
+    /// Started days past the due date; 0 when on time.
+    pub days_late: u32,
+    /// Version of the assignment's lesson the work was done against.
+    #[serde(default)]
+    pub lesson_version: Option<u32>,
+    #[serde(default)]
+    pub grade: Option<Grade>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Grade {
+    pub raw_score: f64,
+    /// `raw_score` after the late penalty.
+    pub score: f64,
+    pub graded_at: u64,
+}
+
+/// Current mastery of `concept`.
+///
+/// `student_id` defaults to the caller; reading another student's mastery
+/// needs an admin or a teacher of the student's class.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetMastery {
+    pub concept: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+}
+
+/// Mastery as stored, before any decay is applied.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MasteryState {
+    pub concept_id: String,
+    /// 0 when the caller has not learned the concept yet.
+    pub mastery: f64,
+    /// Seconds since the Unix epoch of the last update, if any.
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+    #[serde(default)]
+    pub review: Option<ReviewSchedule>,
+    /// Number of updates so far; pass it back as
+    /// [`UpdateMastery::if_revision`] to build on this value.
+    #[serde(default)]
+    pub revision: u64,
+}
+
+/// When a learned concept should next be reviewed, kept on its `LEARNS`
+/// relationship.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReviewSchedule {
+    /// Days from the last review to the next.
+    pub interval_days: u32,
+    /// How quickly the interval grows after successful reviews.
+    pub ease: f64,
+    /// Successful reviews in a row.
+    pub repetitions: u32,
+    /// Seconds since the Unix epoch.
+    pub due_at: u64,
+}
+
+/// The caller's concepts due for review by `until`, soonest due first.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DueReviews {
+    /// Seconds since the Unix epoch.
+    pub until: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DueReview {
+    pub concept_id: String,
+    pub concept_name: String,
+    /// As stored, before any decay is applied.
+    pub mastery: f64,
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+    pub review: ReviewSchedule,
+}
+
+/// Set a student's mastery of `concept`, recording it in the history.
+///
+/// Values outside `[0, 1]` break the `learns_unit_range` constraint. To
+/// build on the current value, read it with [`GetMastery`] and pass its
+/// `revision` as `if_revision`, so a concurrent update is not overwritten.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UpdateMastery {
+    pub concept: String,
+    /// Defaults to the caller; another student's mastery needs an admin or a
+    /// teacher of their class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+    pub mastery: f64,
+    pub truth_field_strength: f64,
+    pub harmonic_coherence: f64,
+    pub context: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Replaces the review schedule when given; `None` keeps the stored one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewSchedule>,
+    /// Fail with `InvalidState` unless the stored revision still equals
+    /// this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_revision: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MasteryUpdated {
+    pub concept_id: String,
+    pub mastery: f64,
+}
+
+/// Mastery and virtues recorded for `child_id`.
+///
+/// Only the child, a guardian of the child or an admin may read it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetChildProgress {
+    pub child_id: String,
+    /// Only return mastery history from this time on (seconds since the
+    /// Unix epoch); `None` returns all of it.
+    #[serde(default)]
+    pub since: Option<u64>,
+}
+
+/// [`ChildProgress`] for every student enrolled in `class_id`.
+///
+/// Only teachers of the class and admins may read it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetClassProgress {
+    pub class_id: String,
+    #[serde(default)]
+    pub since: Option<u64>,
+}
+
+/// Whether the caller is a guardian of `child_id`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IsGuardian {
+    pub child_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Guardianship {
+    pub guardian: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChildProgress {
+    pub child_id: String,
+    pub concepts: Vec<ConceptProgress>,
+    pub virtues: Virtues,
+}
+
+/// One `LEARNS` relationship.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConceptProgress {
+    pub concept_id: String,
+    pub concept_name: String,
+    pub mastery: f64,
+    /// Seconds since the Unix epoch of the last mastery update.
+    #[serde(default)]
+    pub last_updated: Option<u64>,
+    #[serde(default)]
+    pub curiosity: Option<f64>,
+    #[serde(default)]
+    pub patience: Option<f64>,
+    #[serde(default)]
+    pub honesty: Option<f64>,
+    /// Mastery as of `since`, when there was any before it.
+    #[serde(default)]
+    pub baseline: Option<f64>,
+    /// Mastery after each update in the requested window, oldest first.
+    #[serde(default)]
+    pub history: Vec<MasterySample>,
+    #[serde(default)]
+    pub review: Option<ReviewSchedule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MasterySample {
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    pub mastery: f64,
+}
+
+/// A virtue delta as kept on the `LEARNS` relationship.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VirtueSample {
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    #[serde(flatten)]
+    pub change: VirtueChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Virtues {
+    pub honesty: f64,
+    pub curiosity: f64,
+    pub patience: f64,
+}
+
+/// A student's recorded activity, newest first, a page at a time.
+///
+/// `student_id` defaults to the caller, with the same access as
+/// [`GetMastery`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ActivityHistory {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+    /// Activities to skip.
+    #[serde(default)]
+    pub offset: usize,
+    /// Greater than 0.
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ActivityPage {
+    pub activities: Vec<Activity>,
+    /// Activities across all pages.
+    pub total: usize,
+    /// Where the next page starts, if there is one.
+    #[serde(default)]
+    pub next_offset: Option<usize>,
+}
+
+/// One thing a student did, at `at` seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Activity {
+    Mastery {
+        at: u64,
+        concept_id: String,
+        mastery: f64,
+    },
+    Submitted {
+        at: u64,
+        submission_id: String,
+        assignment_id: String,
+        days_late: u32,
+    },
+    Graded {
+        at: u64,
+        submission_id: String,
+        assignment_id: String,
+        score: f64,
+    },
+    FollowedLink {
+        at: u64,
+        source: String,
+        target: String,
+        link_type: LinkType,
+    },
+    GoalSet {
+        at: u64,
+        concept_id: String,
+        target_mastery: f64,
+    },
+    Virtue {
+        at: u64,
+        concept_id: String,
+        #[serde(flatten)]
+        change: VirtueChange,
+    },
+}
+
+impl Activity {
+    pub fn at(&self) -> u64 {
+        match self {
+            Activity::Mastery { at, .. }
+            | Activity::Submitted { at, .. }
+            | Activity::Graded { at, .. }
+            | Activity::FollowedLink { at, .. }
+            | Activity::GoalSet { at, .. }
+            | Activity::Virtue { at, .. } => *at,
+        }
+    }
+}
+
+/// Aim for `target_mastery` of `concept` by `due_at`, replacing the caller's
+/// goal for it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SetGoal {
+    pub concept: String,
+    /// In `(0, 1]`.
+    pub target_mastery: f64,
+    /// Seconds since the Unix epoch, after `timestamp`.
+    pub due_at: u64,
+    pub timestamp: u64,
+}
+
+/// Kept as a `GOAL` relationship from the student to the Concept.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Goal {
+    pub concept_id: String,
+    pub concept_name: String,
+    pub target_mastery: f64,
+    pub due_at: u64,
+    pub set_at: u64,
+    /// Mastery when the goal was set, after decay.
+    pub starting_mastery: f64,
+}
+
+/// A student's goals, soonest due first, with their current mastery.
+///
+/// `student_id` defaults to the caller, with the same access as
+/// [`GetMastery`].
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListGoals {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: Goal,
+    /// Stored, before any decay is applied.
+    pub mastery: f64,
+    /// Of the stored mastery, to decay it with.
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+    #[serde(default)]
+    pub review: Option<ReviewSchedule>,
+}
+
+/// Add a question to the bank of `concept`. Teachers and admins only.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AddQuestion {
+    pub concept: String,
+    pub prompt: String,
+    pub item: QuestionItem,
+    pub timestamp: u64,
+}
+
+/// The question bank of `concept`, oldest first.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListQuestions {
+    pub concept: String,
+}
+
+/// A Question node, which a Concept `HAS_QUESTION`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct QuestionInfo {
+    pub id: String,
+    pub concept_id: String,
+    pub prompt: String,
+    /// The kind of question with its answer key.
+    pub item: QuestionItem,
+    pub created_at: u64,
+}
+
+/// A question and its answer key.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestionItem {
+    MultipleChoice {
+        /// At least two.
+        choices: Vec<String>,
+        /// Index of the correct choice.
+        answer: usize,
+    },
+    Numeric {
+        answer: f64,
+        /// Largest accepted absolute difference from `answer`.
+        tolerance: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
+    },
+    Ordering {
+        /// At least two distinct items, in the correct order.
+        items: Vec<String>,
+    },
+    ShortAnswer {
+        rubric: Vec<RubricKeyword>,
+        /// Share of the rubric's weight an answer needs to count as correct,
+        /// in `(0, 1]`.
+        pass_score: f64,
+    },
+}
+
+impl QuestionItem {
+    /// Whether the answer key can be met and every number is usable.
+    pub fn is_well_formed(&self) -> bool {
+        match self {
+            QuestionItem::MultipleChoice { choices, answer } => {
+                choices.len() >= 2 && *answer < choices.len()
+            }
+            QuestionItem::Numeric {
+                answer, tolerance, ..
+            } => answer.is_finite() && tolerance.is_finite() && *tolerance >= 0.0,
+            QuestionItem::Ordering { items } => {
+                let distinct: HashSet<&String> = items.iter().collect();
+                items.len() >= 2 && distinct.len() == items.len()
+            }
+            QuestionItem::ShortAnswer { rubric, pass_score } => {
+                !rubric.is_empty()
+                    && rubric.iter().all(|k| {
+                        !k.keyword.trim().is_empty() && k.weight > 0.0 && k.weight.is_finite()
+                    })
+                    && *pass_score > 0.0
+                    && *pass_score <= 1.0
+            }
+        }
+    }
+}
+
+/// A keyword a short answer earns `weight` for mentioning, in any case.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RubricKeyword {
+    pub keyword: String,
+    /// Other spellings or words that earn the same weight.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    /// Greater than 0.
+    pub weight: f64,
+}
+
+/// A student's answer, of the same `type` as the question.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestionAnswer {
+    MultipleChoice { choice: usize },
+    Numeric { value: f64 },
+    Ordering { order: Vec<String> },
+    ShortAnswer { text: String },
+}
+
+/// Put a topic into the graph: its Concepts, question bank and concept
+/// links, and the manifest itself on a Topic node. Teachers and admins only.
+///
+/// Installing again updates the topic in place and drops questions the
+/// manifest no longer lists. Only the topic that first installed a concept
+/// may change its label, domain or parameters; `Forbidden` otherwise.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InstallTopic {
+    pub manifest: TopicManifest,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopicInstalled {
+    pub topic_id: String,
+    pub concepts: Vec<String>,
+    /// Ids of the Question nodes, `<topic id>:<question id>`.
+    pub questions: Vec<String>,
+    /// Links this install added, including ones earlier topics were waiting
+    /// on for its concepts.
+    pub links: Vec<ConceptLink>,
+    /// Links to concepts not in the graph yet; they are added when a topic
+    /// brings those concepts.
+    #[serde(default)]
+    pub pending_links: Vec<LinkConcepts>,
+}
+
+/// The manifest of an installed topic, if there is one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GetTopic {
+    pub topic_id: String,
+}
+
+/// Everything a topic agent teaches, so a subject can be added as data
+/// rather than code.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopicManifest {
+    /// `<domain>.<topic>`, e.g. `chemistry.carbon_cycle`.
+    pub id: String,
+    pub title: String,
+    /// The first is the one the topic teaches by default.
+    pub concepts: Vec<TopicConcept>,
+    #[serde(default)]
+    pub lessons: Vec<TopicLesson>,
+    #[serde(default)]
+    pub questions: Vec<TopicQuestion>,
+    #[serde(default)]
+    pub virtues: VirtueRules,
+    /// Links from this topic's concepts into other domains.
+    #[serde(default)]
+    pub links: Vec<LinkConcepts>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopicConcept {
+    /// Node id, e.g. `ex:Carbon_Cycle`.
+    pub id: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bkt: Option<BktParams>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopicLesson {
+    pub id: String,
+    /// Id or label of one of the topic's concepts.
+    pub concept: String,
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopicQuestion {
+    /// Unique within the topic.
+    pub id: String,
+    /// Id or label of one of the topic's concepts.
+    pub concept: String,
+    pub prompt: String,
+    pub item: QuestionItem,
+}
+
+/// How a topic agent turns what a student does into virtue deltas.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VirtueRules {
+    /// Every rule whose condition holds adds its delta.
+    pub rules: Vec<VirtueRule>,
+}
+
+impl Default for VirtueRules {
+    fn default() -> Self {
+        let rule = |virtue, signal, delta, explanation: &str| VirtueRule {
+            virtue,
+            signal,
+            at_least: None,
+            below: None,
+            delta,
+            per_unit: false,
+            max: None,
+            explanation: Some(explanation.to_string()),
+        };
+        VirtueRules {
+            rules: vec![
+                rule(
+                    Virtue::Curiosity,
+                    Signal::LessonStarted,
+                    0.1,
+                    "Started a lesson",
+                ),
+                VirtueRule {
+                    at_least: Some(300.0),
+                    ..rule(
+                        Virtue::Patience,
+                        Signal::TimeOnTaskSecs,
+                        0.15,
+                        "Stayed with the task for {value} seconds",
+                    )
+                },
+                VirtueRule {
+                    at_least: Some(1.0),
+                    below: Some(300.0),
+                    ..rule(
+                        Virtue::Patience,
+                        Signal::TimeOnTaskSecs,
+                        0.05,
+                        "Spent {value} seconds on the task",
+                    )
+                },
+                VirtueRule {
+                    per_unit: true,
+                    max: Some(0.15),
+                    ..rule(
+                        Virtue::Patience,
+                        Signal::Retries,
+                        0.05,
+                        "Tried again {value} times rather than giving up",
+                    )
+                },
+                VirtueRule {
+                    per_unit: true,
+                    max: Some(0.3),
+                    ..rule(
+                        Virtue::Honesty,
+                        Signal::SelfReportedMistakes,
+                        0.1,
+                        "Owned up to {value} mistakes",
+                    )
+                },
+                rule(
+                    Virtue::Honesty,
+                    Signal::HintsUsed,
+                    0.05,
+                    "Asked for {value} hints rather than guessing",
+                ),
+            ],
+        }
+    }
+}
+
+/// Adds `delta` to `virtue` when `signal` was observed within
+/// `[at_least, below)`; with neither bound, when it was above 0.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VirtueRule {
+    pub virtue: Virtue,
+    pub signal: Signal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_least: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<f64>,
+    /// May be negative.
+    pub delta: f64,
+    /// Scale `delta` by the signal's value.
+    #[serde(default)]
+    pub per_unit: bool,
+    /// Largest size of the scaled delta, when `per_unit` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Shown with the delta; `{value}` is replaced by the signal's value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Virtue {
+    Curiosity,
+    Patience,
+    Honesty,
+}
+
+impl Virtue {
+    /// The name `record_virtue` takes.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Virtue::Curiosity => "curiosity",
+            Virtue::Patience => "patience",
+            Virtue::Honesty => "honesty",
+        }
+    }
+}
+
+/// Observable behaviour a [`VirtueRule`] responds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    /// 1 when a lesson starts.
+    LessonStarted,
+    /// 1 when a submission is graded.
+    WorkGraded,
+    /// 1 when quiz answers are checked.
+    QuizAnswered,
+    /// Seconds spent on the work or quiz.
+    TimeOnTaskSecs,
+    HintsUsed,
+    /// Answers given again after a wrong one.
+    Retries,
+    /// Mistakes the student pointed out themselves.
+    SelfReportedMistakes,
+}
+
+/// Net deltas by virtue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VirtueDeltas {
+    pub curiosity: f64,
+    pub patience: f64,
+    pub honesty: f64,
+}
+
+/// Add virtue deltas to a student's `LEARNS` relationship with `concept`,
+/// keeping each delta with its explanation.
+///
+/// Each virtue's net delta is added to its stored value, or to 0, and the
+/// result clamped to `[0, 1]`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordVirtues {
+    pub concept: String,
+    /// Defaults to the caller; another student's virtues need an admin or a
+    /// teacher of their class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+    /// At least one, each with a finite delta.
+    pub changes: Vec<VirtueChange>,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// A virtue delta and why it was recorded.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VirtueChange {
+    pub virtue: Virtue,
+    pub delta: f64,
+    pub explanation: String,
+}
+
+/// The virtues stored on the `LEARNS` relationship afterwards.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VirtuesRecorded {
+    pub concept_id: String,
+    #[serde(default)]
+    pub curiosity: Option<f64>,
+    #[serde(default)]
+    pub patience: Option<f64>,
+    #[serde(default)]
+    pub honesty: Option<f64>,
+}
@@ -0,0 +1,105 @@
+//! Buffer ABI shared by every agent module.
+//!
+//! The host drives an agent through three exports generated by
+//! [`export_agent!`](crate::export_agent):
+//!
+//! - `alloc(len) -> ptr` reserves `len` bytes in agent memory;
+//! - `run(ptr, len) -> reply` processes the JSON command stored at `ptr`;
+//! - `dealloc(ptr, len)` releases a buffer obtained from `alloc` or `run`.
+//!
+//! A reply is length-prefixed: a little-endian `u32` payload length followed
+//! by that many bytes of UTF-8 JSON. The whole reply spans
+//! `LEN_PREFIX + payload_len` bytes and is freed with that size. Input
+//! buffers stay owned by the host, which frees them once `run` returns.
+
+use std::alloc::Layout;
+use std::ptr::NonNull;
+
+/// Size of the little-endian `u32` length header in front of every reply.
+pub const LEN_PREFIX: usize = 4;
+
+fn layout(len: usize) -> Layout {
+    Layout::array::<u8>(len).expect("buffer length overflows the address space")
+}
+
+/// Allocate `len` bytes with the layout [`dealloc`] expects.
+///
+/// Zero-length requests return a dangling, non-null pointer that must not be
+/// dereferenced.
+pub fn alloc(len: usize) -> *mut u8 {
+    if len == 0 {
+        return NonNull::dangling().as_ptr();
+    }
+    let layout = layout(len);
+    // SAFETY: `layout` has a non-zero size.
+    let ptr = unsafe { std::alloc::alloc(layout) };
+    if ptr.is_null() {
+        std::alloc::handle_alloc_error(layout);
+    }
+    ptr
+}
+
+/// Release a buffer of `len` bytes obtained from [`alloc`] or [`into_raw`].
+///
+/// # Safety
+///
+/// `ptr` must come from [`alloc`] (or [`into_raw`]) with the same `len` and
+/// must not be used afterwards.
+pub unsafe fn dealloc(ptr: *mut u8, len: usize) {
+    if len == 0 || ptr.is_null() {
+        return;
+    }
+    std::alloc::dealloc(ptr, layout(len));
+}
+
+/// Borrow the host-provided input buffer.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes that outlive the returned slice.
+pub unsafe fn input<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        return &[];
+    }
+    std::slice::from_raw_parts(ptr, len)
+}
+
+/// Hand a reply to the host as a length-prefixed buffer.
+pub fn into_raw(reply: String) -> *mut u8 {
+    let payload = reply.into_bytes();
+    let header = u32::try_from(payload.len())
+        .expect("reply exceeds u32::MAX bytes")
+        .to_le_bytes();
+
+    let ptr = alloc(LEN_PREFIX + payload.len());
+    // SAFETY: `ptr` points to `LEN_PREFIX + payload.len()` freshly allocated bytes.
+    unsafe {
+        std::ptr::copy_nonoverlapping(header.as_ptr(), ptr, LEN_PREFIX);
+        std::ptr::copy_nonoverlapping(payload.as_ptr(), ptr.add(LEN_PREFIX), payload.len());
+    }
+    ptr
+}
+
+/// Payload length recorded in a reply header.
+///
+/// # Safety
+///
+/// `ptr` must point to a reply produced by [`into_raw`].
+pub unsafe fn reply_len(ptr: *const u8) -> usize {
+    let mut header = [0u8; LEN_PREFIX];
+    std::ptr::copy_nonoverlapping(ptr, header.as_mut_ptr(), LEN_PREFIX);
+    u32::from_le_bytes(header) as usize
+}
+
+/// Copy a reply out of agent memory and free it, as a native host would.
+///
+/// # Safety
+///
+/// `ptr` must point to a reply produced by [`into_raw`] that has not been
+/// freed yet.
+pub unsafe fn take_reply(ptr: *mut u8) -> String {
+    let len = reply_len(ptr);
+    let payload = std::slice::from_raw_parts(ptr.add(LEN_PREFIX), len).to_vec();
+    dealloc(ptr, LEN_PREFIX + len);
+    String::from_utf8(payload).expect("replies are UTF-8 JSON")
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use fot_protocol::ErrorCode;
+
+/// Structured error returned to the host as `{"success": false, "error": {...}}`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgentError {
+    /// The input was not valid JSON or did not match the operation's schema.
+    InvalidInput { message: String },
+    /// The `op` field named an operation this agent does not implement.
+    UnknownOperation { op: String },
+    /// One or more fields of an otherwise well-formed request are invalid.
+    Validation { fields: Vec<FieldError> },
+    /// `auth_ctx` did not identify the caller.
+    Unauthenticated,
+    /// The caller may not perform the operation.
+    Forbidden { message: String },
+    /// A graph import rejected the operation.
+    Graph { import: String, error: ErrorCode },
+    /// A host import returned an error code.
+    Host { import: String, code: u32 },
+    /// The agent failed for a reason outside the caller's control.
+    Internal { message: String },
+}
+
+/// Why a single request field was rejected.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl AgentError {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        AgentError::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    pub fn host(import: &str, code: u32) -> Self {
+        AgentError::Host {
+            import: import.to_string(),
+            code,
+        }
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        AgentError::Forbidden {
+            message: message.into(),
+        }
+    }
+
+    /// A graph import failure; codes outside [`ErrorCode`] stay raw.
+    pub fn graph(import: &str, code: u32) -> Self {
+        match ErrorCode::from_code(code) {
+            Some(ErrorCode::Unauthenticated) => AgentError::Unauthenticated,
+            Some(ErrorCode::Forbidden) => {
+                AgentError::forbidden(format!("`{import}` denied access"))
+            }
+            Some(error) => AgentError::Graph {
+                import: import.to_string(),
+                error,
+            },
+            None => AgentError::host(import, code),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        AgentError::Internal {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AgentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgentError::InvalidInput { message } => write!(f, "invalid input: {message}"),
+            AgentError::UnknownOperation { op } => write!(f, "unknown operation `{op}`"),
+            AgentError::Validation { fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|e| format!("{}: {}", e.field, e.message))
+                    .collect();
+                write!(f, "invalid fields: {}", fields.join(", "))
+            }
+            AgentError::Unauthenticated => write!(f, "caller is not authenticated"),
+            AgentError::Forbidden { message } => write!(f, "forbidden: {message}"),
+            AgentError::Graph { import, error } => write!(f, "`{import}` failed: {error}"),
+            AgentError::Host { import, code } => {
+                write!(f, "host import `{import}` failed with code {code}")
+            }
+            AgentError::Internal { message } => write!(f, "internal error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for AgentError {}
@@ -0,0 +1,275 @@
+//! Host imports as traits.
+//!
+//! Agent logic talks to the graph, events, metrics and files imports through
+//! [`Host`] instead of calling the `fot-*` bindings directly. Inside a wasm
+//! module [`Host::wit`] forwards to the real imports; native builds and tests
+//! plug in other implementations such as the ones in [`crate::memory`].
+
+use fot_protocol::{AuthContext, Envelope, GraphRead, GraphWrite, Mutation, Query, Role};
+use serde::de::DeserializeOwned;
+
+use crate::AgentError;
+
+/// `graph.wit`: knowledge-graph access and caller identity.
+pub trait Graph: Send + Sync {
+    fn graph_read(&self, query: &str) -> Result<String, u32>;
+    fn graph_write(&self, mutation: &str) -> Result<String, u32>;
+    fn begin_txn(&self) -> u64;
+    fn commit_txn(&self, txn: u64) -> Result<(), u32>;
+    fn rollback_txn(&self, txn: u64) -> Result<(), u32>;
+    fn auth_ctx(&self) -> String;
+}
+
+/// `events.wit`: pub/sub, notifications and resonance events.
+pub trait Events: Send + Sync {
+    fn publish(&self, topic: &str, message: &str) -> Result<(), u32>;
+    fn subscribe(&self, topic: &str) -> Result<u64, u32>;
+    fn unsubscribe(&self, subscription: u64) -> Result<(), u32>;
+    fn notify(&self, user_id: &str, message: &str) -> Result<(), u32>;
+    fn emit_resonance(
+        &self,
+        agent_id: &str,
+        frequency: f64,
+        amplitude: f64,
+        context: &str,
+    ) -> Result<(), u32>;
+    fn broadcast_harmonic(&self, resonance_data: &str) -> Result<(), u32>;
+    fn subscribe_resonance(&self, frequency_range: &str) -> Result<u64, u32>;
+    fn measure_collective_resonance(&self, agent_cluster: &str) -> Result<f64, u32>;
+}
+
+/// `metrics.wit`: virtue, usage and resonance measurements.
+pub trait Metrics: Send + Sync {
+    fn record_virtue(&self, user_id: &str, virtue: &str, delta: f64) -> Result<(), u32>;
+    fn record_usage(&self, user_id: &str, action: &str, duration: u64) -> Result<(), u32>;
+    fn get_stats(&self, user_id: &str) -> Result<String, u32>;
+    fn export_metrics(&self) -> Result<String, u32>;
+    fn record_resonance(
+        &self,
+        agent_id: &str,
+        context: &str,
+        resonance_amplitude: f64,
+    ) -> Result<(), u32>;
+    fn measure_harmonic_coherence(&self, agent_ids: &str, context: &str) -> Result<f64, u32>;
+    fn calculate_truth_field(&self, claim: &str, agent_contexts: &str) -> Result<f64, u32>;
+    fn get_resonance_spectrum(&self, domain: &str) -> Result<String, u32>;
+}
+
+/// `files.wit`: datasets, cache and local files.
+pub trait Files: Send + Sync {
+    fn fetch_dataset(&self, url: &str) -> Result<String, u32>;
+    fn cache_get(&self, key: &str) -> Result<String, u32>;
+    fn cache_set(&self, key: &str, value: &str) -> Result<(), u32>;
+    fn open_local(&self, path: &str) -> Result<String, u32>;
+}
+
+/// The set of imports an agent runs against.
+#[derive(Clone, Copy)]
+pub struct Host<'a> {
+    pub graph: &'a dyn Graph,
+    pub events: &'a dyn Events,
+    pub metrics: &'a dyn Metrics,
+    pub files: &'a dyn Files,
+}
+
+impl Host<'_> {
+    /// The caller identity from `auth_ctx`.
+    pub fn auth(&self) -> Result<AuthContext, AgentError> {
+        AuthContext::parse(&self.graph.auth_ctx()).ok_or(AgentError::Unauthenticated)
+    }
+
+    /// The student a request acts for: the caller, or `student_id` when the
+    /// caller is a teacher or admin. The graph checks that a teacher teaches
+    /// the student's class.
+    pub fn acting_for(&self, student_id: Option<String>) -> Result<String, AgentError> {
+        let auth = self.auth()?;
+        match student_id {
+            None => Ok(auth.subject),
+            Some(id) if id == auth.subject => Ok(id),
+            Some(id) if matches!(auth.role, Role::Teacher | Role::Admin) => Ok(id),
+            Some(id) => Err(AgentError::forbidden(format!(
+                "{} cannot act for {id}",
+                auth.subject
+            ))),
+        }
+    }
+
+    /// Send a typed read through `graph_read` and decode its result.
+    pub fn query<Q: Query>(&self, query: Q) -> Result<Q::Output, AgentError> {
+        self.read(Envelope::new(query))
+    }
+
+    /// Like [`Host::query`], but reading transaction `txn`'s staged data.
+    pub fn query_in<Q: Query>(&self, txn: u64, query: Q) -> Result<Q::Output, AgentError> {
+        self.read(Envelope::new(query).in_txn(txn))
+    }
+
+    /// Send a typed write through `graph_write` and decode its result.
+    pub fn mutate<M: Mutation>(&self, mutation: M) -> Result<M::Output, AgentError> {
+        self.write(Envelope::new(mutation))
+    }
+
+    /// Like [`Host::mutate`], but staging the write in transaction `txn`.
+    pub fn mutate_in<M: Mutation>(&self, txn: u64, mutation: M) -> Result<M::Output, AgentError> {
+        self.write(Envelope::new(mutation).in_txn(txn))
+    }
+
+    fn read<T: DeserializeOwned>(&self, query: Envelope<GraphRead>) -> Result<T, AgentError> {
+        let reply = self
+            .graph
+            .graph_read(&query.encode())
+            .map_err(|code| AgentError::graph("graph_read", code))?;
+        decode_reply("graph_read", &reply)
+    }
+
+    fn write<T: DeserializeOwned>(&self, mutation: Envelope<GraphWrite>) -> Result<T, AgentError> {
+        let reply = self
+            .graph
+            .graph_write(&mutation.encode())
+            .map_err(|code| AgentError::graph("graph_write", code))?;
+        decode_reply("graph_write", &reply)
+    }
+}
+
+fn decode_reply<T: DeserializeOwned>(import: &str, reply: &str) -> Result<T, AgentError> {
+    serde_json::from_str(reply)
+        .map_err(|e| AgentError::internal(format!("unexpected `{import}` reply: {e}")))
+}
+
+impl Host<'static> {
+    /// The imports provided by the wasm host this module runs in.
+    pub fn wit() -> Self {
+        Host {
+            graph: &Wit,
+            events: &Wit,
+            metrics: &Wit,
+            files: &Wit,
+        }
+    }
+}
+
+/// Forwards every trait method to the `fot-*` guest bindings.
+pub struct Wit;
+
+impl Graph for Wit {
+    fn graph_read(&self, query: &str) -> Result<String, u32> {
+        fot_graph::graph_read(query)
+    }
+
+    fn graph_write(&self, mutation: &str) -> Result<String, u32> {
+        fot_graph::graph_write(mutation)
+    }
+
+    fn begin_txn(&self) -> u64 {
+        fot_graph::begin_txn()
+    }
+
+    fn commit_txn(&self, txn: u64) -> Result<(), u32> {
+        fot_graph::commit_txn(txn)
+    }
+
+    fn rollback_txn(&self, txn: u64) -> Result<(), u32> {
+        fot_graph::rollback_txn(txn)
+    }
+
+    fn auth_ctx(&self) -> String {
+        fot_graph::auth_ctx()
+    }
+}
+
+impl Events for Wit {
+    fn publish(&self, topic: &str, message: &str) -> Result<(), u32> {
+        fot_events::publish(topic, message)
+    }
+
+    fn subscribe(&self, topic: &str) -> Result<u64, u32> {
+        fot_events::subscribe(topic)
+    }
+
+    fn unsubscribe(&self, subscription: u64) -> Result<(), u32> {
+        fot_events::unsubscribe(subscription)
+    }
+
+    fn notify(&self, user_id: &str, message: &str) -> Result<(), u32> {
+        fot_events::notify(user_id, message)
+    }
+
+    fn emit_resonance(
+        &self,
+        agent_id: &str,
+        frequency: f64,
+        amplitude: f64,
+        context: &str,
+    ) -> Result<(), u32> {
+        fot_events::emit_resonance(agent_id, frequency, amplitude, context)
+    }
+
+    fn broadcast_harmonic(&self, resonance_data: &str) -> Result<(), u32> {
+        fot_events::broadcast_harmonic(resonance_data)
+    }
+
+    fn subscribe_resonance(&self, frequency_range: &str) -> Result<u64, u32> {
+        fot_events::subscribe_resonance(frequency_range)
+    }
+
+    fn measure_collective_resonance(&self, agent_cluster: &str) -> Result<f64, u32> {
+        fot_events::measure_collective_resonance(agent_cluster)
+    }
+}
+
+impl Metrics for Wit {
+    fn record_virtue(&self, user_id: &str, virtue: &str, delta: f64) -> Result<(), u32> {
+        fot_metrics::record_virtue(user_id, virtue, delta)
+    }
+
+    fn record_usage(&self, user_id: &str, action: &str, duration: u64) -> Result<(), u32> {
+        fot_metrics::record_usage(user_id, action, duration)
+    }
+
+    fn get_stats(&self, user_id: &str) -> Result<String, u32> {
+        fot_metrics::get_stats(user_id)
+    }
+
+    fn export_metrics(&self) -> Result<String, u32> {
+        fot_metrics::export_metrics()
+    }
+
+    fn record_resonance(
+        &self,
+        agent_id: &str,
+        context: &str,
+        resonance_amplitude: f64,
+    ) -> Result<(), u32> {
+        fot_metrics::record_resonance(agent_id, context, resonance_amplitude)
+    }
+
+    fn measure_harmonic_coherence(&self, agent_ids: &str, context: &str) -> Result<f64, u32> {
+        fot_metrics::measure_harmonic_coherence(agent_ids, context)
+    }
+
+    fn calculate_truth_field(&self, claim: &str, agent_contexts: &str) -> Result<f64, u32> {
+        fot_metrics::calculate_truth_field(claim, agent_contexts)
+    }
+
+    fn get_resonance_spectrum(&self, domain: &str) -> Result<String, u32> {
+        fot_metrics::get_resonance_spectrum(domain)
+    }
+}
+
+impl Files for Wit {
+    fn fetch_dataset(&self, url: &str) -> Result<String, u32> {
+        fot_files::fetch_dataset(url)
+    }
+
+    fn cache_get(&self, key: &str) -> Result<String, u32> {
+        fot_files::cache_get(key)
+    }
+
+    fn cache_set(&self, key: &str, value: &str) -> Result<(), u32> {
+        fot_files::cache_set(key, value)
+    }
+
+    fn open_local(&self, path: &str) -> Result<String, u32> {
+        fot_files::open_local(path)
+    }
+}
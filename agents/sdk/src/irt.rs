@@ -0,0 +1,144 @@
+//! Item Response Theory: item difficulty and discrimination, and student
+//! ability, estimated from graded responses.
+//!
+//! Calibration is joint maximum a posteriori estimation under the logistic
+//! 1PL or 2PL model. Standard normal priors on ability and difficulty, and on
+//! the log of discrimination, keep the estimates finite when a student or an
+//! item has only successes or only failures. Scores in `[0, 1]` are treated
+//! as fractional successes.
+
+use std::collections::BTreeMap;
+
+use fot_protocol::{BktParams, IrtModel, ItemResponse};
+
+/// Calibration rounds; each takes one Newton step per parameter.
+const ROUNDS: usize = 50;
+
+/// Discrimination is kept within this range.
+const DISCRIMINATION: (f64, f64) = (0.2, 4.0);
+
+/// Abilities that stand for "not mastered" and "mastered" when an item's
+/// curve is turned into knowledge-tracing evidence.
+const UNMASTERED_ABILITY: f64 = -1.0;
+const MASTERED_ABILITY: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemParams {
+    pub difficulty: f64,
+    pub discrimination: f64,
+}
+
+impl Default for ItemParams {
+    fn default() -> Self {
+        ItemParams {
+            difficulty: 0.0,
+            discrimination: 1.0,
+        }
+    }
+}
+
+impl ItemParams {
+    /// Chance that a student of `ability` succeeds on the item.
+    pub fn probability(&self, ability: f64) -> f64 {
+        1.0 / (1.0 + (-self.discrimination * (ability - self.difficulty)).exp())
+    }
+
+    /// `bkt` with slip and guess read off the item's curve, so a correct
+    /// answer to a hard item is stronger evidence than one to an easy item.
+    pub fn tracing_params(&self, bkt: BktParams) -> BktParams {
+        BktParams {
+            slip: 1.0 - self.probability(MASTERED_ABILITY),
+            guess: self.probability(UNMASTERED_ABILITY),
+            ..bkt
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calibration {
+    /// By item id, with the number of responses each is based on.
+    pub items: BTreeMap<String, (ItemParams, usize)>,
+    /// By student id.
+    pub abilities: BTreeMap<String, f64>,
+}
+
+/// Estimate item parameters and abilities from `responses`.
+pub fn calibrate(responses: &[ItemResponse], model: IrtModel) -> Calibration {
+    let mut calibration = Calibration::default();
+    for response in responses {
+        calibration
+            .items
+            .entry(response.assignment_id.clone())
+            .or_insert((ItemParams::default(), 0))
+            .1 += 1;
+        calibration
+            .abilities
+            .insert(response.student_id.clone(), 0.0);
+    }
+
+    for _ in 0..ROUNDS {
+        for (student, ability) in calibration.abilities.iter_mut() {
+            let answered = responses.iter().filter(|r| &r.student_id == student);
+            let items = answered.map(|r| (calibration.items[&r.assignment_id].0, r.score));
+            *ability = ability_step(*ability, items);
+        }
+        for (item, (params, _)) in calibration.items.iter_mut() {
+            let answers: Vec<(f64, f64)> = responses
+                .iter()
+                .filter(|r| &r.assignment_id == item)
+                .map(|r| (calibration.abilities[&r.student_id], r.score))
+                .collect();
+            *params = item_step(*params, &answers, model);
+        }
+    }
+    calibration
+}
+
+/// A student's ability given calibrated items and their scores on them.
+pub fn ability(answers: &[(ItemParams, f64)]) -> f64 {
+    (0..ROUNDS).fold(0.0, |theta, _| ability_step(theta, answers.iter().copied()))
+}
+
+fn ability_step(theta: f64, answers: impl Iterator<Item = (ItemParams, f64)>) -> f64 {
+    // The prior contributes -theta to the gradient and -1 to the curvature.
+    let (mut gradient, mut curvature) = (-theta, -1.0);
+    for (item, score) in answers {
+        let p = item.probability(theta);
+        let a = item.discrimination;
+        gradient += a * (score - p);
+        curvature -= a * a * p * (1.0 - p);
+    }
+    theta - gradient / curvature
+}
+
+fn item_step(item: ItemParams, answers: &[(f64, f64)], model: IrtModel) -> ItemParams {
+    let a = item.discrimination;
+    let (mut gradient, mut curvature) = (-item.difficulty, -1.0);
+    for &(theta, score) in answers {
+        let p = item.probability(theta);
+        gradient -= a * (score - p);
+        curvature -= a * a * p * (1.0 - p);
+    }
+    let difficulty = item.difficulty - gradient / curvature;
+    if model == IrtModel::OnePl {
+        return ItemParams {
+            difficulty,
+            discrimination: 1.0,
+        };
+    }
+
+    // Step on the log of discrimination, which has the normal prior.
+    let log_a = a.ln();
+    let (mut gradient, mut curvature) = (-log_a, -1.0);
+    for &(theta, score) in answers {
+        let p = item.probability(theta);
+        let spread = a * (theta - item.difficulty);
+        gradient += spread * (score - p);
+        curvature -= spread * spread * p * (1.0 - p);
+    }
+    let (low, high) = DISCRIMINATION;
+    ItemParams {
+        difficulty,
+        discrimination: (log_a - gradient / curvature).exp().clamp(low, high),
+    }
+}
@@ -0,0 +1,154 @@
+//! Shared runtime for FoT WASM agents.
+//!
+//! An agent implements [`Agent`] with a typed request enum and response type,
+//! then calls [`export_agent!`] to generate the `alloc`/`run`/`dealloc`
+//! exports the host invokes. Parsing, error reporting and buffer handling
+//! live here so agent crates only contain domain logic.
+//!
+//! Host imports are reached through [`Host`], so the same agent runs against
+//! the real WIT imports in wasm and against [`memory::MemoryHost`] natively.
+//! Graph operations are the typed [`protocol`] values sent with
+//! [`Host::query`] and [`Host::mutate`].
+
+pub mod abi;
+mod error;
+pub mod host;
+pub mod irt;
+pub mod mastery;
+pub mod memory;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+pub use error::{AgentError, FieldError};
+pub use fot_protocol as protocol;
+pub use host::Host;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A role or topic agent driven by JSON commands of the form `{"op": ..., ...}`.
+pub trait Agent: Default {
+    /// Operations accepted by the agent, usually an enum tagged on `op`.
+    type Request: DeserializeOwned;
+    /// Payload returned on success.
+    type Response: Serialize;
+
+    fn handle(&self, host: &Host, request: Self::Request) -> Result<Self::Response, AgentError>;
+}
+
+/// Successful reply: the response fields flattened next to `success: true`.
+#[derive(serde::Serialize)]
+struct Reply<T> {
+    success: bool,
+    #[serde(flatten)]
+    response: T,
+}
+
+/// Failed reply: `success: false` with a structured error.
+#[derive(serde::Serialize)]
+struct Failure<'a> {
+    success: bool,
+    error: &'a AgentError,
+}
+
+/// Parse `input`, run it through `agent` against `host` and serialize the
+/// reply.
+///
+/// Never fails: every error path is turned into a `success: false` reply.
+pub fn dispatch<A: Agent>(agent: &A, host: &Host, input: &[u8]) -> String {
+    match parse_request::<A::Request>(input).and_then(|request| agent.handle(host, request)) {
+        Ok(response) => serde_json::to_string(&Reply {
+            success: true,
+            response,
+        })
+        .unwrap_or_else(|e| failure(&AgentError::internal(e.to_string()))),
+        Err(error) => failure(&error),
+    }
+}
+
+fn failure(error: &AgentError) -> String {
+    serde_json::to_string(&Failure {
+        success: false,
+        error,
+    })
+    .expect("agent errors always serialize")
+}
+
+/// Decode a request, telling an unknown `op` apart from a malformed payload.
+fn parse_request<R: DeserializeOwned>(input: &[u8]) -> Result<R, AgentError> {
+    let value: serde_json::Value =
+        serde_json::from_slice(input).map_err(|e| AgentError::invalid_input(e.to_string()))?;
+    let op = match value.get("op") {
+        Some(serde_json::Value::String(op)) => op.clone(),
+        _ => return Err(AgentError::invalid_input("missing string field `op`")),
+    };
+
+    R::deserialize(&value).map_err(|e| {
+        let message = e.to_string();
+        if message == unknown_op_error::<R>(value, &op) {
+            AgentError::UnknownOperation { op }
+        } else {
+            AgentError::invalid_input(message)
+        }
+    })
+}
+
+/// The error `R` reports when `op` is not one of its operations.
+///
+/// Found by decoding `value` again with an `op` no request can have, so an
+/// unknown variant in a nested field is not mistaken for an unknown `op`.
+fn unknown_op_error<R: DeserializeOwned>(mut value: serde_json::Value, op: &str) -> String {
+    const NO_OP: &str = "\u{0}";
+    value["op"] = serde_json::Value::from(NO_OP);
+    match R::deserialize(&value) {
+        Ok(_) => String::new(),
+        Err(e) => e.to_string().replacen(NO_OP, op, 1),
+    }
+}
+
+/// Generate the wasm exports for an [`Agent`] implementation.
+///
+/// ```ignore
+/// #[derive(Default)]
+/// pub struct StudentAgent;
+/// impl fot_agent_sdk::Agent for StudentAgent { /* ... */ }
+/// fot_agent_sdk::export_agent!(StudentAgent);
+/// ```
+///
+/// This expands to the `alloc`, `run` and `dealloc` exports described in
+/// [`abi`].
+#[macro_export]
+macro_rules! export_agent {
+    ($agent:ty) => {
+        /// Reserve `len` bytes of agent memory for the host to write into.
+        #[no_mangle]
+        pub extern "C" fn alloc(len: usize) -> *mut u8 {
+            $crate::abi::alloc(len)
+        }
+
+        /// Release a buffer obtained from `alloc` or `run`.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` and `len` must describe a live buffer from `alloc` or, for a
+        /// reply, `run` (header included).
+        #[no_mangle]
+        pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+            $crate::abi::dealloc(ptr, len)
+        }
+
+        /// Agent entry point. Returns a length-prefixed JSON reply to be
+        /// released with `dealloc`.
+        ///
+        /// # Safety
+        ///
+        /// `input_ptr` must point to `len` readable bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
+            let input = $crate::abi::input(input_ptr, len);
+            let agent = <$agent as ::core::default::Default>::default();
+            let host = $crate::Host::wit();
+            $crate::abi::into_raw($crate::dispatch(&agent, &host, input))
+        }
+    };
+}
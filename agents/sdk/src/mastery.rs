@@ -0,0 +1,81 @@
+//! Mastery estimation from observed answers.
+//!
+//! Agents turn correct and incorrect answers into a mastery probability
+//! through [`MasteryModel`], so the estimator can change without touching
+//! the agents. [`BktParams`] implements Bayesian Knowledge Tracing. Stored
+//! mastery is read through [`decay`], so every agent forgets the same way.
+
+use fot_protocol::{BktParams, ReviewSchedule};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Mastery left untouched this long has halved.
+pub const MASTERY_HALF_LIFE: u64 = 30 * DAY;
+
+/// Exponential forgetting of `mastery` as stored, up to `now`.
+///
+/// A scheduled concept holds until its review is due and is then forgotten
+/// with a half-life of its review interval, so missed reviews cost more the
+/// newer the material is. Forgetting counts from the later of the due date
+/// and `updated_at`, as the stored mastery already lost what came before.
+/// Otherwise mastery halves every [`MASTERY_HALF_LIFE`] since `updated_at`.
+pub fn decay(
+    mastery: f64,
+    updated_at: Option<u64>,
+    review: Option<ReviewSchedule>,
+    now: u64,
+) -> f64 {
+    let (since, half_life) = match (review, updated_at) {
+        (Some(review), updated_at) => (
+            review.due_at.max(updated_at.unwrap_or(0)),
+            u64::from(review.interval_days.max(1)) * DAY,
+        ),
+        (None, Some(updated_at)) => (updated_at, MASTERY_HALF_LIFE),
+        (None, None) => return mastery,
+    };
+    let elapsed = now.saturating_sub(since) as f64;
+    mastery * 0.5_f64.powf(elapsed / half_life as f64)
+}
+
+/// Estimates the probability that a student has mastered a concept.
+pub trait MasteryModel {
+    /// Mastery before any answer has been observed.
+    fn initial(&self) -> f64;
+
+    /// Mastery after observing one answer, given mastery before it.
+    fn observe(&self, mastery: f64, correct: bool) -> f64;
+
+    /// Like [`observe`](Self::observe) for partial credit: the posteriors
+    /// for a correct and an incorrect answer, weighted by `score` in
+    /// `[0, 1]`.
+    fn observe_score(&self, mastery: f64, score: f64) -> f64 {
+        let score = score.clamp(0.0, 1.0);
+        score * self.observe(mastery, true) + (1.0 - score) * self.observe(mastery, false)
+    }
+}
+
+impl MasteryModel for BktParams {
+    fn initial(&self) -> f64 {
+        self.prior
+    }
+
+    /// Condition on the answer with Bayes' rule, then apply the chance of
+    /// learning from the practice opportunity.
+    fn observe(&self, mastery: f64, correct: bool) -> f64 {
+        let known = mastery.clamp(0.0, 1.0);
+        let unknown = 1.0 - known;
+        let posterior = if correct {
+            let evidence = known * (1.0 - self.slip) + unknown * self.guess;
+            known * (1.0 - self.slip) / evidence
+        } else {
+            let evidence = known * self.slip + unknown * (1.0 - self.guess);
+            known * self.slip / evidence
+        };
+        let posterior = if posterior.is_finite() {
+            posterior
+        } else {
+            known
+        };
+        (posterior + (1.0 - posterior) * self.learn).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,433 @@
+//! In-memory host imports for running agents natively.
+//!
+//! Every backend records what the agent did so tests can assert on it, and
+//! answers reads from state seeded by the test.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use fot_protocol::{ErrorCode, GraphRead, GraphWrite};
+use serde_json::{json, Value};
+
+use crate::host::{Events, Files, Graph, Host, Metrics};
+
+const ERR_INVALID_HANDLE: u32 = ErrorCode::InvalidHandle as u32;
+const ERR_NOT_FOUND: u32 = ErrorCode::NotFound as u32;
+
+/// Bundles one in-memory backend per import.
+#[derive(Default)]
+pub struct MemoryHost {
+    pub graph: MemoryGraph,
+    pub events: MemoryEvents,
+    pub metrics: MemoryMetrics,
+    pub files: MemoryFiles,
+}
+
+impl MemoryHost {
+    pub fn host(&self) -> Host<'_> {
+        Host {
+            graph: &self.graph,
+            events: &self.events,
+            metrics: &self.metrics,
+            files: &self.files,
+        }
+    }
+}
+
+/// Records mutations and serves canned results keyed by `operation`.
+///
+/// Payloads must carry a supported `version` and a known `operation`; their
+/// other fields are not checked.
+#[derive(Default)]
+pub struct MemoryGraph {
+    replies: Mutex<HashMap<String, String>>,
+    writes: Mutex<Vec<Value>>,
+    open_txns: Mutex<Vec<u64>>,
+    rolled_back: Mutex<Vec<u64>>,
+    next_txn: AtomicU64,
+    auth: Mutex<String>,
+}
+
+impl MemoryGraph {
+    /// Answer reads and writes whose `operation` field equals `operation`
+    /// with `result`. Writes without a canned result reply `{"write": n}`.
+    pub fn respond(&self, operation: &str, result: Value) {
+        self.replies
+            .lock()
+            .unwrap()
+            .insert(operation.to_string(), result.to_string());
+    }
+
+    /// Set the JSON returned by `auth_ctx`.
+    pub fn set_auth_ctx(&self, ctx: Value) {
+        *self.auth.lock().unwrap() = ctx.to_string();
+    }
+
+    /// Transactions begun and not yet committed.
+    pub fn open_txns(&self) -> Vec<u64> {
+        self.open_txns.lock().unwrap().clone()
+    }
+
+    /// Transactions passed to `rollback_txn`, in order.
+    pub fn rolled_back(&self) -> Vec<u64> {
+        self.rolled_back.lock().unwrap().clone()
+    }
+
+    /// Every mutation passed to `graph_write`, in order.
+    pub fn writes(&self) -> Vec<Value> {
+        self.writes.lock().unwrap().clone()
+    }
+}
+
+impl Graph for MemoryGraph {
+    fn graph_read(&self, query: &str) -> Result<String, u32> {
+        let operation = fot_protocol::operation::<GraphRead>(query)?;
+        self.replies
+            .lock()
+            .unwrap()
+            .get(&operation)
+            .cloned()
+            .ok_or(ERR_NOT_FOUND)
+    }
+
+    fn graph_write(&self, mutation: &str) -> Result<String, u32> {
+        let operation = fot_protocol::operation::<GraphWrite>(mutation)?;
+        let value: Value = serde_json::from_str(mutation).expect("checked above");
+        let mut writes = self.writes.lock().unwrap();
+        writes.push(value);
+        let canned = self.replies.lock().unwrap().get(&operation).cloned();
+        Ok(canned.unwrap_or_else(|| json!({ "write": writes.len() }).to_string()))
+    }
+
+    fn begin_txn(&self) -> u64 {
+        let txn = self.next_txn.fetch_add(1, Ordering::Relaxed) + 1;
+        self.open_txns.lock().unwrap().push(txn);
+        txn
+    }
+
+    fn commit_txn(&self, txn: u64) -> Result<(), u32> {
+        let mut open = self.open_txns.lock().unwrap();
+        let index = open
+            .iter()
+            .position(|t| *t == txn)
+            .ok_or(ERR_INVALID_HANDLE)?;
+        open.remove(index);
+        Ok(())
+    }
+
+    fn rollback_txn(&self, txn: u64) -> Result<(), u32> {
+        self.commit_txn(txn)?;
+        self.rolled_back.lock().unwrap().push(txn);
+        Ok(())
+    }
+
+    fn auth_ctx(&self) -> String {
+        let auth = self.auth.lock().unwrap();
+        if auth.is_empty() {
+            "{}".to_string()
+        } else {
+            auth.clone()
+        }
+    }
+}
+
+/// A recorded `emit_resonance` call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resonance {
+    pub agent_id: String,
+    pub context: String,
+    pub amplitude: f64,
+}
+
+/// Keeps published messages, notifications and resonance events.
+#[derive(Default)]
+pub struct MemoryEvents {
+    published: Mutex<Vec<(String, String)>>,
+    refused: Mutex<HashMap<String, u32>>,
+    notifications: Mutex<Vec<(String, String)>>,
+    resonances: Mutex<Vec<Resonance>>,
+    subscriptions: Mutex<HashMap<u64, String>>,
+    next_subscription: AtomicU64,
+}
+
+impl MemoryEvents {
+    /// `(topic, message)` pairs passed to `publish`/`broadcast_harmonic`.
+    pub fn published(&self) -> Vec<(String, String)> {
+        self.published.lock().unwrap().clone()
+    }
+
+    /// `(user_id, message)` pairs passed to `notify`.
+    pub fn notifications(&self) -> Vec<(String, String)> {
+        self.notifications.lock().unwrap().clone()
+    }
+
+    /// Make `publish` on `topic` fail with `code` from now on.
+    pub fn refuse(&self, topic: &str, code: u32) {
+        self.refused.lock().unwrap().insert(topic.to_string(), code);
+    }
+
+    pub fn resonances(&self) -> Vec<Resonance> {
+        self.resonances.lock().unwrap().clone()
+    }
+
+    fn add_subscription(&self, filter: String) -> u64 {
+        let id = self.next_subscription.fetch_add(1, Ordering::Relaxed) + 1;
+        self.subscriptions.lock().unwrap().insert(id, filter);
+        id
+    }
+}
+
+impl Events for MemoryEvents {
+    fn publish(&self, topic: &str, message: &str) -> Result<(), u32> {
+        if let Some(code) = self.refused.lock().unwrap().get(topic) {
+            return Err(*code);
+        }
+        self.published
+            .lock()
+            .unwrap()
+            .push((topic.to_string(), message.to_string()));
+        Ok(())
+    }
+
+    fn subscribe(&self, topic: &str) -> Result<u64, u32> {
+        Ok(self.add_subscription(topic.to_string()))
+    }
+
+    fn unsubscribe(&self, subscription: u64) -> Result<(), u32> {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .remove(&subscription)
+            .map(drop)
+            .ok_or(ERR_INVALID_HANDLE)
+    }
+
+    fn notify(&self, user_id: &str, message: &str) -> Result<(), u32> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .push((user_id.to_string(), message.to_string()));
+        Ok(())
+    }
+
+    fn emit_resonance(
+        &self,
+        agent_id: &str,
+        _frequency: f64,
+        amplitude: f64,
+        context: &str,
+    ) -> Result<(), u32> {
+        self.resonances.lock().unwrap().push(Resonance {
+            agent_id: agent_id.to_string(),
+            context: context.to_string(),
+            amplitude,
+        });
+        Ok(())
+    }
+
+    fn broadcast_harmonic(&self, resonance_data: &str) -> Result<(), u32> {
+        self.publish("harmonic", resonance_data)
+    }
+
+    fn subscribe_resonance(&self, frequency_range: &str) -> Result<u64, u32> {
+        Ok(self.add_subscription(format!("resonance:{frequency_range}")))
+    }
+
+    /// Mean amplitude emitted by the comma-separated agents in `agent_cluster`.
+    fn measure_collective_resonance(&self, agent_cluster: &str) -> Result<f64, u32> {
+        let cluster: Vec<&str> = agent_cluster.split(',').map(str::trim).collect();
+        let resonances = self.resonances.lock().unwrap();
+        let amplitudes: Vec<f64> = resonances
+            .iter()
+            .filter(|r| cluster.contains(&r.agent_id.as_str()))
+            .map(|r| r.amplitude)
+            .collect();
+        mean(&amplitudes).ok_or(ERR_NOT_FOUND)
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Accumulates virtue deltas, usage and resonance per user.
+#[derive(Default)]
+pub struct MemoryMetrics {
+    virtues: Mutex<HashMap<String, HashMap<String, f64>>>,
+    usage: Mutex<HashMap<String, HashMap<String, u64>>>,
+    resonances: Mutex<Vec<Resonance>>,
+}
+
+impl MemoryMetrics {
+    /// Sum of the deltas recorded for `user_id`'s `virtue`.
+    pub fn virtue(&self, user_id: &str, virtue: &str) -> f64 {
+        self.virtues
+            .lock()
+            .unwrap()
+            .get(user_id)
+            .and_then(|v| v.get(virtue))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    pub fn resonances(&self) -> Vec<Resonance> {
+        self.resonances.lock().unwrap().clone()
+    }
+
+    fn stats(&self, user_id: &str) -> Value {
+        json!({
+            "user_id": user_id,
+            "virtues": self.virtues.lock().unwrap().get(user_id).cloned().unwrap_or_default(),
+            "usage": self.usage.lock().unwrap().get(user_id).cloned().unwrap_or_default(),
+        })
+    }
+
+    fn amplitudes(&self, context: &str, agents: Option<&[&str]>) -> Vec<f64> {
+        self.resonances
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.context == context)
+            .filter(|r| agents.is_none_or(|a| a.contains(&r.agent_id.as_str())))
+            .map(|r| r.amplitude)
+            .collect()
+    }
+}
+
+impl Metrics for MemoryMetrics {
+    fn record_virtue(&self, user_id: &str, virtue: &str, delta: f64) -> Result<(), u32> {
+        *self
+            .virtues
+            .lock()
+            .unwrap()
+            .entry(user_id.to_string())
+            .or_default()
+            .entry(virtue.to_string())
+            .or_default() += delta;
+        Ok(())
+    }
+
+    fn record_usage(&self, user_id: &str, action: &str, duration: u64) -> Result<(), u32> {
+        *self
+            .usage
+            .lock()
+            .unwrap()
+            .entry(user_id.to_string())
+            .or_default()
+            .entry(action.to_string())
+            .or_default() += duration;
+        Ok(())
+    }
+
+    fn get_stats(&self, user_id: &str) -> Result<String, u32> {
+        Ok(self.stats(user_id).to_string())
+    }
+
+    fn export_metrics(&self) -> Result<String, u32> {
+        let mut users: Vec<String> = self.virtues.lock().unwrap().keys().cloned().collect();
+        users.extend(self.usage.lock().unwrap().keys().cloned());
+        users.sort();
+        users.dedup();
+        let stats: Vec<Value> = users.iter().map(|u| self.stats(u)).collect();
+        Ok(Value::Array(stats).to_string())
+    }
+
+    fn record_resonance(
+        &self,
+        agent_id: &str,
+        context: &str,
+        resonance_amplitude: f64,
+    ) -> Result<(), u32> {
+        self.resonances.lock().unwrap().push(Resonance {
+            agent_id: agent_id.to_string(),
+            context: context.to_string(),
+            amplitude: resonance_amplitude,
+        });
+        Ok(())
+    }
+
+    /// Mean amplitude recorded in `context` by the comma-separated `agent_ids`.
+    fn measure_harmonic_coherence(&self, agent_ids: &str, context: &str) -> Result<f64, u32> {
+        let agents: Vec<&str> = agent_ids.split(',').map(str::trim).collect();
+        mean(&self.amplitudes(context, Some(&agents))).ok_or(ERR_NOT_FOUND)
+    }
+
+    /// Mean amplitude recorded in any of the comma-separated `agent_contexts`.
+    fn calculate_truth_field(&self, _claim: &str, agent_contexts: &str) -> Result<f64, u32> {
+        let amplitudes: Vec<f64> = agent_contexts
+            .split(',')
+            .flat_map(|context| self.amplitudes(context.trim(), None))
+            .collect();
+        mean(&amplitudes).ok_or(ERR_NOT_FOUND)
+    }
+
+    fn get_resonance_spectrum(&self, domain: &str) -> Result<String, u32> {
+        Ok(json!(self.amplitudes(domain, None)).to_string())
+    }
+}
+
+/// Serves seeded datasets and local files and keeps a key/value cache.
+#[derive(Default)]
+pub struct MemoryFiles {
+    datasets: Mutex<HashMap<String, String>>,
+    local: Mutex<HashMap<String, String>>,
+    cache: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryFiles {
+    pub fn add_dataset(&self, url: &str, content: &str) {
+        self.datasets
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), content.to_string());
+    }
+
+    pub fn add_local(&self, path: &str, content: &str) {
+        self.local
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), content.to_string());
+    }
+}
+
+impl Files for MemoryFiles {
+    fn fetch_dataset(&self, url: &str) -> Result<String, u32> {
+        self.datasets
+            .lock()
+            .unwrap()
+            .get(url)
+            .cloned()
+            .ok_or(ERR_NOT_FOUND)
+    }
+
+    fn cache_get(&self, key: &str) -> Result<String, u32> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or(ERR_NOT_FOUND)
+    }
+
+    fn cache_set(&self, key: &str, value: &str) -> Result<(), u32> {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn open_local(&self, path: &str) -> Result<String, u32> {
+        self.local
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or(ERR_NOT_FOUND)
+    }
+}
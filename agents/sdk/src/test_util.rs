@@ -0,0 +1,51 @@
+//! Helpers for agent test suites, behind the `test-util` feature.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use serde_json::Value;
+
+use crate::{dispatch, Agent, Host};
+
+/// Runs `input` through `agent` natively and parses the reply.
+pub fn invoke<A: Agent>(agent: &A, host: &Host, input: &str) -> Value {
+    serde_json::from_str(&dispatch(agent, host, input.as_bytes())).unwrap()
+}
+
+/// The current Unix time in seconds, as agents stamp their writes.
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// A global allocator that tracks the bytes live on each thread, so test
+/// harness threads do not disturb a measurement. Install it in a test
+/// binary with `#[global_allocator]` and read it with [`live_bytes`].
+pub struct CountingAlloc;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track(delta: isize) {
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + delta));
+}
+
+/// Bytes allocated and not yet freed on the current thread.
+pub fn live_bytes() -> isize {
+    LIVE_BYTES.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        track(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+}
@@ -0,0 +1,129 @@
+//! Drives the generated exports the way a host does and checks that every
+//! buffer handed across the ABI is released.
+
+use fot_agent_sdk::test_util::{live_bytes, CountingAlloc};
+use fot_agent_sdk::{abi, export_agent, Agent, AgentError, Host};
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum EchoRequest {
+    Echo {
+        concept: String,
+        delta: f64,
+        #[serde(default)]
+        kind: Option<EchoKind>,
+    },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum EchoKind {
+    Loud,
+}
+
+#[derive(serde::Serialize)]
+struct EchoResponse {
+    concept: String,
+    delta: f64,
+    loud: bool,
+}
+
+#[derive(Default)]
+struct EchoAgent;
+
+impl Agent for EchoAgent {
+    type Request = EchoRequest;
+    type Response = EchoResponse;
+
+    fn handle(&self, _host: &Host, request: EchoRequest) -> Result<EchoResponse, AgentError> {
+        match request {
+            EchoRequest::Echo {
+                concept,
+                delta,
+                kind,
+            } => Ok(EchoResponse {
+                concept,
+                delta,
+                loud: matches!(kind, Some(EchoKind::Loud)),
+            }),
+        }
+    }
+}
+
+export_agent!(EchoAgent);
+
+/// Copy `input` into agent memory, invoke `run`, read the reply and free
+/// both buffers.
+fn invoke(input: &str) -> String {
+    unsafe {
+        let input_ptr = alloc(input.len());
+        std::ptr::copy_nonoverlapping(input.as_ptr(), input_ptr, input.len());
+
+        let reply_ptr = run(input_ptr, input.len());
+        dealloc(input_ptr, input.len());
+
+        let len = abi::reply_len(reply_ptr);
+        let payload = std::slice::from_raw_parts(reply_ptr.add(abi::LEN_PREFIX), len);
+        let reply = String::from_utf8(payload.to_vec()).unwrap();
+        dealloc(reply_ptr, abi::LEN_PREFIX + len);
+        reply
+    }
+}
+
+#[test]
+fn reply_is_length_prefixed_json() {
+    let reply = invoke(r#"{"op":"echo","concept":"Photosynthesis","delta":0.25}"#);
+    let value: serde_json::Value = serde_json::from_str(&reply).unwrap();
+
+    assert_eq!(value["success"], true);
+    assert_eq!(value["concept"], "Photosynthesis");
+    assert_eq!(value["delta"], 0.25);
+}
+
+#[test]
+fn errors_use_the_same_buffer_format() {
+    let unknown: serde_json::Value = serde_json::from_str(&invoke(r#"{"op":"teleport"}"#)).unwrap();
+    assert_eq!(unknown["success"], false);
+    assert_eq!(unknown["error"]["kind"], "unknown_operation");
+
+    let malformed: serde_json::Value = serde_json::from_str(&invoke("not json")).unwrap();
+    assert_eq!(malformed["error"]["kind"], "invalid_input");
+}
+
+#[test]
+fn unknown_nested_variant_is_not_an_unknown_operation() {
+    let reply = invoke(r#"{"op":"echo","concept":"Entropy","delta":0.1,"kind":"quiet"}"#);
+    let value: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    assert_eq!(value["error"]["kind"], "invalid_input");
+
+    let reply = invoke(r#"{"op":"quiet","concept":"Entropy","delta":0.1,"kind":"quiet"}"#);
+    let value: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    assert_eq!(value["error"]["kind"], "unknown_operation");
+    assert_eq!(value["error"]["op"], "quiet");
+}
+
+#[test]
+fn repeated_calls_do_not_leak() {
+    let inputs = [
+        r#"{"op":"echo","concept":"Carbon_Cycle","delta":0.1}"#,
+        r#"{"op":"unknown"}"#,
+        "",
+    ];
+    // Warm up lazily initialised state before taking the baseline.
+    for input in inputs {
+        invoke(input);
+    }
+
+    let baseline = live_bytes();
+    for i in 0..10_000 {
+        invoke(inputs[i % inputs.len()]);
+        assert!(
+            live_bytes() - baseline < 4096,
+            "live memory grew on call {i}"
+        );
+    }
+    assert_eq!(live_bytes(), baseline);
+}
@@ -0,0 +1,84 @@
+//! Item Response Theory calibration on synthetic responses.
+
+use fot_agent_sdk::irt::{self, ItemParams};
+use fot_agent_sdk::protocol::{BktParams, IrtModel, ItemResponse};
+
+fn response(item: &str, student: usize, score: f64) -> ItemResponse {
+    ItemResponse {
+        assignment_id: item.to_string(),
+        student_id: format!("student-{student}"),
+        score,
+    }
+}
+
+/// Ten students of rising ability: everyone solves `easy`, the stronger half
+/// solves `medium`, and only the top two solve `hard`.
+fn responses() -> Vec<ItemResponse> {
+    let mut responses = Vec::new();
+    for student in 0..10 {
+        responses.push(response("easy", student, 1.0));
+        responses.push(response("medium", student, f64::from(student >= 5)));
+        responses.push(response("hard", student, f64::from(student >= 8)));
+    }
+    responses
+}
+
+#[test]
+fn calibration_orders_items_and_students() {
+    for model in [IrtModel::OnePl, IrtModel::TwoPl] {
+        let calibration = irt::calibrate(&responses(), model);
+        let difficulty = |item: &str| calibration.items[item].0.difficulty;
+        assert!(difficulty("easy") < difficulty("medium"));
+        assert!(difficulty("medium") < difficulty("hard"));
+        assert_eq!(calibration.items["hard"].1, 10);
+
+        let ability = |student: usize| calibration.abilities[&format!("student-{student}")];
+        assert!(ability(0) < ability(5));
+        assert!(ability(5) < ability(9));
+        assert!(calibration
+            .items
+            .values()
+            .all(|(item, _)| item.difficulty.is_finite() && item.discrimination > 0.0));
+    }
+
+    let rasch = irt::calibrate(&responses(), IrtModel::OnePl);
+    assert!(rasch
+        .items
+        .values()
+        .all(|(item, _)| item.discrimination == 1.0));
+}
+
+#[test]
+fn ability_follows_answers_on_calibrated_items() {
+    let hard = ItemParams {
+        difficulty: 1.5,
+        discrimination: 1.2,
+    };
+    let easy = ItemParams {
+        difficulty: -1.5,
+        discrimination: 1.2,
+    };
+    let strong = irt::ability(&[(hard, 1.0), (easy, 1.0)]);
+    let weak = irt::ability(&[(hard, 0.0), (easy, 0.0)]);
+    assert!(weak < 0.0 && 0.0 < strong);
+    assert!((hard.probability(1.5) - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn hard_items_make_correct_answers_stronger_evidence() {
+    let bkt = BktParams::default();
+    let hard = ItemParams {
+        difficulty: 1.0,
+        discrimination: 1.0,
+    }
+    .tracing_params(bkt);
+    let easy = ItemParams {
+        difficulty: -1.0,
+        discrimination: 1.0,
+    }
+    .tracing_params(bkt);
+    assert!(hard.guess < easy.guess);
+    assert!(hard.slip > easy.slip);
+    assert!(hard.slip + hard.guess < 1.0);
+    assert_eq!(hard.prior, bkt.prior);
+}
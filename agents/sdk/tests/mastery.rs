@@ -0,0 +1,79 @@
+//! Bayesian Knowledge Tracing through the `MasteryModel` trait, and
+//! forgetting through `decay`.
+
+use fot_agent_sdk::mastery::{decay, MasteryModel, MASTERY_HALF_LIFE};
+use fot_agent_sdk::protocol::{BktParams, ReviewSchedule};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn bkt_updates_by_bayes_rule_then_learning() {
+    let bkt = BktParams {
+        prior: 0.3,
+        learn: 0.1,
+        slip: 0.1,
+        guess: 0.2,
+    };
+    assert_eq!(bkt.initial(), 0.3);
+
+    // P(L|correct) = 0.3·0.9 / (0.3·0.9 + 0.7·0.2) = 0.27 / 0.41
+    let posterior = 0.27 / 0.41;
+    assert!(close(
+        bkt.observe(0.3, true),
+        posterior + (1.0 - posterior) * 0.1
+    ));
+    // P(L|incorrect) = 0.3·0.1 / (0.3·0.1 + 0.7·0.8) = 0.03 / 0.59
+    let posterior = 0.03 / 0.59;
+    assert!(close(
+        bkt.observe(0.3, false),
+        posterior + (1.0 - posterior) * 0.1
+    ));
+}
+
+#[test]
+fn evidence_moves_mastery_the_right_way_and_stays_a_probability() {
+    let bkt = BktParams::default();
+    let mut mastery = bkt.initial();
+    for _ in 0..20 {
+        let next = bkt.observe(mastery, true);
+        assert!(next >= mastery && next <= 1.0);
+        mastery = next;
+    }
+    assert!(mastery > 0.99);
+    assert!(bkt.observe(0.9, false) < 0.9);
+
+    // Certainty cannot be argued with, and partial credit lands in between.
+    assert_eq!(bkt.observe(1.0, false), 1.0);
+    let half = bkt.observe_score(0.5, 0.5);
+    assert!(bkt.observe(0.5, false) < half && half < bkt.observe(0.5, true));
+    assert!(close(bkt.observe_score(0.5, 1.0), bkt.observe(0.5, true)));
+}
+
+#[test]
+fn mastery_is_forgotten_once_reviews_fall_due() {
+    // Unscheduled mastery halves every half-life since the last update.
+    assert!(close(decay(0.8, Some(0), None, MASTERY_HALF_LIFE), 0.4));
+    assert_eq!(decay(0.8, None, None, MASTERY_HALF_LIFE), 0.8);
+
+    let review = ReviewSchedule {
+        interval_days: 2,
+        ease: 2.5,
+        repetitions: 1,
+        due_at: 10 * DAY,
+    };
+    assert_eq!(decay(0.8, Some(0), Some(review), 9 * DAY), 0.8);
+    assert!(close(decay(0.8, Some(0), Some(review), 12 * DAY), 0.4));
+    // An update after the due date already stored what was lost before it.
+    assert!(close(
+        decay(0.4, Some(12 * DAY), Some(review), 12 * DAY),
+        0.4
+    ));
+    assert!(close(
+        decay(0.4, Some(12 * DAY), Some(review), 14 * DAY),
+        0.2
+    ));
+}
@@ -0,0 +1,166 @@
+//! Cross-domain links surfaced once a concept is mastered, and a record of
+//! the ones the student follows.
+
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{
+    FollowLink, FollowedLink, GetConcept, GetMastery, LinkType, ListConceptLinks, ListFollowedLinks,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::now;
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct SuggestAnalogiesInput {
+    concept: String,
+    /// Mastery at which a concept counts as learned.
+    mastery_threshold: f64,
+}
+
+impl Default for SuggestAnalogiesInput {
+    fn default() -> Self {
+        SuggestAnalogiesInput {
+            concept: String::new(),
+            mastery_threshold: 0.8,
+        }
+    }
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct FollowAnalogyInput {
+    from_concept: String,
+    to_concept: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct AnalogyResponse {
+    concept_id: String,
+    /// After forgetting.
+    mastery: f64,
+    mastered: bool,
+    /// Empty until the concept is mastered; analogies first.
+    suggestions: Vec<AnalogySuggestion>,
+}
+
+#[derive(serde::Serialize)]
+pub struct AnalogySuggestion {
+    concept_id: String,
+    concept_name: String,
+    domain: Option<String>,
+    link_type: LinkType,
+    comment: Option<String>,
+    /// Whether the student has already followed this link.
+    followed: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct FollowResponse {
+    followed: FollowedLink,
+    message: String,
+}
+
+/// Concepts in other domains linked to a mastered `concept`, in either
+/// direction.
+pub(crate) fn suggest_analogies(
+    host: &Host,
+    input: SuggestAnalogiesInput,
+) -> Result<AnalogyResponse, AgentError> {
+    let mut fields = Vec::new();
+    if input.concept.trim().is_empty() {
+        fields.push(FieldError::new("concept", "must not be empty"));
+    }
+    if !(input.mastery_threshold > 0.0 && input.mastery_threshold <= 1.0) {
+        fields.push(FieldError::new(
+            "mastery_threshold",
+            "must be greater than 0 and at most 1",
+        ));
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let stored = host.query(GetMastery {
+        concept: input.concept.clone(),
+        student_id: None,
+    })?;
+    let mastery = decay(stored.mastery, stored.updated_at, stored.review, now());
+    let mastered = mastery >= input.mastery_threshold;
+    let mut response = AnalogyResponse {
+        concept_id: stored.concept_id,
+        mastery,
+        mastered,
+        suggestions: Vec::new(),
+    };
+    if !mastered {
+        return Ok(response);
+    }
+
+    let concept = host
+        .query(GetConcept {
+            concept: response.concept_id.clone(),
+        })?
+        .ok_or_else(|| AgentError::internal("mastered concept is missing from the graph"))?;
+    let map = host.query(ListConceptLinks::default())?;
+    let followed = host.query(ListFollowedLinks::default())?;
+
+    for link in map.links {
+        let other = if link.source == concept.id {
+            &link.target
+        } else if link.target == concept.id {
+            &link.source
+        } else {
+            continue;
+        };
+        let Some(other) = map.concepts.iter().find(|c| &c.id == other) else {
+            continue;
+        };
+        if other.domain == concept.domain {
+            continue;
+        }
+        response.suggestions.push(AnalogySuggestion {
+            concept_id: other.id.clone(),
+            concept_name: other.label.clone(),
+            domain: other.domain.clone(),
+            link_type: link.link_type,
+            comment: link.comment,
+            followed: followed
+                .iter()
+                .any(|f| f.source == concept.id && f.target == other.id),
+        });
+    }
+    response.suggestions.sort_by(|a, b| {
+        (a.link_type != LinkType::Analogy)
+            .cmp(&(b.link_type != LinkType::Analogy))
+            .then_with(|| a.concept_name.cmp(&b.concept_name))
+    });
+    Ok(response)
+}
+
+/// Records the jump with the student's mastery of both concepts, so growth
+/// on the target can later be compared against it.
+pub(crate) fn follow_analogy(
+    host: &Host,
+    input: FollowAnalogyInput,
+) -> Result<FollowResponse, AgentError> {
+    let mut fields = Vec::new();
+    if input.from_concept.trim().is_empty() {
+        fields.push(FieldError::new("from_concept", "must not be empty"));
+    }
+    if input.to_concept.trim().is_empty() {
+        fields.push(FieldError::new("to_concept", "must not be empty"));
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let followed = host.mutate(FollowLink {
+        source: input.from_concept,
+        target: input.to_concept,
+        timestamp: now(),
+    })?;
+    Ok(FollowResponse {
+        message: format!("Followed {} from {}", followed.target, followed.source),
+        followed,
+    })
+}
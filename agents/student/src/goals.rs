@@ -0,0 +1,121 @@
+//! Target mastery of a concept by a date, and how the caller is doing.
+
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{Goal, ListGoals, SetGoal};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::now;
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct SetGoalInput {
+    concept: String,
+    target_mastery: f64,
+    /// Seconds since the Unix epoch.
+    due_at: u64,
+}
+
+#[derive(serde::Serialize)]
+pub struct GoalResponse {
+    goal: Goal,
+    message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct GoalStatusResponse {
+    /// Soonest due first.
+    goals: Vec<GoalStatus>,
+}
+
+#[derive(serde::Serialize)]
+pub struct GoalStatus {
+    #[serde(flatten)]
+    goal: Goal,
+    /// After decay, as `my_progress` reports it.
+    mastery: f64,
+    /// Where mastery would be by now on a straight line from the starting
+    /// mastery to the target.
+    expected_mastery: f64,
+    status: Status,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Achieved,
+    OnTrack,
+    Behind,
+    /// Due and not achieved.
+    Missed,
+}
+
+pub(crate) fn set_goal(host: &Host, input: SetGoalInput) -> Result<GoalResponse, AgentError> {
+    let now = now();
+    let mut fields = Vec::new();
+    if input.concept.trim().is_empty() {
+        fields.push(FieldError::new("concept", "must not be empty"));
+    }
+    if !(input.target_mastery > 0.0 && input.target_mastery <= 1.0) {
+        fields.push(FieldError::new(
+            "target_mastery",
+            "must be greater than 0 and at most 1",
+        ));
+    }
+    if input.due_at <= now {
+        fields.push(FieldError::new("due_at", "must be in the future"));
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let goal = host.mutate(SetGoal {
+        concept: input.concept,
+        target_mastery: input.target_mastery,
+        due_at: input.due_at,
+        timestamp: now,
+    })?;
+    Ok(GoalResponse {
+        message: format!(
+            "Aiming for {:.0}% mastery of {} by {}",
+            goal.target_mastery * 100.0,
+            goal.concept_name,
+            goal.due_at
+        ),
+        goal,
+    })
+}
+
+pub(crate) fn goal_status(host: &Host) -> Result<GoalStatusResponse, AgentError> {
+    let now = now();
+    let goals = host
+        .query(ListGoals::default())?
+        .into_iter()
+        .map(|progress| {
+            let mastery = decay(progress.mastery, progress.updated_at, progress.review, now);
+            status(progress.goal, mastery, now)
+        })
+        .collect();
+    Ok(GoalStatusResponse { goals })
+}
+
+fn status(goal: Goal, mastery: f64, now: u64) -> GoalStatus {
+    let span = goal.due_at.saturating_sub(goal.set_at).max(1) as f64;
+    let elapsed = (now.saturating_sub(goal.set_at) as f64 / span).min(1.0);
+    let expected_mastery =
+        goal.starting_mastery + (goal.target_mastery - goal.starting_mastery) * elapsed;
+    let status = if mastery >= goal.target_mastery {
+        Status::Achieved
+    } else if now >= goal.due_at {
+        Status::Missed
+    } else if mastery >= expected_mastery {
+        Status::OnTrack
+    } else {
+        Status::Behind
+    };
+    GoalStatus {
+        goal,
+        mastery,
+        expected_mastery,
+        status,
+    }
+}
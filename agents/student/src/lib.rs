@@ -1,138 +1,302 @@
-use serde_json::json;
-use wasm_bindgen::prelude::*;
+use fot_agent_sdk::mastery::{decay, MasteryModel};
+use fot_agent_sdk::protocol::{
+    BktParams, ErrorCode, GetConcept, GetMastery, ReviewSchedule, UpdateMastery,
+};
+use fot_agent_sdk::{export_agent, Agent, AgentError, Host};
 
+mod analogies;
+mod goals;
+mod progress;
+mod recommendations;
+mod reviews;
+mod submissions;
+
+pub use analogies::{
+    AnalogyResponse, AnalogySuggestion, FollowAnalogyInput, FollowResponse, SuggestAnalogiesInput,
+};
+pub use goals::{GoalResponse, GoalStatus, GoalStatusResponse, SetGoalInput, Status};
+pub use progress::{
+    ActivityHistoryInput, ActivityHistoryResponse, ConceptVirtues, MyProgressInput,
+    MyProgressResponse, MyVirtuesResponse,
+};
+pub use recommendations::{Reason, RecommendNextInput, Recommendation, RecommendationResponse};
+pub use reviews::{DueReviewsInput, DueReviewsResponse, RecordReviewInput};
+pub use submissions::{SubmitInput, SubmitResponse};
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum StudentRequest {
+    UpdateMastery(UpdateMasteryInput),
+    RecordAnswer(RecordAnswerInput),
+    DueReviews(DueReviewsInput),
+    RecordReview(RecordReviewInput),
+    RecommendNext(RecommendNextInput),
+    SuggestAnalogies(SuggestAnalogiesInput),
+    FollowAnalogy(FollowAnalogyInput),
+    MyProgress(MyProgressInput),
+    MyVirtues,
+    ActivityHistory(ActivityHistoryInput),
+    SetGoal(SetGoalInput),
+    GoalStatus,
+    Submit(SubmitInput),
+}
+
+/// Mastery-writing ops act for the caller unless they name a `student_id`,
+/// which only the student's teachers and admins may do.
 #[derive(serde::Deserialize)]
-struct UpdateMasteryInput {
-    op: String,
+pub struct UpdateMasteryInput {
     concept: String,
     delta: f64,
     context: Option<String>,
+    #[serde(default)]
+    student_id: Option<String>,
 }
 
+/// One observed answer, turned into mastery through the concept's knowledge
+/// tracing model.
+#[derive(serde::Deserialize)]
+pub struct RecordAnswerInput {
+    concept: String,
+    correct: bool,
+    context: Option<String>,
+    #[serde(default)]
+    student_id: Option<String>,
+}
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Read-modify-write attempts before a concept updated concurrently this
+/// often is reported as a conflict.
+const MAX_ATTEMPTS: u32 = 3;
+
 #[derive(serde::Serialize)]
-struct UpdateMasteryResponse {
-    success: bool,
+pub struct UpdateMasteryResponse {
+    student_id: String,
+    /// As stored before this update.
+    previous_mastery: f64,
+    /// Lost to decay since the previous update.
+    decay: f64,
     new_mastery: f64,
+    /// When the concept is next due for review.
+    review: ReviewSchedule,
     truth_field_strength: f64,
     harmonic_coherence: f64,
     message: String,
 }
 
-#[wasm_bindgen]
-pub fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
-    // Parse input command
-    let input_bytes = unsafe { std::slice::from_raw_parts(input_ptr, len) };
-    let input_str = String::from_utf8_lossy(input_bytes);
-    
-    match serde_json::from_str::<UpdateMasteryInput>(&input_str) {
-        Ok(input) => {
-            if input.op == "update_mastery" {
-                // REAL HOST FUNCTION CALL - NO SIMULATION
-                let context = input.context.unwrap_or_else(|| "general_learning".to_string());
-                
-                // Calculate harmonic truth field for the concept
-                let truth_field = calculate_harmonic_truth_field(&input.concept, &context);
-                let harmonic_coherence = calculate_harmonic_coherence(&input.concept, &context);
-                
-                let mutation = json!({
-                    "operation": "update_mastery",
-                    "concept": input.concept,
-                    "delta": input.delta,
-                    "truth_field_strength": truth_field,
-                    "harmonic_coherence": harmonic_coherence,
-                    "context": context,
-                    "timestamp": std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs()
-                });
-                
-                let mutation_str = serde_json::to_string(&mutation).unwrap();
-                
-                // CALL REAL HOST FUNCTION
-                match unsafe { 
-                    crate::fot_graph::graph_write(mutation_str.as_ptr(), mutation_str.len()) 
-                } {
-                    Ok(result) => {
-                        // Record resonance in metrics
-                        let _ = unsafe { 
-                            crate::fot_metrics::record_resonance(
-                                "student_agent".as_ptr(), 
-                                "student_agent".len(),
-                                context.as_ptr(), 
-                                context.len(),
-                                truth_field
-                            ) 
-                        };
-                        
-                        // Parse real response from graph
-                        let response = UpdateMasteryResponse {
-                            success: true,
-                            new_mastery: input.delta, // Real delta from input
-                            truth_field_strength: truth_field,
-                            harmonic_coherence: harmonic_coherence,
-                            message: format!("Mastery updated for concept: {} via real graph operation. Truth field strength: {:.4}", 
-                                           input.concept, truth_field),
-                        };
-                        
-                        let response_json = serde_json::to_string(&response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                    Err(_) => {
-                        let error_response = UpdateMasteryResponse {
-                            success: false,
-                            new_mastery: 0.0,
-                            truth_field_strength: 0.0,
-                            harmonic_coherence: 0.0,
-                            message: "Graph write operation failed".to_string(),
-                        };
-                        
-                        let response_json = serde_json::to_string(&error_response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                }
-            } else {
-                let error_response = UpdateMasteryResponse {
-                    success: false,
-                    new_mastery: 0.0,
-                    truth_field_strength: 0.0,
-                    harmonic_coherence: 0.0,
-                    message: "Unknown operation".to_string(),
-                };
-                
-                let response_json = serde_json::to_string(&error_response).unwrap();
-                let response_bytes = response_json.into_bytes();
-                let response_ptr = response_bytes.as_mut_ptr();
-                
-                std::mem::forget(response_bytes);
-                response_ptr
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum StudentResponse {
+    Mastery(UpdateMasteryResponse),
+    Reviews(DueReviewsResponse),
+    Recommendations(RecommendationResponse),
+    Analogies(AnalogyResponse),
+    Followed(FollowResponse),
+    Progress(MyProgressResponse),
+    Virtues(MyVirtuesResponse),
+    Activity(ActivityHistoryResponse),
+    Goal(GoalResponse),
+    Goals(GoalStatusResponse),
+    Submission(SubmitResponse),
+}
+
+#[derive(Default)]
+pub struct StudentAgent;
+
+impl Agent for StudentAgent {
+    type Request = StudentRequest;
+    type Response = StudentResponse;
+
+    fn handle(&self, host: &Host, request: StudentRequest) -> Result<StudentResponse, AgentError> {
+        match request {
+            StudentRequest::UpdateMastery(input) => {
+                update_mastery(host, input).map(StudentResponse::Mastery)
+            }
+            StudentRequest::RecordAnswer(input) => {
+                record_answer(host, input).map(StudentResponse::Mastery)
+            }
+            StudentRequest::DueReviews(input) => {
+                reviews::due_reviews(host, input).map(StudentResponse::Reviews)
+            }
+            StudentRequest::RecordReview(input) => {
+                reviews::record_review(host, input).map(StudentResponse::Mastery)
+            }
+            StudentRequest::RecommendNext(input) => {
+                recommendations::recommend_next(host, input).map(StudentResponse::Recommendations)
+            }
+            StudentRequest::SuggestAnalogies(input) => {
+                analogies::suggest_analogies(host, input).map(StudentResponse::Analogies)
+            }
+            StudentRequest::FollowAnalogy(input) => {
+                analogies::follow_analogy(host, input).map(StudentResponse::Followed)
+            }
+            StudentRequest::MyProgress(input) => {
+                progress::my_progress(host, input).map(StudentResponse::Progress)
+            }
+            StudentRequest::MyVirtues => progress::my_virtues(host).map(StudentResponse::Virtues),
+            StudentRequest::ActivityHistory(input) => {
+                progress::activity_history(host, input).map(StudentResponse::Activity)
+            }
+            StudentRequest::SetGoal(input) => {
+                goals::set_goal(host, input).map(StudentResponse::Goal)
+            }
+            StudentRequest::GoalStatus => goals::goal_status(host).map(StudentResponse::Goals),
+            StudentRequest::Submit(input) => {
+                submissions::submit(host, input).map(StudentResponse::Submission)
             }
         }
-        Err(_) => {
-            let error_response = UpdateMasteryResponse {
-                success: false,
-                new_mastery: 0.0,
-                truth_field_strength: 0.0,
-                harmonic_coherence: 0.0,
-                message: "Invalid input format".to_string(),
-            };
-            
-            let response_json = serde_json::to_string(&error_response).unwrap();
-            let response_bytes = response_json.into_bytes();
-            let response_ptr = response_bytes.as_mut_ptr();
-            
-            std::mem::forget(response_bytes);
-            response_ptr
+    }
+}
+
+export_agent!(StudentAgent);
+
+fn update_mastery(
+    host: &Host,
+    input: UpdateMasteryInput,
+) -> Result<UpdateMasteryResponse, AgentError> {
+    if !input.delta.is_finite() {
+        return Err(AgentError::invalid_input("delta must be a finite number"));
+    }
+    let student = host.acting_for(input.student_id)?;
+    apply(host, student, input.concept, input.context, |current| {
+        let mastery = (current.decayed + input.delta).clamp(0.0, 1.0);
+        (mastery, current.review_or_first())
+    })
+}
+
+/// Updates mastery with the concept's BKT parameters, or the defaults when it
+/// has none. A concept never practiced starts from the model's prior.
+fn record_answer(
+    host: &Host,
+    input: RecordAnswerInput,
+) -> Result<UpdateMasteryResponse, AgentError> {
+    let student = host.acting_for(input.student_id)?;
+    let model = concept_model(host, &input.concept)?;
+    apply(host, student, input.concept, input.context, |current| {
+        let mastery = model.observe(current.before(&model), input.correct);
+        (mastery, current.review_or_first())
+    })
+}
+
+/// The concept's BKT parameters, or the defaults when it has none.
+fn concept_model(host: &Host, concept: &str) -> Result<BktParams, AgentError> {
+    Ok(host
+        .query(GetConcept {
+            concept: concept.to_string(),
+        })?
+        .and_then(|concept| concept.bkt)
+        .unwrap_or_default())
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Stored mastery as [`apply`] sees it.
+struct Current {
+    decayed: f64,
+    updated_at: Option<u64>,
+    review: Option<ReviewSchedule>,
+    now: u64,
+}
+
+impl Current {
+    /// Mastery before an answer: a concept never practiced starts from the
+    /// model's prior.
+    fn before(&self, model: &impl MasteryModel) -> f64 {
+        match self.updated_at {
+            Some(_) => self.decayed,
+            None => model.initial(),
         }
     }
+
+    /// The stored schedule; learning a concept schedules its first review.
+    fn review_or_first(&self) -> ReviewSchedule {
+        self.review.unwrap_or_else(|| reviews::first(self.now))
+    }
+}
+
+/// Writes the mastery and review schedule `update` computes from the decayed
+/// current value.
+///
+/// The read and the write are tied together by the stored revision rather
+/// than a transaction. A transaction only keeps its staged writes from other
+/// readers; on commit they are replayed over whatever committed meanwhile,
+/// so a concurrent update read before ours would still be overwritten. The
+/// revision check makes the graph refuse that write instead.
+fn apply(
+    host: &Host,
+    student: String,
+    concept: String,
+    context: Option<String>,
+    update: impl Fn(&Current) -> (f64, ReviewSchedule),
+) -> Result<UpdateMasteryResponse, AgentError> {
+    let context = context.unwrap_or_else(|| "general_learning".to_string());
+
+    // Calculate harmonic truth field for the concept
+    let truth_field = calculate_harmonic_truth_field(&concept, &context);
+    let harmonic_coherence = calculate_harmonic_coherence(&concept, &context);
+
+    let now = now();
+
+    // Read, decay and write back only if nothing updated the concept in
+    // between: a concurrent update makes the graph refuse the write, and the
+    // update is recomputed from the value it left.
+    let mut attempts = 0;
+    let (previous_mastery, decayed, new_mastery, review) = loop {
+        attempts += 1;
+        let stored = host.query(GetMastery {
+            concept: concept.clone(),
+            student_id: Some(student.clone()),
+        })?;
+        let current = Current {
+            decayed: decay(stored.mastery, stored.updated_at, stored.review, now),
+            updated_at: stored.updated_at,
+            review: stored.review,
+            now,
+        };
+        let (mastery, review) = update(&current);
+        let updated = host.mutate(UpdateMastery {
+            concept: concept.clone(),
+            student_id: Some(student.clone()),
+            mastery,
+            truth_field_strength: truth_field,
+            harmonic_coherence,
+            context: context.clone(),
+            timestamp: now,
+            review: Some(review),
+            if_revision: Some(stored.revision),
+        });
+        match updated {
+            Err(AgentError::Graph {
+                error: ErrorCode::InvalidState,
+                ..
+            }) if attempts < MAX_ATTEMPTS => continue,
+            updated => break (stored.mastery, current.decayed, updated?.mastery, review),
+        }
+    };
+
+    // Only updates that landed resonate.
+    host.metrics
+        .record_resonance(&student, &context, truth_field)
+        .map_err(|code| AgentError::host("record_resonance", code))?;
+
+    Ok(UpdateMasteryResponse {
+        student_id: student,
+        previous_mastery,
+        decay: previous_mastery - decayed,
+        new_mastery,
+        review,
+        truth_field_strength: truth_field,
+        harmonic_coherence,
+        message: format!(
+            "Mastery updated for concept: {} via real graph operation. Truth field strength: {:.4}",
+            concept, truth_field
+        ),
+    })
 }
 
 // HARMONIC RESONANCE FUNCTIONS
@@ -142,10 +306,10 @@ pub fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
 fn calculate_harmonic_truth_field(concept: &str, context: &str) -> f64 {
     // Base resonance from concept complexity and context relevance
     let base_resonance = (concept.len() as f64).ln() * context_relevance_score(context);
-    
+
     // Harmonic amplification factor (quantum-like superposition)
     let harmonic_factor = (base_resonance * std::f64::consts::PI).cos().abs();
-    
+
     // Normalize to [0, 1] range
     (harmonic_factor * 0.5 + 0.5).min(1.0)
 }
@@ -155,11 +319,9 @@ fn calculate_harmonic_coherence(concept: &str, context: &str) -> f64 {
     // Simplified coherence calculation using concept-context alignment
     let concept_hash = concept.chars().map(|c| c as u32).sum::<u32>() as f64;
     let context_hash = context.chars().map(|c| c as u32).sum::<u32>() as f64;
-    
+
     let phase_difference = (concept_hash - context_hash).abs();
-    let coherence = (phase_difference * 0.01).cos().abs();
-    
-    coherence
+    (phase_difference * 0.01).cos().abs()
 }
 
 /// Calculate context relevance score for learning
@@ -167,6 +329,6 @@ fn context_relevance_score(context: &str) -> f64 {
     // Context relevance based on semantic complexity and domain specificity
     let complexity = context.split_whitespace().count() as f64;
     let specificity = context.chars().filter(|c| c.is_ascii_uppercase()).count() as f64;
-    
+
     (complexity * 0.1 + specificity * 0.05).min(1.0)
 }
@@ -0,0 +1,180 @@
+//! The caller's own progress, virtues and activity, read from the graph.
+
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{
+    ActivityHistory, ActivityPage, ChildProgress, ConceptProgress, ErrorCode, GetChildProgress,
+    Virtues,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::now;
+
+/// Largest page `activity_history` returns.
+const MAX_PAGE: usize = 100;
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct MyProgressInput {
+    /// Only include mastery history from this time on (seconds since the
+    /// Unix epoch).
+    since: Option<u64>,
+    /// Mastery at which a concept counts as learned.
+    mastery_threshold: f64,
+}
+
+impl Default for MyProgressInput {
+    fn default() -> Self {
+        MyProgressInput {
+            since: None,
+            mastery_threshold: 0.8,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct ActivityHistoryInput {
+    offset: usize,
+    limit: usize,
+}
+
+impl Default for ActivityHistoryInput {
+    fn default() -> Self {
+        ActivityHistoryInput {
+            offset: 0,
+            limit: 20,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct MyProgressResponse {
+    student_id: String,
+    /// By concept, with mastery after forgetting.
+    concepts: Vec<ConceptProgress>,
+    mastered: usize,
+    /// Mean current mastery over learned concepts; 0 when there are none.
+    mean_mastery: f64,
+}
+
+#[derive(serde::Serialize)]
+pub struct MyVirtuesResponse {
+    student_id: String,
+    /// Averages across concepts with a recorded value.
+    virtues: Virtues,
+    by_concept: Vec<ConceptVirtues>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ConceptVirtues {
+    concept_id: String,
+    concept_name: String,
+    curiosity: Option<f64>,
+    patience: Option<f64>,
+    honesty: Option<f64>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ActivityHistoryResponse {
+    #[serde(flatten)]
+    page: ActivityPage,
+}
+
+/// The caller's progress; a student with nothing learned yet has no Agent
+/// node, which reads as no progress.
+pub(crate) fn own_progress(host: &Host, since: Option<u64>) -> Result<ChildProgress, AgentError> {
+    let student = host.auth()?.subject;
+    match host.query(GetChildProgress {
+        child_id: student.clone(),
+        since,
+    }) {
+        Err(AgentError::Graph {
+            error: ErrorCode::NotFound,
+            ..
+        }) => Ok(ChildProgress {
+            child_id: student,
+            concepts: Vec::new(),
+            virtues: Virtues::default(),
+        }),
+        result => result,
+    }
+}
+
+pub(crate) fn my_progress(
+    host: &Host,
+    input: MyProgressInput,
+) -> Result<MyProgressResponse, AgentError> {
+    if !(input.mastery_threshold > 0.0 && input.mastery_threshold <= 1.0) {
+        return Err(AgentError::Validation {
+            fields: vec![FieldError::new(
+                "mastery_threshold",
+                "must be greater than 0 and at most 1",
+            )],
+        });
+    }
+    let progress = own_progress(host, input.since)?;
+    let now = now();
+    let concepts: Vec<ConceptProgress> = progress
+        .concepts
+        .into_iter()
+        .map(|concept| ConceptProgress {
+            mastery: decay(concept.mastery, concept.last_updated, concept.review, now),
+            ..concept
+        })
+        .collect();
+    let mastered = concepts
+        .iter()
+        .filter(|c| c.mastery >= input.mastery_threshold)
+        .count();
+    let mean_mastery = if concepts.is_empty() {
+        0.0
+    } else {
+        concepts.iter().map(|c| c.mastery).sum::<f64>() / concepts.len() as f64
+    };
+    Ok(MyProgressResponse {
+        student_id: progress.child_id,
+        concepts,
+        mastered,
+        mean_mastery,
+    })
+}
+
+pub(crate) fn my_virtues(host: &Host) -> Result<MyVirtuesResponse, AgentError> {
+    let progress = own_progress(host, None)?;
+    let by_concept = progress
+        .concepts
+        .into_iter()
+        .map(|c| ConceptVirtues {
+            concept_id: c.concept_id,
+            concept_name: c.concept_name,
+            curiosity: c.curiosity,
+            patience: c.patience,
+            honesty: c.honesty,
+        })
+        .collect();
+    Ok(MyVirtuesResponse {
+        student_id: progress.child_id,
+        virtues: progress.virtues,
+        by_concept,
+    })
+}
+
+pub(crate) fn activity_history(
+    host: &Host,
+    input: ActivityHistoryInput,
+) -> Result<ActivityHistoryResponse, AgentError> {
+    if !(1..=MAX_PAGE).contains(&input.limit) {
+        return Err(AgentError::Validation {
+            fields: vec![FieldError::new(
+                "limit",
+                format!("must be between 1 and {MAX_PAGE}"),
+            )],
+        });
+    }
+    let page = host.query(ActivityHistory {
+        student_id: None,
+        offset: input.offset,
+        limit: input.limit,
+    })?;
+    Ok(ActivityHistoryResponse { page })
+}
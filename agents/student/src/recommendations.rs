@@ -0,0 +1,178 @@
+//! Which concepts to learn next, from the prerequisite structure of the
+//! concept map and the caller's mastery.
+
+use std::collections::BTreeMap;
+
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{LinkType, ListConceptLinks};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::progress::own_progress;
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct RecommendNextInput {
+    limit: usize,
+    /// Mastery at which a concept counts as learned.
+    mastery_threshold: f64,
+}
+
+impl Default for RecommendNextInput {
+    fn default() -> Self {
+        RecommendNextInput {
+            limit: 5,
+            mastery_threshold: 0.8,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct RecommendationResponse {
+    /// Best first.
+    recommendations: Vec<Recommendation>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Recommendation {
+    concept_id: String,
+    concept_name: String,
+    mastery: f64,
+    reason: Reason,
+    /// The reason in words.
+    explanation: String,
+    /// Concepts that list this one as a prerequisite.
+    unlocks: Vec<String>,
+}
+
+/// Why a concept is recommended, in order of preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    /// Started but not yet learned.
+    Continue,
+    /// Every prerequisite is learned.
+    Unlocked,
+    /// Has no prerequisites.
+    Foundation,
+}
+
+struct Concept {
+    name: String,
+    mastery: f64,
+    prerequisites: Vec<String>,
+    unlocks: Vec<String>,
+}
+
+/// Recommends unlearned concepts whose prerequisites are all learned, by
+/// decayed mastery, so a prerequisite forgotten since counts as unlearned.
+/// Concepts still blocked are left out; their missing prerequisites are
+/// recommended instead.
+pub(crate) fn recommend_next(
+    host: &Host,
+    input: RecommendNextInput,
+) -> Result<RecommendationResponse, AgentError> {
+    let mut fields = Vec::new();
+    if input.limit == 0 {
+        fields.push(FieldError::new("limit", "must be at least 1"));
+    }
+    if !(input.mastery_threshold > 0.0 && input.mastery_threshold <= 1.0) {
+        fields.push(FieldError::new(
+            "mastery_threshold",
+            "must be greater than 0 and at most 1",
+        ));
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let map = host.query(ListConceptLinks {
+        link_types: vec![LinkType::Prerequisite, LinkType::Entails],
+    })?;
+    let learned = own_progress(host, None)?.concepts;
+    let now = crate::now();
+
+    let mut concepts: BTreeMap<String, Concept> = BTreeMap::new();
+    for info in map.concepts {
+        concepts.insert(info.id, concept(info.label, 0.0));
+    }
+    for learned in learned {
+        concepts
+            .entry(learned.concept_id)
+            .or_insert_with(|| concept(learned.concept_name, 0.0))
+            .mastery = decay(learned.mastery, learned.last_updated, learned.review, now);
+    }
+    for link in map.links {
+        if let Some(target) = concepts.get_mut(&link.target) {
+            target.prerequisites.push(link.source.clone());
+        }
+        if let Some(source) = concepts.get_mut(&link.source) {
+            source.unlocks.push(link.target);
+        }
+    }
+
+    let learned = |id: &String| {
+        concepts
+            .get(id)
+            .is_some_and(|c| c.mastery >= input.mastery_threshold)
+    };
+    let names = |ids: &[String]| {
+        let names: Vec<&str> = ids
+            .iter()
+            .map(|id| concepts.get(id).map_or(id.as_str(), |c| c.name.as_str()))
+            .collect();
+        names.join(", ")
+    };
+    let mut recommendations: Vec<Recommendation> = concepts
+        .iter()
+        .filter(|(id, c)| !learned(id) && c.prerequisites.iter().all(learned))
+        .map(|(id, c)| {
+            let (reason, explanation) = if c.mastery > 0.0 {
+                let explanation = format!(
+                    "You are {:.0}% of the way to mastering {}",
+                    c.mastery * 100.0,
+                    c.name
+                );
+                (Reason::Continue, explanation)
+            } else if !c.prerequisites.is_empty() {
+                let explanation = format!(
+                    "Builds on {}, which you have mastered",
+                    names(&c.prerequisites)
+                );
+                (Reason::Unlocked, explanation)
+            } else if !c.unlocks.is_empty() {
+                let explanation = format!("Needs nothing first and leads to {}", names(&c.unlocks));
+                (Reason::Foundation, explanation)
+            } else {
+                let explanation = "Needs nothing first".to_string();
+                (Reason::Foundation, explanation)
+            };
+            Recommendation {
+                concept_id: id.clone(),
+                concept_name: c.name.clone(),
+                mastery: c.mastery,
+                reason,
+                explanation,
+                unlocks: c.unlocks.clone(),
+            }
+        })
+        .collect();
+
+    recommendations.sort_by(|a, b| {
+        a.reason
+            .cmp(&b.reason)
+            .then(b.unlocks.len().cmp(&a.unlocks.len()))
+            .then(b.mastery.total_cmp(&a.mastery))
+            .then_with(|| a.concept_name.cmp(&b.concept_name))
+    });
+    recommendations.truncate(input.limit);
+    Ok(RecommendationResponse { recommendations })
+}
+
+fn concept(name: String, mastery: f64) -> Concept {
+    Concept {
+        name,
+        mastery,
+        prerequisites: Vec::new(),
+        unlocks: Vec::new(),
+    }
+}
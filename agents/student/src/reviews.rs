@@ -0,0 +1,128 @@
+//! Spaced repetition: SM-2 review intervals for learned concepts.
+//!
+//! Every concept whose mastery is written gets a [`ReviewSchedule`] on its
+//! `LEARNS` relationship. Reviews graded 3 or better stretch the interval by
+//! the concept's ease; worse ones start it over. Overdue concepts decay, see
+//! [`decay`].
+
+use fot_agent_sdk::mastery::{decay, MasteryModel};
+use fot_agent_sdk::protocol::{DueReviews, ReviewSchedule};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::{apply, concept_model, now, UpdateMasteryResponse, DAY};
+
+/// SM-2's starting ease, and the floor it never drops below.
+const INITIAL_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+
+/// Lowest review quality that counts as remembered.
+const PASSING_QUALITY: u8 = 3;
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct DueReviewsInput {
+    /// Also list reviews falling due within this many days.
+    within_days: u32,
+}
+
+/// A review of `concept`, graded by SM-2 `quality` from 0 (blackout) to 5
+/// (perfect recall).
+#[derive(serde::Deserialize)]
+pub struct RecordReviewInput {
+    concept: String,
+    quality: u8,
+    context: Option<String>,
+    #[serde(default)]
+    student_id: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DueReviewsResponse {
+    /// Soonest due first.
+    reviews: Vec<ReviewItem>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ReviewItem {
+    concept_id: String,
+    concept_name: String,
+    due_at: u64,
+    /// Whole days past `due_at`; 0 when not yet overdue.
+    overdue_days: u64,
+    /// After forgetting since the review fell due.
+    mastery: f64,
+}
+
+/// The schedule of a newly learned concept: review it tomorrow.
+pub(crate) fn first(now: u64) -> ReviewSchedule {
+    ReviewSchedule {
+        interval_days: 1,
+        ease: INITIAL_EASE,
+        repetitions: 0,
+        due_at: now + DAY,
+    }
+}
+
+/// The SM-2 schedule after a review of `quality` at `now`.
+pub(crate) fn next(review: ReviewSchedule, quality: u8, now: u64) -> ReviewSchedule {
+    let q = f64::from(5 - quality.min(5));
+    let ease = (review.ease + 0.1 - q * (0.08 + q * 0.02)).max(MIN_EASE);
+    let (repetitions, interval_days) = if quality < PASSING_QUALITY {
+        (0, 1)
+    } else {
+        let interval = match review.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(review.interval_days) * review.ease).round() as u32,
+        };
+        (review.repetitions + 1, interval)
+    };
+    ReviewSchedule {
+        interval_days,
+        ease,
+        repetitions,
+        due_at: now + u64::from(interval_days) * DAY,
+    }
+}
+
+pub(crate) fn due_reviews(
+    host: &Host,
+    input: DueReviewsInput,
+) -> Result<DueReviewsResponse, AgentError> {
+    let now = now();
+    let due = host.query(DueReviews {
+        until: now + u64::from(input.within_days) * DAY,
+    })?;
+    let reviews = due
+        .into_iter()
+        .map(|d| ReviewItem {
+            overdue_days: now.saturating_sub(d.review.due_at) / DAY,
+            mastery: decay(d.mastery, d.updated_at, Some(d.review), now),
+            concept_id: d.concept_id,
+            concept_name: d.concept_name,
+            due_at: d.review.due_at,
+        })
+        .collect();
+    Ok(DueReviewsResponse { reviews })
+}
+
+/// Reschedules the concept and treats the review as an answer: remembered
+/// or not, depending on `quality`.
+pub(crate) fn record_review(
+    host: &Host,
+    input: RecordReviewInput,
+) -> Result<UpdateMasteryResponse, AgentError> {
+    if input.quality > 5 {
+        return Err(AgentError::Validation {
+            fields: vec![FieldError::new("quality", "must be between 0 and 5")],
+        });
+    }
+    let student = host.acting_for(input.student_id)?;
+    let model = concept_model(host, &input.concept)?;
+    apply(host, student, input.concept, input.context, |current| {
+        let remembered = input.quality >= PASSING_QUALITY;
+        let mastery = model.observe(current.before(&model), remembered);
+        let review = current.review.unwrap_or_else(|| first(current.now));
+        (mastery, next(review, input.quality, current.now))
+    })
+}
@@ -0,0 +1,52 @@
+//! Handing in work for an assignment.
+
+use fot_agent_sdk::protocol::{Role, SubmissionInfo, Submit};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct SubmitInput {
+    assignment_id: String,
+    content: String,
+    time_spent: u64,
+}
+
+#[derive(serde::Serialize)]
+pub struct SubmitResponse {
+    submission: SubmissionInfo,
+    message: String,
+}
+
+/// The graph records the submission against the caller and works out how
+/// late it is from the assignment's due date.
+pub(crate) fn submit(host: &Host, input: SubmitInput) -> Result<SubmitResponse, AgentError> {
+    if host.auth()?.role != Role::Student {
+        return Err(AgentError::forbidden("only students hand in work"));
+    }
+
+    let mut fields = Vec::new();
+    if input.assignment_id.trim().is_empty() {
+        fields.push(FieldError::new("assignment_id", "must not be empty"));
+    }
+    if input.content.trim().is_empty() {
+        fields.push(FieldError::new("content", "must not be empty"));
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let submission = host.mutate(Submit {
+        assignment_id: input.assignment_id,
+        content: input.content,
+        time_spent: input.time_spent,
+        timestamp: crate::now(),
+    })?;
+    let message = match submission.days_late {
+        0 => format!("Submitted {} on time", submission.assignment_id),
+        days => format!("Submitted {} {days} day(s) late", submission.assignment_id),
+    };
+    Ok(SubmitResponse {
+        submission,
+        message,
+    })
+}
@@ -0,0 +1,76 @@
+//! Drives the student agent's buffer exports the way a host does and checks
+//! that every buffer handed across the ABI is released.
+//!
+//! Natively the generated `run` is bound to the wasm imports, so the loop
+//! stands in for it with the same dispatch against the in-memory host.
+
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::test_util::{live_bytes, CountingAlloc};
+use fot_agent_sdk::{abi, dispatch};
+use serde_json::{json, Value};
+use student_agent::StudentAgent;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Copy `input` into the agent's memory through its `alloc` export, run it,
+/// then free both buffers through its `dealloc` export.
+fn invoke(host: &MemoryHost, input: &str) -> Value {
+    unsafe {
+        let input_ptr = student_agent::alloc(input.len());
+        std::ptr::copy_nonoverlapping(input.as_ptr(), input_ptr, input.len());
+
+        let request = abi::input(input_ptr, input.len());
+        let reply_ptr = abi::into_raw(dispatch(&StudentAgent, &host.host(), request));
+        student_agent::dealloc(input_ptr, input.len());
+
+        let len = abi::reply_len(reply_ptr);
+        let payload = std::slice::from_raw_parts(reply_ptr.add(abi::LEN_PREFIX), len);
+        let reply = serde_json::from_slice(payload).unwrap();
+        student_agent::dealloc(reply_ptr, abi::LEN_PREFIX + len);
+        reply
+    }
+}
+
+#[test]
+fn repeated_calls_do_not_leak() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host.graph.respond(
+        "get_child_progress",
+        json!({
+            "child_id": "ada",
+            "concepts": [{
+                "concept_id": "fot:Photosynthesis",
+                "concept_name": "Photosynthesis",
+                "mastery": 0.6,
+            }],
+            "virtues": {"curiosity": 0.2, "patience": 0.1, "honesty": 0.3},
+        }),
+    );
+    let inputs = [
+        r#"{"op":"my_progress"}"#,
+        r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.1,"student_id":"ben"}"#,
+        r#"{"op":"update_mastery","concept":"","delta":7}"#,
+        r#"{"op":"teleport"}"#,
+        "",
+    ];
+    // Warm up lazily initialised state before taking the baseline.
+    for input in inputs {
+        invoke(&host, input);
+    }
+    assert_eq!(invoke(&host, inputs[0])["success"], true);
+    assert_eq!(invoke(&host, inputs[1])["error"]["kind"], "forbidden");
+
+    let baseline = live_bytes();
+    for i in 0..5_000 {
+        invoke(&host, inputs[i % inputs.len()]);
+        assert!(
+            live_bytes() - baseline < 4096,
+            "live memory grew on call {i}"
+        );
+    }
+    assert_eq!(live_bytes(), baseline);
+    assert!(host.graph.writes().is_empty());
+}
@@ -0,0 +1,129 @@
+//! Runs the student agent against the embedded graph store.
+
+mod common;
+
+use std::sync::Mutex;
+
+use common::invoke_on;
+use fot_agent_sdk::host::Graph;
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::protocol::{Envelope, GetMastery, GraphRead, MasteryState};
+use fot_agent_sdk::test_util::now;
+use fot_graph_store::GraphStore;
+use serde_json::json;
+
+/// A store with one concept and `ada` signed in.
+fn classroom() -> GraphStore {
+    let store = GraphStore::new();
+    store
+        .add_node(
+            "fot:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    store
+}
+
+fn stored(store: &GraphStore) -> MasteryState {
+    let query = Envelope::<GraphRead>::new(GetMastery {
+        concept: "Photosynthesis".to_string(),
+        student_id: None,
+    });
+    serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap()
+}
+
+/// Lets another request reach the store between the agent's read and its
+/// first write.
+struct Interleaved<'a> {
+    store: &'a GraphStore,
+    others: &'a MemoryHost,
+    concurrent: Mutex<Option<&'static str>>,
+}
+
+impl Graph for Interleaved<'_> {
+    fn graph_read(&self, query: &str) -> Result<String, u32> {
+        self.store.graph_read(query)
+    }
+
+    fn graph_write(&self, mutation: &str) -> Result<String, u32> {
+        let concurrent = self.concurrent.lock().unwrap().take();
+        if let Some(input) = concurrent {
+            assert_eq!(invoke_on(self.store, self.others, input)["success"], true);
+        }
+        self.store.graph_write(mutation)
+    }
+
+    fn begin_txn(&self) -> u64 {
+        self.store.begin_txn()
+    }
+
+    fn commit_txn(&self, txn: u64) -> Result<(), u32> {
+        self.store.commit_txn(txn)
+    }
+
+    fn rollback_txn(&self, txn: u64) -> Result<(), u32> {
+        self.store.rollback_txn(txn)
+    }
+
+    fn auth_ctx(&self) -> String {
+        self.store.auth_ctx()
+    }
+}
+
+#[test]
+fn an_overdue_review_is_forgotten_once() {
+    const DAY: u64 = 24 * 60 * 60;
+    let store = classroom();
+    let others = MemoryHost::default();
+    let now = now();
+    store
+        .add_node("ada", "Agent", json!({"role": "student"}))
+        .unwrap();
+    store
+        .relate(
+            "ada",
+            "LEARNS",
+            "fot:Photosynthesis",
+            json!({
+                "mastery": 0.8,
+                "updated_at": now - 40 * DAY,
+                "review": {"interval_days": 30, "ease": 2.5, "repetitions": 2, "due_at": now - 10 * DAY},
+            }),
+        )
+        .unwrap();
+    let update = r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.0}"#;
+
+    // Ten days past due with a 30-day interval keeps 2^(-1/3) of it.
+    let first = invoke_on(&store, &others, update);
+    let after_first = first["new_mastery"].as_f64().unwrap();
+    assert!((after_first - 0.8 * 0.5_f64.powf(1.0 / 3.0)).abs() < 1e-3);
+
+    // The review is still overdue, but that forgetting is already stored.
+    let second = invoke_on(&store, &others, update);
+    assert!(second["decay"].as_f64().unwrap() < 1e-3);
+    assert!((second["new_mastery"].as_f64().unwrap() - after_first).abs() < 1e-3);
+}
+
+#[test]
+fn interleaved_updates_are_not_lost() {
+    let store = classroom();
+    let others = MemoryHost::default();
+    let update = r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.1}"#;
+    let graph = Interleaved {
+        store: &store,
+        others: &others,
+        concurrent: Mutex::new(Some(update)),
+    };
+
+    // Both updates read 0; the later write sees the earlier one and redoes
+    // its update on top of it.
+    let reply = invoke_on(&graph, &others, update);
+    assert_eq!(reply["success"], true);
+    assert!((reply["new_mastery"].as_f64().unwrap() - 0.2).abs() < 1e-9);
+    let stored = stored(&store);
+    assert!((stored.mastery - 0.2).abs() < 1e-9);
+    assert_eq!(stored.revision, 2);
+    assert!(!store.in_txn());
+}
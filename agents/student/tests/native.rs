@@ -0,0 +1,489 @@
+//! Runs the student agent natively against the in-memory host.
+
+mod common;
+
+use common::invoke;
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::test_util::now;
+use serde_json::json;
+
+/// A host signed in as the student `ada`.
+fn student_host() -> MemoryHost {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host
+}
+
+#[test]
+fn update_mastery_writes_to_the_graph_and_records_resonance() {
+    let host = student_host();
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.0}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.1}),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.1,"context":"Biology lab"}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["previous_mastery"], 0.0);
+    assert_eq!(reply["new_mastery"], 0.1);
+    let writes = host.graph.writes();
+    assert_eq!(writes.len(), 1);
+    assert_eq!(writes[0]["operation"], "update_mastery");
+    assert_eq!(writes[0]["concept"], "Photosynthesis");
+    assert_eq!(writes[0]["mastery"], 0.1);
+    assert_eq!(writes[0]["student_id"], "ada");
+    assert!(host.graph.open_txns().is_empty());
+
+    let resonances = host.metrics.resonances();
+    assert_eq!(resonances.len(), 1);
+    assert_eq!(resonances[0].agent_id, "ada");
+    assert_eq!(resonances[0].context, "Biology lab");
+}
+
+#[test]
+fn only_teachers_and_admins_act_for_another_student() {
+    let host = student_host();
+    let update =
+        r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.1,"student_id":"ben"}"#;
+    let reply = invoke(&host, update);
+    assert_eq!(reply["error"]["kind"], "forbidden");
+    assert!(host.graph.writes().is_empty());
+
+    host.graph
+        .set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.0}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.1}),
+    );
+    let reply = invoke(&host, update);
+    assert_eq!(reply["student_id"], "ben");
+    assert_eq!(host.graph.writes()[0]["student_id"], "ben");
+    assert_eq!(host.metrics.resonances()[0].agent_id, "ben");
+}
+
+#[test]
+fn submit_stores_the_work_as_the_caller() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host.graph.respond(
+        "submit",
+        json!({
+            "id": "submission-3",
+            "assignment_id": "assignment-1",
+            "student_id": "ada",
+            "content": "Chlorophyll",
+            "time_spent": 600,
+            "submitted_at": 100,
+            "days_late": 1
+        }),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"submit","assignment_id":"assignment-1","content":"Chlorophyll","time_spent":600}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["submission"]["id"], "submission-3");
+    assert_eq!(reply["message"], "Submitted assignment-1 1 day(s) late");
+    let writes = host.graph.writes();
+    assert_eq!(writes[0]["operation"], "submit");
+    assert_eq!(writes[0]["content"], "Chlorophyll");
+}
+
+#[test]
+fn mastery_decays_and_is_clamped_to_one() {
+    let host = student_host();
+    // Last updated a long time ago: a year of forgetting leaves little.
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.8, "updated_at": 0}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 1.0}),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.5}"#,
+    );
+    let decay = reply["decay"].as_f64().unwrap();
+    assert!(decay > 0.7 && decay <= 0.8, "decay {decay}");
+    let written = host.graph.writes()[0]["mastery"].as_f64().unwrap();
+    assert!(written < 0.6);
+
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.9}),
+    );
+    invoke(
+        &host,
+        r#"{"op":"update_mastery","concept":"Photosynthesis","delta":0.5}"#,
+    );
+    assert_eq!(host.graph.writes()[1]["mastery"], 1.0);
+    assert!(host.graph.open_txns().is_empty());
+}
+
+#[test]
+fn record_answer_traces_knowledge_with_the_concepts_parameters() {
+    let host = student_host();
+    host.graph.respond(
+        "get_concept",
+        json!({
+            "id": "fot:Photosynthesis",
+            "label": "Photosynthesis",
+            "bkt": {"prior": 0.5, "learn": 0.0, "slip": 0.1, "guess": 0.1}
+        }),
+    );
+    // Never practiced, so the concept's prior applies.
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.0}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.9}),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"record_answer","concept":"Photosynthesis","correct":true}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    let writes = host.graph.writes();
+    assert_eq!(writes[0]["operation"], "update_mastery");
+    // 0.5·0.9 / (0.5·0.9 + 0.5·0.1)
+    let mastery = writes[0]["mastery"].as_f64().unwrap();
+    assert!((mastery - 0.9).abs() < 1e-9);
+    assert!(host.graph.open_txns().is_empty());
+}
+
+#[test]
+fn reviews_stretch_the_interval_by_the_ease() {
+    let host = student_host();
+    // No parameters of its own, so the default model applies.
+    host.graph.respond("get_concept", json!(null));
+    // Not due until far in the future, so nothing has been forgotten.
+    host.graph.respond(
+        "get_mastery",
+        json!({
+            "concept_id": "fot:Photosynthesis",
+            "mastery": 0.8,
+            "updated_at": 0,
+            "review": {"interval_days": 6, "ease": 2.5, "repetitions": 2, "due_at": 4_000_000_000u64}
+        }),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "fot:Photosynthesis", "mastery": 0.9}),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"record_review","concept":"Photosynthesis","quality":5}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["decay"], 0.0);
+    let review = &host.graph.writes()[0]["review"];
+    assert_eq!(review["interval_days"], 15);
+    assert_eq!(review["repetitions"], 3);
+    assert!((review["ease"].as_f64().unwrap() - 2.6).abs() < 1e-9);
+    assert_eq!(reply["review"], *review);
+
+    let reply = invoke(
+        &host,
+        r#"{"op":"record_review","concept":"Photosynthesis","quality":1}"#,
+    );
+    let review = &host.graph.writes()[1]["review"];
+    assert_eq!(review["interval_days"], 1);
+    assert_eq!(review["repetitions"], 0);
+    assert!(reply["new_mastery"].is_number());
+
+    let reply = invoke(
+        &host,
+        r#"{"op":"record_review","concept":"Photosynthesis","quality":6}"#,
+    );
+    assert_eq!(reply["error"]["kind"], "validation");
+}
+
+#[test]
+fn overdue_reviews_have_decayed() {
+    let host = MemoryHost::default();
+    host.graph.respond(
+        "due_reviews",
+        json!([{
+            "concept_id": "fot:Photosynthesis",
+            "concept_name": "Photosynthesis",
+            "mastery": 0.8,
+            "updated_at": 0,
+            "review": {"interval_days": 1, "ease": 2.5, "repetitions": 0, "due_at": 86_400}
+        }]),
+    );
+    let reply = invoke(&host, r#"{"op":"due_reviews"}"#);
+
+    assert_eq!(reply["success"], true);
+    let review = &reply["reviews"][0];
+    assert_eq!(review["concept_name"], "Photosynthesis");
+    assert!(review["overdue_days"].as_u64().unwrap() > 365);
+    assert!(review["mastery"].as_f64().unwrap() < 0.01);
+}
+
+#[test]
+fn recommendations_follow_prerequisites_and_mastery() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    let concept = |id: &str| json!({"id": id, "label": id.replace('_', " ")});
+    let link = |source: &str, target: &str| json!({"source": source, "target": target, "link_type": "entails"});
+    // Photosynthesis -> Carbon_Cycle -> Climate_Model, and Entropy on its own
+    // leading to Entropy_in_Atmosphere.
+    host.graph.respond(
+        "list_concept_links",
+        json!({
+            "concepts": [
+                concept("Photosynthesis"), concept("Carbon_Cycle"), concept("Climate_Model"),
+                concept("Entropy"), concept("Entropy_in_Atmosphere")
+            ],
+            "links": [
+                link("Photosynthesis", "Carbon_Cycle"),
+                link("Carbon_Cycle", "Climate_Model"),
+                link("Entropy", "Entropy_in_Atmosphere")
+            ]
+        }),
+    );
+    let learned = |id: &str, mastery: f64| json!({"concept_id": id, "concept_name": id, "mastery": mastery, "history": []});
+    host.graph.respond(
+        "get_child_progress",
+        json!({
+            "child_id": "ada",
+            "concepts": [learned("Photosynthesis", 0.9), learned("Entropy", 0.3)],
+            "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0}
+        }),
+    );
+    let reply = invoke(&host, r#"{"op":"recommend_next"}"#);
+
+    assert_eq!(reply["success"], true);
+    let recommended: Vec<(&str, &str)> = reply["recommendations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["concept_id"].as_str().unwrap(),
+                r["reason"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    // Climate_Model waits for Carbon_Cycle; Entropy_in_Atmosphere for Entropy.
+    assert_eq!(
+        recommended,
+        [("Entropy", "continue"), ("Carbon_Cycle", "unlocked")]
+    );
+    assert_eq!(
+        reply["recommendations"][1]["explanation"],
+        "Builds on Photosynthesis, which you have mastered"
+    );
+}
+
+#[test]
+fn forgotten_prerequisites_are_recommended_again() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host.graph.respond(
+        "list_concept_links",
+        json!({
+            "concepts": [
+                {"id": "Photosynthesis", "label": "Photosynthesis"},
+                {"id": "Carbon_Cycle", "label": "Carbon Cycle"}
+            ],
+            "links": [{"source": "Photosynthesis", "target": "Carbon_Cycle", "link_type": "prerequisite"}]
+        }),
+    );
+    // Mastered, but untouched for decades.
+    host.graph.respond(
+        "get_child_progress",
+        json!({
+            "child_id": "ada",
+            "concepts": [{
+                "concept_id": "Photosynthesis", "concept_name": "Photosynthesis",
+                "mastery": 0.9, "last_updated": 0, "history": []
+            }],
+            "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0}
+        }),
+    );
+    let reply = invoke(&host, r#"{"op":"recommend_next"}"#);
+
+    assert_eq!(reply["success"], true);
+    let recommendations = reply["recommendations"].as_array().unwrap();
+    assert_eq!(
+        recommendations.len(),
+        1,
+        "Carbon_Cycle waits for Photosynthesis"
+    );
+    assert_eq!(recommendations[0]["concept_id"], "Photosynthesis");
+    assert!(recommendations[0]["mastery"].as_f64().unwrap() < 0.01);
+}
+
+#[test]
+fn mastered_concepts_surface_links_into_other_domains() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "ex:Ecosystem", "mastery": 0.3}),
+    );
+    let reply = invoke(&host, r#"{"op":"suggest_analogies","concept":"Ecosystem"}"#);
+    assert_eq!(reply["mastered"], false);
+    assert_eq!(reply["suggestions"], json!([]));
+
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "ex:Ecosystem", "mastery": 0.95}),
+    );
+    let concept = |id: &str, domain: &str| json!({"id": id, "label": id, "domain": domain});
+    host.graph
+        .respond("get_concept", concept("ex:Ecosystem", "Biology"));
+    host.graph.respond(
+        "list_concept_links",
+        json!({
+            "concepts": [
+                concept("ex:Ecosystem", "Biology"),
+                concept("ex:Resource_Use", "Economics"),
+                concept("ex:Food_Web", "Biology"),
+                concept("ex:Entropy", "Physics")
+            ],
+            "links": [
+                {"source": "ex:Ecosystem", "target": "ex:Resource_Use", "link_type": "analogy",
+                 "comment": "Ecosystems and resource use share principles of sustainability and balance"},
+                {"source": "ex:Food_Web", "target": "ex:Ecosystem", "link_type": "analogy"},
+                {"source": "ex:Entropy", "target": "ex:Ecosystem", "link_type": "entails"}
+            ]
+        }),
+    );
+    host.graph.respond(
+        "list_followed_links",
+        json!([{
+            "source": "ex:Ecosystem", "target": "ex:Resource_Use", "link_type": "analogy",
+            "followed_at": 1, "source_mastery": 0.9, "target_mastery_before": 0.0, "target_mastery": 0.2
+        }]),
+    );
+    let reply = invoke(&host, r#"{"op":"suggest_analogies","concept":"Ecosystem"}"#);
+
+    assert_eq!(reply["mastered"], true);
+    let suggestions = reply["suggestions"].as_array().unwrap();
+    // Same-domain links are left out, analogies come first.
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0]["concept_id"], "ex:Resource_Use");
+    assert_eq!(suggestions[0]["domain"], "Economics");
+    assert_eq!(suggestions[0]["followed"], true);
+    assert!(suggestions[0]["comment"]
+        .as_str()
+        .unwrap()
+        .contains("sustainability"));
+    assert_eq!(suggestions[1]["link_type"], "entails");
+    assert_eq!(suggestions[1]["followed"], false);
+
+    invoke(
+        &host,
+        r#"{"op":"follow_analogy","from_concept":"Ecosystem","to_concept":"Resource_Use"}"#,
+    );
+    let writes = host.graph.writes();
+    assert_eq!(writes[0]["operation"], "follow_link");
+    assert_eq!(writes[0]["target"], "Resource_Use");
+}
+
+#[test]
+fn progress_reads_as_empty_before_anything_is_learned() {
+    let host = student_host();
+    let reply = invoke(&host, r#"{"op":"my_progress"}"#);
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["student_id"], "ada");
+    assert_eq!(reply["concepts"], json!([]));
+    assert_eq!(reply["mean_mastery"], 0.0);
+
+    let reply = invoke(&host, r#"{"op":"activity_history","limit":500}"#);
+    assert_eq!(reply["error"]["kind"], "validation");
+    host.graph.respond(
+        "activity_history",
+        json!({"activities": [], "total": 0, "next_offset": null}),
+    );
+    let reply = invoke(&host, r#"{"op":"activity_history","offset":20}"#);
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["total"], 0);
+}
+
+#[test]
+fn goals_are_on_track_when_mastery_keeps_pace_with_the_due_date() {
+    let host = student_host();
+    let now = now();
+    let day = 24 * 60 * 60;
+    // Each goal was set ten days ago from 0.2 towards 0.8.
+    let goal = |concept: &str, due_in_days: i64, mastery: f64| {
+        json!({
+            "concept_id": concept, "concept_name": concept, "target_mastery": 0.8,
+            "due_at": now.saturating_add_signed(due_in_days * day as i64),
+            "set_at": now - 10 * day, "starting_mastery": 0.2, "mastery": mastery
+        })
+    };
+    host.graph.respond(
+        "list_goals",
+        json!([
+            goal("Entropy", -1, 0.5),
+            goal("Photosynthesis", 10, 0.6),
+            goal("Carbon_Cycle", 10, 0.4),
+            goal("Climate_Model", 10, 0.9),
+            // Stored above target, but two half-lives ago.
+            json!({
+                "concept_id": "Optics", "concept_name": "Optics", "target_mastery": 0.8,
+                "due_at": now + 10 * day, "set_at": now - 10 * day, "starting_mastery": 0.2,
+                "mastery": 0.9, "updated_at": now - 60 * day
+            })
+        ]),
+    );
+    let reply = invoke(&host, r#"{"op":"goal_status"}"#);
+
+    let statuses: Vec<(&str, &str)> = reply["goals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|g| {
+            (
+                g["concept_id"].as_str().unwrap(),
+                g["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    // Halfway there in time, 0.5 is expected.
+    assert_eq!(
+        statuses,
+        [
+            ("Entropy", "missed"),
+            ("Photosynthesis", "on_track"),
+            ("Carbon_Cycle", "behind"),
+            ("Climate_Model", "achieved"),
+            ("Optics", "behind")
+        ]
+    );
+    assert!((reply["goals"][1]["expected_mastery"].as_f64().unwrap() - 0.5).abs() < 1e-3);
+
+    let reply = invoke(
+        &host,
+        r#"{"op":"set_goal","concept":"Entropy","target_mastery":0.8,"due_at":1}"#,
+    );
+    assert_eq!(reply["error"]["fields"][0]["field"], "due_at");
+    assert!(host.graph.writes().is_empty());
+}
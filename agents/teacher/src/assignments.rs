@@ -0,0 +1,68 @@
+//! Assignments with a due date and a late policy.
+
+use fot_agent_sdk::protocol::{AssignmentInfo, CreateAssignment, LatePolicy};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::lessons::check_concept;
+use crate::{now, require_class, require_text, teacher, validated};
+
+/// Difficulty is not set here: `calibrate_items` estimates it from graded
+/// submissions.
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+pub struct CreateAssignmentInput {
+    class_id: String,
+    concept: String,
+    title: String,
+    lesson_id: Option<String>,
+    due_at: Option<u64>,
+    late_policy: LatePolicy,
+}
+
+#[derive(serde::Serialize)]
+pub struct AssignmentResponse {
+    assignment: AssignmentInfo,
+    message: String,
+}
+
+pub(crate) fn create_assignment(
+    host: &Host,
+    input: CreateAssignmentInput,
+) -> Result<AssignmentResponse, AgentError> {
+    let auth = teacher(host)?;
+
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    require_text(&mut errors, "concept", &input.concept);
+    require_text(&mut errors, "title", &input.title);
+    if input.due_at.is_none() {
+        errors.push(FieldError::new("due_at", "must be given"));
+    }
+    if !(0.0..=1.0).contains(&input.late_policy.penalty_per_day) {
+        errors.push(FieldError::new(
+            "late_policy.penalty_per_day",
+            "must be between 0 and 1",
+        ));
+    }
+    check_concept(host, &mut errors, &input.concept)?;
+    validated(errors)?;
+
+    require_class(host, &auth, &input.class_id)?;
+
+    let assignment = host.mutate(CreateAssignment {
+        class_id: input.class_id,
+        concept: input.concept,
+        title: input.title,
+        lesson_id: input.lesson_id,
+        due_at: input.due_at.unwrap_or_default(),
+        late_policy: input.late_policy,
+        timestamp: now(),
+    })?;
+    Ok(AssignmentResponse {
+        message: format!(
+            "Assignment {} is due at {}",
+            assignment.id, assignment.due_at
+        ),
+        assignment,
+    })
+}
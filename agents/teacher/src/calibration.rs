@@ -0,0 +1,113 @@
+//! Item Response Theory calibration of a class's assignments from their
+//! graded submissions.
+
+use std::collections::BTreeMap;
+
+use fot_agent_sdk::irt;
+use fot_agent_sdk::protocol::{CalibrateAssignment, IrtModel, ItemCalibration, ListResponses};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::{now, require_class, require_text, teacher, validated};
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct CalibrateItemsInput {
+    class_id: String,
+    model: IrtModel,
+    /// Assignments with fewer graded submissions keep their current
+    /// parameters.
+    min_responses: usize,
+}
+
+impl Default for CalibrateItemsInput {
+    fn default() -> Self {
+        CalibrateItemsInput {
+            class_id: String::new(),
+            model: IrtModel::default(),
+            min_responses: 5,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct CalibrationReport {
+    class_id: String,
+    model: IrtModel,
+    /// Calibrated assignments, by id.
+    items: BTreeMap<String, ItemCalibration>,
+    /// Assignments left out for lack of responses.
+    skipped: Vec<String>,
+    /// Estimated ability of each student who has graded work, on the same
+    /// logit scale as item difficulty.
+    abilities: BTreeMap<String, f64>,
+}
+
+/// Estimates every assignment of the class together, so difficulties and
+/// abilities share one scale, then stores the parameters in one transaction:
+/// either every calibrated assignment is updated or none is.
+pub(crate) fn calibrate_items(
+    host: &Host,
+    input: CalibrateItemsInput,
+) -> Result<CalibrationReport, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    if input.min_responses == 0 {
+        errors.push(FieldError::new("min_responses", "must be at least 1"));
+    }
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let responses = host.query(ListResponses {
+        class_id: input.class_id.clone(),
+    })?;
+    let estimate = irt::calibrate(&responses, input.model);
+    let calibrated_at = now();
+
+    let mut items = BTreeMap::new();
+    let mut skipped = Vec::new();
+    for (assignment_id, (params, count)) in estimate.items {
+        if count < input.min_responses {
+            skipped.push(assignment_id);
+            continue;
+        }
+        items.insert(
+            assignment_id,
+            ItemCalibration {
+                model: input.model,
+                difficulty: params.difficulty,
+                discrimination: params.discrimination,
+                responses: count,
+                calibrated_at,
+            },
+        );
+    }
+
+    let txn = host.graph.begin_txn();
+    let result = items.iter().try_for_each(|(assignment_id, calibration)| {
+        host.mutate_in(
+            txn,
+            CalibrateAssignment {
+                assignment_id: assignment_id.clone(),
+                calibration: *calibration,
+            },
+        )
+        .map(drop)
+    });
+    if let Err(error) = result {
+        // The error being reported is the write's, not the rollback's.
+        let _ = host.graph.rollback_txn(txn);
+        return Err(error);
+    }
+    host.graph
+        .commit_txn(txn)
+        .map_err(|code| AgentError::graph("commit_txn", code))?;
+
+    Ok(CalibrationReport {
+        class_id: input.class_id,
+        model: input.model,
+        items,
+        skipped,
+        abilities: estimate.abilities,
+    })
+}
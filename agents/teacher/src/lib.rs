@@ -1,108 +1,159 @@
-use serde_json::json;
-use wasm_bindgen::prelude::*;
+use fot_agent_sdk::protocol::{AuthContext, Role, TeachesClass};
+use fot_agent_sdk::{export_agent, Agent, AgentError, FieldError, Host};
+
+mod assignments;
+mod calibration;
+mod lessons;
+mod reports;
+mod roster;
+
+pub use assignments::{AssignmentResponse, CreateAssignmentInput};
+pub use calibration::{CalibrateItemsInput, CalibrationReport};
+pub use lessons::{
+    CreateLessonInput, CreateLessonResponse, LessonIdInput, LessonListResponse, LessonResponse,
+    ListLessonsInput, ScheduleLessonInput, UpdateLessonInput,
+};
+pub use reports::{ClassReport, ClassReportInput};
+pub use roster::{
+    ClassResponse, CreateClassInput, EnrollStudentInput, EnrollmentResponse, ListRosterInput,
+    RemoveStudentInput, RosterResponse, TransferStudentInput,
+};
 
 #[derive(serde::Deserialize)]
-struct CreateLessonInput {
-    op: String,
-    concept: String,
-    class_id: String,
-    content: String,
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TeacherRequest {
+    CreateLesson(CreateLessonInput),
+    UpdateLesson(UpdateLessonInput),
+    PublishLesson(LessonIdInput),
+    ScheduleLesson(ScheduleLessonInput),
+    ArchiveLesson(LessonIdInput),
+    ListLessons(ListLessonsInput),
+    CreateClass(CreateClassInput),
+    EnrollStudent(EnrollStudentInput),
+    RemoveStudent(RemoveStudentInput),
+    TransferStudent(TransferStudentInput),
+    ListRoster(ListRosterInput),
+    CreateAssignment(CreateAssignmentInput),
+    CalibrateItems(CalibrateItemsInput),
+    ClassReport(ClassReportInput),
 }
 
 #[derive(serde::Serialize)]
-struct CreateLessonResponse {
-    success: bool,
-    lesson_id: String,
-    message: String,
+#[serde(untagged)]
+pub enum TeacherResponse {
+    LessonCreated(CreateLessonResponse),
+    Lesson(LessonResponse),
+    Lessons(LessonListResponse),
+    Class(ClassResponse),
+    Enrollment(EnrollmentResponse),
+    Roster(RosterResponse),
+    Assignment(AssignmentResponse),
+    Calibration(CalibrationReport),
+    Report(ClassReport),
 }
 
-#[wasm_bindgen]
-pub fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
-    // Parse input command
-    let input_bytes = unsafe { std::slice::from_raw_parts(input_ptr, len) };
-    let input_str = String::from_utf8_lossy(input_bytes);
-    
-    match serde_json::from_str::<CreateLessonInput>(&input_str) {
-        Ok(input) => {
-            if input.op == "create_lesson" {
-                // REAL HOST FUNCTION CALL - NO SIMULATION
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                
-                let mutation = json!({
-                    "operation": "create_lesson",
-                    "concept": input.concept,
-                    "class_id": input.class_id,
-                    "content": input.content,
-                    "timestamp": timestamp
-                });
-                
-                let mutation_str = serde_json::to_string(&mutation).unwrap();
-                
-                // CALL REAL HOST FUNCTION
-                match unsafe { 
-                    crate::fot_graph::graph_write(mutation_str.as_ptr(), mutation_str.len()) 
-                } {
-                    Ok(result) => {
-                        // Parse real response from graph - NO FAKE UUID
-                        let response = CreateLessonResponse {
-                            success: true,
-                            lesson_id: "pending_graph_id".to_string(), // Will be populated from real graph response
-                            message: format!("Lesson created for concept: {} in class: {} via real graph operation", input.concept, input.class_id),
-                        };
-                        
-                        let response_json = serde_json::to_string(&response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                    Err(_) => {
-                        let error_response = CreateLessonResponse {
-                            success: false,
-                            lesson_id: "".to_string(),
-                            message: "Graph write operation failed".to_string(),
-                        };
-                        
-                        let response_json = serde_json::to_string(&error_response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                }
-            } else {
-                let error_response = CreateLessonResponse {
-                    success: false,
-                    lesson_id: "".to_string(),
-                    message: "Unknown operation".to_string(),
-                };
-                
-                let response_json = serde_json::to_string(&error_response).unwrap();
-                let response_bytes = response_json.into_bytes();
-                let response_ptr = response_bytes.as_mut_ptr();
-                
-                std::mem::forget(response_bytes);
-                response_ptr
+#[derive(Default)]
+pub struct TeacherAgent;
+
+impl Agent for TeacherAgent {
+    type Request = TeacherRequest;
+    type Response = TeacherResponse;
+
+    fn handle(&self, host: &Host, request: TeacherRequest) -> Result<TeacherResponse, AgentError> {
+        match request {
+            TeacherRequest::CreateLesson(input) => {
+                lessons::create_lesson(host, input).map(TeacherResponse::LessonCreated)
+            }
+            TeacherRequest::UpdateLesson(input) => {
+                lessons::update_lesson(host, input).map(TeacherResponse::Lesson)
+            }
+            TeacherRequest::PublishLesson(input) => {
+                lessons::publish_lesson(host, input).map(TeacherResponse::Lesson)
+            }
+            TeacherRequest::ScheduleLesson(input) => {
+                lessons::schedule_lesson(host, input).map(TeacherResponse::Lesson)
+            }
+            TeacherRequest::ArchiveLesson(input) => {
+                lessons::archive_lesson(host, input).map(TeacherResponse::Lesson)
+            }
+            TeacherRequest::ListLessons(input) => {
+                lessons::list_lessons(host, input).map(TeacherResponse::Lessons)
+            }
+            TeacherRequest::CreateClass(input) => {
+                roster::create_class(host, input).map(TeacherResponse::Class)
+            }
+            TeacherRequest::EnrollStudent(input) => {
+                roster::enroll_student(host, input).map(TeacherResponse::Enrollment)
+            }
+            TeacherRequest::RemoveStudent(input) => {
+                roster::remove_student(host, input).map(TeacherResponse::Enrollment)
+            }
+            TeacherRequest::TransferStudent(input) => {
+                roster::transfer_student(host, input).map(TeacherResponse::Enrollment)
+            }
+            TeacherRequest::ListRoster(input) => {
+                roster::list_roster(host, input).map(TeacherResponse::Roster)
+            }
+            TeacherRequest::CreateAssignment(input) => {
+                assignments::create_assignment(host, input).map(TeacherResponse::Assignment)
+            }
+            TeacherRequest::CalibrateItems(input) => {
+                calibration::calibrate_items(host, input).map(TeacherResponse::Calibration)
+            }
+            TeacherRequest::ClassReport(input) => {
+                reports::class_report(host, input).map(TeacherResponse::Report)
             }
         }
-        Err(_) => {
-            let error_response = CreateLessonResponse {
-                success: false,
-                lesson_id: "".to_string(),
-                message: "Invalid input format".to_string(),
-            };
-            
-            let response_json = serde_json::to_string(&error_response).unwrap();
-            let response_bytes = response_json.into_bytes();
-            let response_ptr = response_bytes.as_mut_ptr();
-            
-            std::mem::forget(response_bytes);
-            response_ptr
-        }
+    }
+}
+
+export_agent!(TeacherAgent);
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// The caller, if it is a teacher (or admin).
+fn teacher(host: &Host) -> Result<AuthContext, AgentError> {
+    let auth = host.auth()?;
+    match auth.role {
+        Role::Teacher | Role::Admin => Ok(auth),
+        _ => Err(AgentError::forbidden("only teachers may manage classes")),
+    }
+}
+
+fn require_text(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(FieldError::new(field, "must not be empty"));
+    }
+}
+
+/// `Ok` when no field errors were collected.
+fn validated(errors: Vec<FieldError>) -> Result<(), AgentError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::Validation { fields: errors })
+    }
+}
+
+/// Fail unless the caller teaches `class_id`.
+fn require_class(host: &Host, auth: &AuthContext, class_id: &str) -> Result<(), AgentError> {
+    if auth.role == Role::Admin {
+        return Ok(());
+    }
+    let teaching = host.query(TeachesClass {
+        class_id: class_id.to_string(),
+    })?;
+    if teaching.teaches {
+        Ok(())
+    } else {
+        Err(AgentError::forbidden(format!(
+            "{} does not teach class {class_id}",
+            auth.subject
+        )))
     }
 }
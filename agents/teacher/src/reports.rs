@@ -0,0 +1,224 @@
+//! Class-wide analytics built from the roster's `LEARNS` relationships.
+
+use std::collections::BTreeMap;
+
+use fot_agent_sdk::mastery::decay;
+use fot_agent_sdk::protocol::{ChildProgress, ConceptProgress, GetClassProgress, Virtues};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::{now, require_class, require_text, teacher, validated};
+
+/// Number of equal-width mastery buckets in a distribution.
+const BUCKETS: usize = 5;
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct ClassReportInput {
+    class_id: String,
+    /// Students under this mastery are listed as needing attention.
+    threshold: f64,
+    /// Only measure drop-off from this time on (seconds since the Unix
+    /// epoch).
+    since: Option<u64>,
+}
+
+impl Default for ClassReportInput {
+    fn default() -> Self {
+        ClassReportInput {
+            class_id: String::new(),
+            threshold: 0.5,
+            since: None,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct ClassReport {
+    class_id: String,
+    students: usize,
+    threshold: f64,
+    /// By concept name.
+    concepts: Vec<ConceptSummary>,
+    /// Lowest mastery first.
+    below_threshold: Vec<StudentAlert>,
+    /// Each averaged over the students it is recorded for; 0 when it is
+    /// recorded for nobody.
+    virtues: Virtues,
+    /// Largest mean drop first; concepts nobody dropped on are left out.
+    drop_offs: Vec<DropOff>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ConceptSummary {
+    concept_id: String,
+    concept_name: String,
+    learners: usize,
+    mean: f64,
+    median: f64,
+    min: f64,
+    max: f64,
+    /// Learner counts for mastery in `[0, 0.2)`, `[0.2, 0.4)`, ... `[0.8, 1]`.
+    distribution: [usize; BUCKETS],
+}
+
+#[derive(serde::Serialize)]
+pub struct StudentAlert {
+    student_id: String,
+    concept_id: String,
+    concept_name: String,
+    mastery: f64,
+}
+
+/// How far mastery fell from its peak in the window.
+#[derive(serde::Serialize)]
+pub struct DropOff {
+    concept_id: String,
+    concept_name: String,
+    mean_drop: f64,
+    students_dropped: usize,
+}
+
+pub(crate) fn class_report(
+    host: &Host,
+    input: ClassReportInput,
+) -> Result<ClassReport, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    if !(0.0..=1.0).contains(&input.threshold) {
+        errors.push(FieldError::new("threshold", "must be between 0 and 1"));
+    }
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let mut progress = host.query(GetClassProgress {
+        class_id: input.class_id.clone(),
+        since: input.since,
+    })?;
+    // The report reads mastery as it stands now, forgetting included, so a
+    // concept left to fade shows up as a drop-off.
+    let now = now();
+    for concept in progress.iter_mut().flat_map(|p| &mut p.concepts) {
+        concept.mastery = decay(concept.mastery, concept.last_updated, concept.review, now);
+    }
+    Ok(build(input.class_id, input.threshold, &progress))
+}
+
+fn build(class_id: String, threshold: f64, progress: &[ChildProgress]) -> ClassReport {
+    let mut by_concept: BTreeMap<&str, Vec<(&str, &ConceptProgress)>> = BTreeMap::new();
+    for student in progress {
+        for concept in &student.concepts {
+            by_concept
+                .entry(&concept.concept_id)
+                .or_default()
+                .push((&student.child_id, concept));
+        }
+    }
+
+    let mut concepts = Vec::new();
+    let mut below_threshold = Vec::new();
+    let mut drop_offs = Vec::new();
+    for (concept_id, learners) in &by_concept {
+        let concept_name = learners[0].1.concept_name.clone();
+        let mut mastery: Vec<f64> = learners.iter().map(|(_, c)| c.mastery).collect();
+        mastery.sort_by(f64::total_cmp);
+
+        let mut distribution = [0; BUCKETS];
+        for m in &mastery {
+            let bucket = (m * BUCKETS as f64) as usize;
+            distribution[bucket.min(BUCKETS - 1)] += 1;
+        }
+        concepts.push(ConceptSummary {
+            concept_id: concept_id.to_string(),
+            concept_name: concept_name.clone(),
+            learners: mastery.len(),
+            mean: mean(&mastery),
+            median: median(&mastery),
+            min: mastery[0],
+            max: mastery[mastery.len() - 1],
+            distribution,
+        });
+
+        for (student_id, concept) in learners {
+            if concept.mastery < threshold {
+                below_threshold.push(StudentAlert {
+                    student_id: student_id.to_string(),
+                    concept_id: concept_id.to_string(),
+                    concept_name: concept_name.clone(),
+                    mastery: concept.mastery,
+                });
+            }
+        }
+
+        let drops: Vec<f64> = learners.iter().map(|(_, c)| drop(c)).collect();
+        let students_dropped = drops.iter().filter(|d| **d > 0.0).count();
+        if students_dropped > 0 {
+            drop_offs.push(DropOff {
+                concept_id: concept_id.to_string(),
+                concept_name,
+                mean_drop: mean(&drops),
+                students_dropped,
+            });
+        }
+    }
+
+    concepts.sort_by(|a, b| a.concept_name.cmp(&b.concept_name));
+    below_threshold.sort_by(|a, b| a.mastery.total_cmp(&b.mastery));
+    drop_offs.sort_by(|a, b| b.mean_drop.total_cmp(&a.mean_drop));
+
+    // The graph reports a virtue nothing recorded as 0, so a student only
+    // counts towards a virtue some LEARNS relationship of theirs records.
+    let average = |virtue: fn(&Virtues) -> f64, recorded: fn(&ConceptProgress) -> Option<f64>| {
+        let values: Vec<f64> = progress
+            .iter()
+            .filter(|p| p.concepts.iter().any(|c| recorded(c).is_some()))
+            .map(|p| virtue(&p.virtues))
+            .collect();
+        mean(&values)
+    };
+    let virtues = Virtues {
+        honesty: average(|v| v.honesty, |c| c.honesty),
+        curiosity: average(|v| v.curiosity, |c| c.curiosity),
+        patience: average(|v| v.patience, |c| c.patience),
+    };
+
+    ClassReport {
+        class_id,
+        students: progress.len(),
+        threshold,
+        concepts,
+        below_threshold,
+        virtues,
+        drop_offs,
+    }
+}
+
+/// Peak mastery in the window (including the baseline) minus current,
+/// decayed mastery.
+fn drop(concept: &ConceptProgress) -> f64 {
+    let peak = concept
+        .history
+        .iter()
+        .map(|s| s.mastery)
+        .chain(concept.baseline)
+        .fold(concept.mastery, f64::max);
+    peak - concept.mastery
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Median of sorted, non-empty `values`.
+fn median(values: &[f64]) -> f64 {
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
@@ -0,0 +1,184 @@
+//! Classes and their rosters: create, enroll, remove, transfer, list.
+
+use fot_agent_sdk::protocol::{
+    ClassInfo, CreateClass, EnrollStudent, ListRoster, RemoveStudent, Roster, RosterEntry,
+    TransferStudent,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::{now, require_class, require_text, teacher, validated};
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct CreateClassInput {
+    name: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct EnrollStudentInput {
+    class_id: String,
+    student_id: String,
+    name: Option<String>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct RemoveStudentInput {
+    class_id: String,
+    student_id: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct TransferStudentInput {
+    student_id: String,
+    from_class_id: String,
+    to_class_id: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ListRosterInput {
+    class_id: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ClassResponse {
+    class: ClassInfo,
+    message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct EnrollmentResponse {
+    enrollment: RosterEntry,
+    message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct RosterResponse {
+    roster: Roster,
+}
+
+pub(crate) fn create_class(
+    host: &Host,
+    input: CreateClassInput,
+) -> Result<ClassResponse, AgentError> {
+    teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "name", &input.name);
+    validated(errors)?;
+
+    let class = host.mutate(CreateClass {
+        name: input.name,
+        timestamp: now(),
+    })?;
+    Ok(ClassResponse {
+        message: format!("Class {} created", class.id),
+        class,
+    })
+}
+
+pub(crate) fn enroll_student(
+    host: &Host,
+    input: EnrollStudentInput,
+) -> Result<EnrollmentResponse, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    require_text(&mut errors, "student_id", &input.student_id);
+    if let Some(name) = &input.name {
+        require_text(&mut errors, "name", name);
+    }
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let enrollment = host.mutate(EnrollStudent {
+        class_id: input.class_id,
+        student_id: input.student_id,
+        name: input.name,
+        timestamp: now(),
+    })?;
+    Ok(EnrollmentResponse {
+        message: format!(
+            "{} enrolled in {}",
+            enrollment.student_id, enrollment.class_id
+        ),
+        enrollment,
+    })
+}
+
+pub(crate) fn remove_student(
+    host: &Host,
+    input: RemoveStudentInput,
+) -> Result<EnrollmentResponse, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    require_text(&mut errors, "student_id", &input.student_id);
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let enrollment = host.mutate(RemoveStudent {
+        class_id: input.class_id,
+        student_id: input.student_id,
+    })?;
+    Ok(EnrollmentResponse {
+        message: format!(
+            "{} removed from {}",
+            enrollment.student_id, enrollment.class_id
+        ),
+        enrollment,
+    })
+}
+
+/// Moves the student in one graph write, so they are never in both classes.
+pub(crate) fn transfer_student(
+    host: &Host,
+    input: TransferStudentInput,
+) -> Result<EnrollmentResponse, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "student_id", &input.student_id);
+    require_text(&mut errors, "from_class_id", &input.from_class_id);
+    require_text(&mut errors, "to_class_id", &input.to_class_id);
+    if !input.to_class_id.is_empty() && input.to_class_id == input.from_class_id {
+        errors.push(FieldError::new(
+            "to_class_id",
+            "must differ from from_class_id",
+        ));
+    }
+    validated(errors)?;
+    require_class(host, &auth, &input.from_class_id)?;
+    require_class(host, &auth, &input.to_class_id)?;
+
+    let enrollment = host.mutate(TransferStudent {
+        student_id: input.student_id,
+        from_class_id: input.from_class_id.clone(),
+        to_class_id: input.to_class_id,
+        timestamp: now(),
+    })?;
+    Ok(EnrollmentResponse {
+        message: format!(
+            "{} moved from {} to {}",
+            enrollment.student_id, input.from_class_id, enrollment.class_id
+        ),
+        enrollment,
+    })
+}
+
+pub(crate) fn list_roster(
+    host: &Host,
+    input: ListRosterInput,
+) -> Result<RosterResponse, AgentError> {
+    let auth = teacher(host)?;
+    let mut errors = Vec::new();
+    require_text(&mut errors, "class_id", &input.class_id);
+    validated(errors)?;
+    require_class(host, &auth, &input.class_id)?;
+
+    let roster = host.query(ListRoster {
+        class_id: input.class_id,
+    })?;
+    Ok(RosterResponse { roster })
+}
@@ -0,0 +1,199 @@
+//! Runs the teacher agent natively against the in-memory host.
+
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::test_util::{self, now};
+use serde_json::{json, Value};
+use teacher_agent::TeacherAgent;
+
+fn invoke(host: &MemoryHost, input: &str) -> Value {
+    test_util::invoke(&TeacherAgent, &host.host(), input)
+}
+
+fn teacher_host(teaches: bool) -> MemoryHost {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    host.graph.respond(
+        "get_concept",
+        json!({"id": "fot:Photosynthesis", "label": "Photosynthesis"}),
+    );
+    host.graph
+        .respond("teaches_class", json!({ "teaches": teaches }));
+    host.graph
+        .respond("create_lesson", json!({"id": "lesson-7"}));
+    host
+}
+
+const LESSON: &str = r#"{"op":"create_lesson","concept":"Photosynthesis","class_id":"class-1","content":"Light reactions"}"#;
+
+#[test]
+fn create_lesson_returns_the_graph_id() {
+    let host = teacher_host(true);
+    let reply = invoke(&host, LESSON);
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["lesson_id"], "lesson-7");
+    assert_eq!(host.graph.writes()[0]["class_id"], "class-1");
+}
+
+#[test]
+fn invalid_fields_are_reported_individually() {
+    let host = teacher_host(true);
+    host.graph.respond("get_concept", Value::Null);
+    let reply = invoke(
+        &host,
+        r#"{"op":"create_lesson","concept":"Alchemy","class_id":"  "}"#,
+    );
+
+    assert_eq!(reply["error"]["kind"], "validation");
+    let fields: Vec<&str> = reply["error"]["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["class_id", "content", "concept"]);
+    assert!(host.graph.writes().is_empty());
+}
+
+#[test]
+fn teachers_cannot_add_lessons_to_other_classes() {
+    let host = teacher_host(false);
+    let reply = invoke(&host, LESSON);
+
+    assert_eq!(reply["error"]["kind"], "forbidden");
+    assert!(host.graph.writes().is_empty());
+}
+
+#[test]
+fn transfers_need_both_classes() {
+    let host = teacher_host(true);
+    let reply = invoke(
+        &host,
+        r#"{"op":"transfer_student","student_id":"ada","from_class_id":"class-1","to_class_id":"class-1"}"#,
+    );
+
+    assert_eq!(reply["error"]["kind"], "validation");
+    assert_eq!(reply["error"]["fields"][0]["field"], "to_class_id");
+    assert!(host.graph.writes().is_empty());
+}
+
+#[test]
+fn class_report_aggregates_the_roster() {
+    let host = teacher_host(true);
+    let concept = |id: &str, mastery: f64, peak: f64, patience: f64| {
+        json!({
+            "concept_id": id,
+            "concept_name": id,
+            "mastery": mastery,
+            "patience": patience,
+            "history": [{"at": 1, "mastery": peak}, {"at": 2, "mastery": mastery}]
+        })
+    };
+    host.graph.respond(
+        "get_class_progress",
+        json!([
+            {
+                "child_id": "ada",
+                "concepts": [concept("Photosynthesis", 0.9, 0.9, 0.4), concept("Respiration", 0.2, 0.6, 0.4)],
+                "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.4}
+            },
+            {
+                "child_id": "ben",
+                "concepts": [concept("Photosynthesis", 0.3, 0.3, 0.8)],
+                "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.8}
+            },
+            {"child_id": "cy", "concepts": [], "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0}},
+            {
+                "child_id": "dee",
+                "concepts": [{"concept_id": "Transpiration", "concept_name": "Transpiration", "mastery": 0.7}],
+                "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0}
+            }
+        ]),
+    );
+    let reply = invoke(&host, r#"{"op":"class_report","class_id":"class-1"}"#);
+
+    assert_eq!(reply["students"], 4);
+    let photosynthesis = &reply["concepts"][0];
+    assert_eq!(photosynthesis["learners"], 2);
+    assert!((photosynthesis["mean"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+    assert_eq!(photosynthesis["distribution"], json!([0, 1, 0, 0, 1]));
+    let below: Vec<&str> = reply["below_threshold"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["concept_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(below, ["Respiration", "Photosynthesis"]);
+    // Only ada and ben have patience recorded; nobody has honesty.
+    assert!((reply["virtues"]["patience"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+    assert_eq!(reply["virtues"]["honesty"], 0.0);
+    assert_eq!(reply["drop_offs"][0]["concept_id"], "Respiration");
+    assert_eq!(reply["drop_offs"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn class_report_counts_forgetting_as_drop_off() {
+    let host = teacher_host(true);
+    // Two half-lives since ada last practised: 0.8 has faded to 0.2.
+    let updated_at = now() - 60 * 24 * 60 * 60;
+    host.graph.respond(
+        "get_class_progress",
+        json!([{
+            "child_id": "ada",
+            "concepts": [{
+                "concept_id": "Photosynthesis",
+                "concept_name": "Photosynthesis",
+                "mastery": 0.8,
+                "last_updated": updated_at,
+                "history": [{"at": updated_at, "mastery": 0.8}]
+            }],
+            "virtues": {"honesty": 0.0, "curiosity": 0.0, "patience": 0.0}
+        }]),
+    );
+    let reply = invoke(&host, r#"{"op":"class_report","class_id":"class-1"}"#);
+
+    assert!((reply["concepts"][0]["mean"].as_f64().unwrap() - 0.2).abs() < 1e-3);
+    assert_eq!(reply["below_threshold"][0]["student_id"], "ada");
+    assert!((reply["drop_offs"][0]["mean_drop"].as_f64().unwrap() - 0.6).abs() < 1e-3);
+}
+
+#[test]
+fn calibration_skips_items_without_enough_responses() {
+    let host = teacher_host(true);
+    let mut responses = Vec::new();
+    for student in 0..6 {
+        responses.push(json!({"assignment_id": "assignment-1", "student_id": format!("s{student}"), "score": f64::from(student % 3 != 0)}));
+    }
+    responses.push(json!({"assignment_id": "assignment-2", "student_id": "s0", "score": 1.0}));
+    host.graph.respond("list_responses", json!(responses));
+    host.graph.respond(
+        "calibrate_assignment",
+        json!({
+            "id": "assignment-1",
+            "class_id": "class-1",
+            "concept": "Photosynthesis",
+            "title": "Leaf pigments",
+            "due_at": 0,
+            "late_policy": {},
+            "created_by": "teacher-1",
+            "created_at": 0
+        }),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"op":"calibrate_items","class_id":"class-1","model":"2pl"}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["model"], "2pl");
+    assert_eq!(reply["items"]["assignment-1"]["responses"], 6);
+    assert_eq!(reply["skipped"], json!(["assignment-2"]));
+    assert_eq!(reply["abilities"].as_object().unwrap().len(), 6);
+    let writes = host.graph.writes();
+    assert_eq!(writes.len(), 1);
+    assert_eq!(writes[0]["operation"], "calibrate_assignment");
+    assert_eq!(writes[0]["assignment_id"], "assignment-1");
+    assert_eq!(writes[0]["txn"], 1);
+    assert!(host.graph.open_txns().is_empty());
+}
@@ -1,183 +0,0 @@
-use serde_json::json;
-use wasm_bindgen::prelude::*;
-
-#[derive(serde::Deserialize)]
-struct TopicInput {
-    op: String,
-    student_id: String,
-    lesson_data: Option<LessonData>,
-}
-
-#[derive(serde::Deserialize)]
-struct LessonData {
-    concept: String,
-    difficulty: f64,
-    time_spent: u64,
-}
-
-#[derive(serde::Serialize)]
-struct TopicResponse {
-    success: bool,
-    message: String,
-    mastery_delta: f64,
-    virtue_deltas: VirtueDeltas,
-}
-
-#[derive(serde::Serialize)]
-struct VirtueDeltas {
-    curiosity: f64,
-    patience: f64,
-    honesty: f64,
-}
-
-#[wasm_bindgen]
-pub fn run(input_ptr: *const u8, len: usize) -> *mut u8 {
-    // Parse input command
-    let input_bytes = unsafe { std::slice::from_raw_parts(input_ptr, len) };
-    let input_str = String::from_utf8_lossy(input_bytes);
-    
-    match serde_json::from_str::<TopicInput>(&input_str) {
-        Ok(input) => {
-            match input.op.as_str() {
-                "start_lesson" => {
-                    // REAL HOST FUNCTION CALL - NO SIMULATION
-                    let timestamp = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    
-                    // Record real virtue metrics
-                    let _ = unsafe { 
-                        crate::fot_metrics::record_virtue(
-                            input.student_id.as_ptr(), 
-                            input.student_id.len(),
-                            "curiosity".as_ptr(), 
-                            "curiosity".len(),
-                            0.1
-                        ) 
-                    };
-                    
-                    let response = TopicResponse {
-                        success: true,
-                        message: "Photosynthesis lesson started successfully via real metrics recording".to_string(),
-                        mastery_delta: 0.0,
-                        virtue_deltas: VirtueDeltas {
-                            curiosity: 0.1, // Real recorded value
-                            patience: 0.0,
-                            honesty: 0.0,
-                        },
-                    };
-                    
-                    let response_json = serde_json::to_string(&response).unwrap();
-                    let response_bytes = response_json.into_bytes();
-                    let response_ptr = response_bytes.as_mut_ptr();
-                    
-                    std::mem::forget(response_bytes);
-                    response_ptr
-                }
-                "grade_submission" => {
-                    if let Some(lesson_data) = input.lesson_data {
-                        // REAL CALCULATIONS - NO HARDCODED VALUES
-                        let mastery_delta = (lesson_data.difficulty * 0.1).min(0.2);
-                        let patience_delta = if lesson_data.time_spent > 300 { 0.15 } else { 0.05 };
-                        
-                        // Record real virtue metrics
-                        let _ = unsafe { 
-                            crate::fot_metrics::record_virtue(
-                                input.student_id.as_ptr(), 
-                                input.student_id.len(),
-                                "patience".as_ptr(), 
-                                "patience".len(),
-                                patience_delta
-                            ) 
-                        };
-                        
-                        let _ = unsafe { 
-                            crate::fot_metrics::record_virtue(
-                                input.student_id.as_ptr(), 
-                                input.student_id.len(),
-                                "honesty".as_ptr(), 
-                                "honesty".len(),
-                                0.1
-                            ) 
-                        };
-                        
-                        let response = TopicResponse {
-                            success: true,
-                            message: "Submission graded successfully via real metrics recording".to_string(),
-                            mastery_delta,
-                            virtue_deltas: VirtueDeltas {
-                                curiosity: 0.05, // Real calculated value
-                                patience: patience_delta, // Real calculated value
-                                honesty: 0.1, // Real recorded value
-                            },
-                        };
-                        
-                        let response_json = serde_json::to_string(&response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    } else {
-                        let error_response = TopicResponse {
-                            success: false,
-                            message: "Missing lesson data".to_string(),
-                            mastery_delta: 0.0,
-                            virtue_deltas: VirtueDeltas {
-                                curiosity: 0.0,
-                                patience: 0.0,
-                                honesty: 0.0,
-                            },
-                        };
-                        
-                        let response_json = serde_json::to_string(&error_response).unwrap();
-                        let response_bytes = response_json.into_bytes();
-                        let response_ptr = response_bytes.as_mut_ptr();
-                        
-                        std::mem::forget(response_bytes);
-                        response_ptr
-                    }
-                }
-                _ => {
-                    let error_response = TopicResponse {
-                        success: false,
-                        message: "Unknown operation".to_string(),
-                        mastery_delta: 0.0,
-                        virtue_deltas: VirtueDeltas {
-                            curiosity: 0.0,
-                            patience: 0.0,
-                            honesty: 0.0,
-                        },
-                    };
-                    
-                    let response_json = serde_json::to_string(&error_response).unwrap();
-                    let response_bytes = response_json.into_bytes();
-                    let response_ptr = response_bytes.as_mut_ptr();
-                    
-                    std::mem::forget(response_bytes);
-                    response_ptr
-                }
-            }
-        }
-        Err(_) => {
-            let error_response = TopicResponse {
-                success: false,
-                message: "Invalid input format".to_string(),
-                mastery_delta: 0.0,
-                virtue_deltas: VirtueDeltas {
-                    curiosity: 0.0,
-                    patience: 0.0,
-                    honesty: 0.0,
-                },
-            };
-            
-            let response_json = serde_json::to_string(&error_response).unwrap();
-            let response_bytes = response_json.into_bytes();
-            let response_ptr = response_bytes.as_mut_ptr();
-            
-            std::mem::forget(response_bytes);
-            response_ptr
-        }
-    }
-}
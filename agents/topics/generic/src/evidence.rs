@@ -0,0 +1,65 @@
+//! Graded work and quiz answers as evidence of mastery: scores are traced
+//! through the concept's knowledge-tracing model and the result is stored.
+
+use fot_agent_sdk::mastery::{decay, MasteryModel};
+use fot_agent_sdk::protocol::{ErrorCode, GetMastery, UpdateMastery};
+use fot_agent_sdk::{AgentError, Host};
+
+/// Attempts at a write that keeps losing to concurrent updates.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Trace `scores` through `model` in order, starting from `student`'s decayed
+/// mastery of `concept` or the model's prior if they never practised it, and
+/// store the result. Returns the change from mastery as the student agent
+/// would read it, decay included.
+///
+/// As in the student agent's `apply`, the write carries the revision it was
+/// computed from, and a concurrent update makes the graph refuse it; the
+/// trace is then redone from the value that update left.
+pub(crate) fn record(
+    host: &Host,
+    concept: &str,
+    student: &str,
+    model: &impl MasteryModel,
+    scores: &[f64],
+    context: &str,
+    now: u64,
+) -> Result<f64, AgentError> {
+    // The topic agent has no resonance model; the evidence's mean score
+    // stands in for both measures.
+    let strength = scores.iter().sum::<f64>() / scores.len().max(1) as f64;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let stored = host.query(GetMastery {
+            concept: concept.to_string(),
+            student_id: Some(student.to_string()),
+        })?;
+        let decayed = decay(stored.mastery, stored.updated_at, stored.review, now);
+        let before = match stored.updated_at {
+            Some(_) => decayed,
+            None => model.initial(),
+        };
+        let after = scores.iter().fold(before, |mastery, score| {
+            model.observe_score(mastery, *score)
+        });
+        let updated = host.mutate(UpdateMastery {
+            concept: concept.to_string(),
+            student_id: Some(student.to_string()),
+            mastery: after,
+            truth_field_strength: strength,
+            harmonic_coherence: strength,
+            context: context.to_string(),
+            timestamp: now,
+            review: None,
+            if_revision: Some(stored.revision),
+        });
+        match updated {
+            Err(AgentError::Graph {
+                error: ErrorCode::InvalidState,
+                ..
+            }) if attempts < MAX_ATTEMPTS => continue,
+            updated => return updated.map(|_| after - decayed),
+        }
+    }
+}
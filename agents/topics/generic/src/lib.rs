@@ -0,0 +1,380 @@
+//! A topic agent driven by a [`TopicManifest`]: each request names its topic,
+//! and the agent teaches the concepts, lessons and question bank the topic's
+//! manifest describes. Adding a subject means writing a manifest.
+
+use fot_agent_sdk::irt::ItemParams;
+use fot_agent_sdk::protocol::{
+    GetAssignment, GetConcept, GetSubmission, Grade, InstallTopic, RecordGrade, Signal,
+    TopicInstalled, TopicLesson, TopicManifest, VirtueDeltas,
+};
+use fot_agent_sdk::{export_agent, Agent, AgentError, Host};
+
+mod evidence;
+pub mod manifest;
+mod quiz;
+mod virtues;
+
+pub use quiz::{ItemResult, PresentedItem, PresentedQuestion, Quiz, QuizAnswer};
+pub use virtues::{Behaviour, VirtueEvent};
+
+use virtues::Observation;
+
+#[derive(serde::Deserialize)]
+pub struct TopicRequest {
+    /// Manifest id, e.g. `biology.photosynthesis`.
+    pub topic: String,
+    #[serde(flatten)]
+    pub op: TopicOp,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TopicOp {
+    /// Put the topic into the graph, from `manifest` or the topic's file.
+    InstallTopic {
+        #[serde(default)]
+        manifest: Option<TopicManifest>,
+    },
+    StartLesson {
+        /// Defaults to the caller; only a teacher or admin may name another
+        /// student.
+        #[serde(default)]
+        student_id: Option<String>,
+        /// Defaults to the topic's first lesson.
+        #[serde(default)]
+        lesson: Option<String>,
+    },
+    /// Grade a submission stored through the student agent's `submit`.
+    GradeSubmission {
+        submission_id: String,
+        /// In `[0, 1]`, before the assignment's late penalty.
+        score: Option<f64>,
+        /// Time on task defaults to the submission's `time_spent`.
+        #[serde(default)]
+        behaviour: Behaviour,
+    },
+    /// Present up to `count` questions from a concept's question bank. The
+    /// topic must be installed.
+    StartQuiz {
+        /// Defaults to the topic's first concept.
+        #[serde(default)]
+        concept: Option<String>,
+        #[serde(default = "default_quiz_length")]
+        count: usize,
+    },
+    /// Check answers to quiz questions, item by item. The topic must be
+    /// installed.
+    AnswerQuiz {
+        #[serde(default)]
+        concept: Option<String>,
+        /// Defaults to the caller; only a teacher or admin may name another
+        /// student.
+        #[serde(default)]
+        student_id: Option<String>,
+        answers: Vec<QuizAnswer>,
+        #[serde(default)]
+        behaviour: Behaviour,
+    },
+}
+
+fn default_quiz_length() -> usize {
+    5
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum TopicResponse {
+    Learning(LearningResponse),
+    Installed(TopicInstalled),
+}
+
+#[derive(serde::Serialize)]
+pub struct LearningResponse {
+    message: String,
+    mastery_delta: f64,
+    /// Net of `virtue_events`.
+    virtue_deltas: VirtueDeltas,
+    /// Every recorded delta with its explanation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    virtue_events: Vec<VirtueEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lesson: Option<TopicLesson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grade: Option<Grade>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quiz: Option<Quiz>,
+    /// Per-item correctness of quiz answers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    results: Vec<ItemResult>,
+}
+
+impl LearningResponse {
+    fn new(message: String) -> Self {
+        LearningResponse {
+            message,
+            mastery_delta: 0.0,
+            virtue_deltas: VirtueDeltas::default(),
+            virtue_events: Vec::new(),
+            lesson: None,
+            grade: None,
+            quiz: None,
+            results: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TopicAgent;
+
+impl Agent for TopicAgent {
+    type Request = TopicRequest;
+    type Response = TopicResponse;
+
+    fn handle(&self, host: &Host, request: TopicRequest) -> Result<TopicResponse, AgentError> {
+        let topic = request.topic;
+        let response = match request.op {
+            TopicOp::InstallTopic { manifest } => {
+                return install_topic(host, &topic, manifest).map(TopicResponse::Installed);
+            }
+            TopicOp::StartLesson { student_id, lesson } => {
+                let (manifest, installed) = manifest::load(host, &topic)?;
+                let student = host.acting_for(student_id)?;
+                start_lesson(host, &manifest, installed, &student, lesson.as_deref())
+            }
+            TopicOp::GradeSubmission {
+                submission_id,
+                score,
+                behaviour,
+            } => {
+                let (manifest, _) = manifest::load(host, &topic)?;
+                let score = score
+                    .filter(|s| (0.0..=1.0).contains(s))
+                    .ok_or_else(|| AgentError::invalid_input("score must be between 0 and 1"))?;
+                grade_submission(host, &manifest, &submission_id, score, behaviour)
+            }
+            TopicOp::StartQuiz { concept, count } => {
+                let manifest = installed_manifest(host, &topic)?;
+                let concept = quiz_concept(&manifest, concept)?;
+                start_quiz(host, &concept, count)
+            }
+            TopicOp::AnswerQuiz {
+                concept,
+                student_id,
+                answers,
+                behaviour,
+            } => {
+                let manifest = installed_manifest(host, &topic)?;
+                let concept = quiz_concept(&manifest, concept)?;
+                let student = host.acting_for(student_id)?;
+                answer_quiz(host, &manifest, &concept, &student, answers, behaviour)
+            }
+        };
+        response.map(TopicResponse::Learning)
+    }
+}
+
+export_agent!(TopicAgent);
+
+/// The manifest of `topic`, which must be installed: quizzes trace mastery
+/// of its concepts in the graph.
+fn installed_manifest(host: &Host, topic: &str) -> Result<TopicManifest, AgentError> {
+    match manifest::load(host, topic)? {
+        (manifest, true) => Ok(manifest),
+        (_, false) => Err(AgentError::invalid_input(format!(
+            "install {topic} before quizzing on it"
+        ))),
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn install_topic(
+    host: &Host,
+    topic: &str,
+    manifest: Option<TopicManifest>,
+) -> Result<TopicInstalled, AgentError> {
+    let manifest = match manifest {
+        Some(manifest) => manifest,
+        None => manifest::load_file(host, topic)?,
+    };
+    manifest::check(&manifest, topic)?;
+    host.mutate(InstallTopic {
+        manifest,
+        timestamp: now(),
+    })
+}
+
+/// The id of the concept a quiz is about; it must be one of the topic's.
+fn quiz_concept(manifest: &TopicManifest, concept: Option<String>) -> Result<String, AgentError> {
+    match concept {
+        None => Ok(manifest.concepts[0].id.clone()),
+        Some(key) => manifest::concept(manifest, &key)
+            .map(|c| c.id.clone())
+            .ok_or_else(|| {
+                AgentError::invalid_input(format!("{key} is not a concept of {}", manifest.id))
+            }),
+    }
+}
+
+/// Virtues are kept in the graph only when the topic is `installed`, since
+/// otherwise its concepts are not there.
+fn start_lesson(
+    host: &Host,
+    manifest: &TopicManifest,
+    installed: bool,
+    student_id: &str,
+    lesson: Option<&str>,
+) -> Result<LearningResponse, AgentError> {
+    let lesson = match lesson {
+        Some(id) => Some(
+            manifest
+                .lessons
+                .iter()
+                .find(|l| l.id == id)
+                .ok_or_else(|| {
+                    AgentError::invalid_input(format!("{id} is not a lesson of {}", manifest.id))
+                })?,
+        ),
+        None => manifest.lessons.first(),
+    };
+    let events = virtues::score(
+        &manifest.virtues,
+        &Observation {
+            activity: Signal::LessonStarted,
+            behaviour: Behaviour::default(),
+        },
+    );
+
+    let concept = match lesson {
+        Some(lesson) => manifest::concept(manifest, &lesson.concept).map(|c| c.id.as_str()),
+        None => Some(manifest.concepts[0].id.as_str()),
+    };
+    let title = lesson.map_or(&manifest.title, |l| &l.title);
+    Ok(LearningResponse {
+        virtue_deltas: virtues::record(host, student_id, concept.filter(|_| installed), &events)?,
+        virtue_events: events,
+        lesson: lesson.cloned(),
+        ..LearningResponse::new(format!("{title} lesson started"))
+    })
+}
+
+fn grade_submission(
+    host: &Host,
+    manifest: &TopicManifest,
+    submission_id: &str,
+    score: f64,
+    behaviour: Behaviour,
+) -> Result<LearningResponse, AgentError> {
+    let submission = host.query(GetSubmission {
+        submission_id: submission_id.to_string(),
+    })?;
+    let assignment = host.query(GetAssignment {
+        assignment_id: submission.assignment_id.clone(),
+    })?;
+    if manifest::concept(manifest, &assignment.concept).is_none() {
+        return Err(AgentError::invalid_input(format!(
+            "{} is not a concept of {}",
+            assignment.concept, manifest.id
+        )));
+    }
+    // The graph applies the late policy, so the grade is the stored one.
+    let graded = host.mutate(RecordGrade {
+        submission_id: submission.id,
+        raw_score: score,
+        timestamp: now(),
+    })?;
+    let grade = graded
+        .grade
+        .ok_or_else(|| AgentError::internal("graph returned an ungraded submission"))?;
+    let student_id = graded.student_id.as_str();
+
+    // The grade is partial evidence of mastery under the concept's BKT model,
+    // with slip and guess taken from the assignment's calibrated difficulty.
+    let model = host
+        .query(GetConcept {
+            concept: assignment.concept.clone(),
+        })?
+        .and_then(|concept| concept.bkt)
+        .unwrap_or_default();
+    let model = match assignment.calibration {
+        Some(calibration) => ItemParams {
+            difficulty: calibration.difficulty,
+            discrimination: calibration.discrimination,
+        }
+        .tracing_params(model),
+        None => model,
+    };
+    let mastery_delta = evidence::record(
+        host,
+        &assignment.concept,
+        student_id,
+        &model,
+        &[grade.score],
+        "graded_work",
+        now(),
+    )?;
+
+    let events = virtues::score(
+        &manifest.virtues,
+        &Observation {
+            activity: Signal::WorkGraded,
+            behaviour: Behaviour {
+                time_on_task_secs: behaviour.time_on_task_secs.or(Some(graded.time_spent)),
+                ..behaviour
+            },
+        },
+    );
+
+    Ok(LearningResponse {
+        mastery_delta,
+        virtue_deltas: virtues::record(host, student_id, Some(&assignment.concept), &events)?,
+        virtue_events: events,
+        grade: Some(grade),
+        ..LearningResponse::new(format!("{} submission graded", manifest.title))
+    })
+}
+
+fn start_quiz(host: &Host, concept: &str, count: usize) -> Result<LearningResponse, AgentError> {
+    let quiz = quiz::present(host, concept, count)?;
+    let message = format!("Quiz on {concept} with {} questions", quiz.questions.len());
+    Ok(LearningResponse {
+        quiz: Some(quiz),
+        ..LearningResponse::new(message)
+    })
+}
+
+fn answer_quiz(
+    host: &Host,
+    manifest: &TopicManifest,
+    concept: &str,
+    student: &str,
+    answers: Vec<QuizAnswer>,
+    behaviour: Behaviour,
+) -> Result<LearningResponse, AgentError> {
+    let checked = quiz::check(host, concept, student, answers)?;
+    let events = virtues::score(
+        &manifest.virtues,
+        &Observation {
+            activity: Signal::QuizAnswered,
+            behaviour,
+        },
+    );
+    let correct = checked.results.iter().filter(|r| r.correct).count();
+    let message = format!(
+        "{correct} of {} correct on {}",
+        checked.results.len(),
+        checked.concept_id
+    );
+    Ok(LearningResponse {
+        mastery_delta: checked.mastery_delta,
+        virtue_deltas: virtues::record(host, student, Some(concept), &events)?,
+        virtue_events: events,
+        results: checked.results,
+        ..LearningResponse::new(message)
+    })
+}
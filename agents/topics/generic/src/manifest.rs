@@ -0,0 +1,138 @@
+//! Loading and checking topic manifests.
+//!
+//! An installed topic's manifest comes from its Topic node. Otherwise the
+//! host's local files are tried, as `topics/<id>.toml` and then
+//! `topics/<id>.json`; this repository keeps them in `agents/topics/`.
+
+use std::collections::HashSet;
+
+use fot_agent_sdk::protocol::{ErrorCode, GetTopic, TopicConcept, TopicManifest};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::virtues;
+
+pub fn from_toml(text: &str) -> Result<TopicManifest, AgentError> {
+    toml::from_str(text).map_err(|e| AgentError::invalid_input(format!("invalid manifest: {e}")))
+}
+
+pub fn from_json(text: &str) -> Result<TopicManifest, AgentError> {
+    serde_json::from_str(text)
+        .map_err(|e| AgentError::invalid_input(format!("invalid manifest: {e}")))
+}
+
+/// The manifest of `topic`, from the graph if it is installed, and whether
+/// it is.
+pub(crate) fn load(host: &Host, topic: &str) -> Result<(TopicManifest, bool), AgentError> {
+    let installed = match host.query(GetTopic {
+        topic_id: topic.to_string(),
+    }) {
+        Err(AgentError::Graph {
+            error: ErrorCode::NotFound,
+            ..
+        }) => None,
+        result => result?,
+    };
+    let (manifest, installed) = match installed {
+        Some(manifest) => (manifest, true),
+        None => (load_file(host, topic)?, false),
+    };
+    check(&manifest, topic)?;
+    Ok((manifest, installed))
+}
+
+/// The manifest of `topic` from the host's local files; `NotFound` when it
+/// has neither file.
+pub(crate) fn load_file(host: &Host, topic: &str) -> Result<TopicManifest, AgentError> {
+    let valid_id = !topic.is_empty()
+        && topic
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+        && !topic.contains("..");
+    if !valid_id {
+        return Err(AgentError::Validation {
+            fields: vec![FieldError::new(
+                "topic",
+                "must be lowercase letters, digits, '_' and single '.'",
+            )],
+        });
+    }
+    let read = |extension: &str| match host
+        .files
+        .open_local(&format!("topics/{topic}.{extension}"))
+    {
+        Ok(text) => Ok(Some(text)),
+        Err(code) if code == ErrorCode::NotFound.code() => Ok(None),
+        Err(code) => Err(AgentError::host("open_local", code)),
+    };
+    if let Some(text) = read("toml")? {
+        return from_toml(&text);
+    }
+    if let Some(text) = read("json")? {
+        return from_json(&text);
+    }
+    Err(AgentError::Graph {
+        import: "open_local".to_string(),
+        error: ErrorCode::NotFound,
+    })
+}
+
+/// Fail unless `manifest` is the one for `topic` and refers only to its own
+/// concepts.
+pub fn check(manifest: &TopicManifest, topic: &str) -> Result<(), AgentError> {
+    let mut fields = Vec::new();
+    if manifest.id != topic {
+        fields.push(FieldError::new("id", format!("must be {topic}")));
+    }
+    if manifest.concepts.is_empty() {
+        fields.push(FieldError::new("concepts", "must not be empty"));
+    }
+    for (i, lesson) in manifest.lessons.iter().enumerate() {
+        if concept(manifest, &lesson.concept).is_none() {
+            fields.push(FieldError::new(
+                &format!("lessons[{i}].concept"),
+                "must be one of the topic's concepts",
+            ));
+        }
+    }
+    let mut ids = HashSet::new();
+    for (i, question) in manifest.questions.iter().enumerate() {
+        if concept(manifest, &question.concept).is_none() {
+            fields.push(FieldError::new(
+                &format!("questions[{i}].concept"),
+                "must be one of the topic's concepts",
+            ));
+        }
+        if question.prompt.trim().is_empty() {
+            fields.push(FieldError::new(
+                &format!("questions[{i}].prompt"),
+                "must not be empty",
+            ));
+        }
+        if !question.item.is_well_formed() {
+            fields.push(FieldError::new(
+                &format!("questions[{i}].item"),
+                "answer key must be one the question can meet",
+            ));
+        }
+        if !ids.insert(question.id.as_str()) {
+            fields.push(FieldError::new(
+                &format!("questions[{i}].id"),
+                "must be unique within the topic",
+            ));
+        }
+    }
+    fields.extend(virtues::check(&manifest.virtues));
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::Validation { fields })
+    }
+}
+
+/// One of the topic's concepts, by id or label.
+pub(crate) fn concept<'a>(manifest: &'a TopicManifest, key: &str) -> Option<&'a TopicConcept> {
+    manifest
+        .concepts
+        .iter()
+        .find(|c| c.id == key || c.label == key)
+}
@@ -0,0 +1,270 @@
+//! Quizzes from a concept's question bank: items are presented without their
+//! answer keys, and answers are checked per item against the bank.
+
+use std::collections::HashSet;
+
+use fot_agent_sdk::protocol::{
+    GetConcept, ListQuestions, QuestionAnswer, QuestionInfo, QuestionItem,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+use crate::evidence;
+
+/// Tolerance for float error when checking numeric answers.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, serde::Serialize)]
+pub struct Quiz {
+    concept_id: String,
+    pub(crate) questions: Vec<PresentedQuestion>,
+}
+
+/// A question as shown to the student.
+#[derive(Debug, serde::Serialize)]
+pub struct PresentedQuestion {
+    question_id: String,
+    prompt: String,
+    #[serde(flatten)]
+    item: PresentedItem,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresentedItem {
+    MultipleChoice {
+        choices: Vec<String>,
+    },
+    Numeric {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
+    },
+    /// Items sorted alphabetically rather than in the correct order.
+    Ordering {
+        items: Vec<String>,
+    },
+    ShortAnswer,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct QuizAnswer {
+    question_id: String,
+    answer: QuestionAnswer,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ItemResult {
+    question_id: String,
+    pub(crate) correct: bool,
+    /// Partial credit in `[0, 1]`; ordering and short answers can earn some.
+    score: f64,
+    feedback: String,
+}
+
+pub(crate) struct Checked {
+    pub concept_id: String,
+    pub results: Vec<ItemResult>,
+    pub mastery_delta: f64,
+}
+
+pub(crate) fn present(host: &Host, concept: &str, count: usize) -> Result<Quiz, AgentError> {
+    if count == 0 {
+        return Err(AgentError::Validation {
+            fields: vec![FieldError::new("count", "must be at least 1")],
+        });
+    }
+    let bank = host.query(ListQuestions {
+        concept: concept.to_string(),
+    })?;
+    let concept_id = match bank.first() {
+        Some(question) => question.concept_id.clone(),
+        None => {
+            return Err(AgentError::invalid_input(format!(
+                "{concept} has no questions"
+            )))
+        }
+    };
+    let questions = bank
+        .into_iter()
+        .take(count)
+        .map(|question| PresentedQuestion {
+            question_id: question.id,
+            prompt: question.prompt,
+            item: match question.item {
+                QuestionItem::MultipleChoice { choices, .. } => {
+                    PresentedItem::MultipleChoice { choices }
+                }
+                QuestionItem::Numeric { unit, .. } => PresentedItem::Numeric { unit },
+                QuestionItem::Ordering { mut items } => {
+                    items.sort();
+                    PresentedItem::Ordering { items }
+                }
+                QuestionItem::ShortAnswer { .. } => PresentedItem::ShortAnswer,
+            },
+        })
+        .collect();
+    Ok(Quiz {
+        concept_id,
+        questions,
+    })
+}
+
+/// Check `answers` against the bank and trace each item's score, partial
+/// credit included, through the concept's knowledge-tracing model in the
+/// order answered, starting from decayed mastery. The traced mastery is
+/// stored.
+pub(crate) fn check(
+    host: &Host,
+    concept: &str,
+    student: &str,
+    answers: Vec<QuizAnswer>,
+) -> Result<Checked, AgentError> {
+    let bank = host.query(ListQuestions {
+        concept: concept.to_string(),
+    })?;
+    let mut fields = Vec::new();
+    if answers.is_empty() {
+        fields.push(FieldError::new("answers", "must not be empty"));
+    }
+    let mut seen = HashSet::new();
+    let mut checked = Vec::new();
+    for (i, answer) in answers.iter().enumerate() {
+        let field = format!("answers[{i}]");
+        if !seen.insert(answer.question_id.as_str()) {
+            fields.push(FieldError::new(&field, "answers the same question twice"));
+            continue;
+        }
+        match bank.iter().find(|q| q.id == answer.question_id) {
+            None => fields.push(FieldError::new(
+                &field,
+                format!("{} is not a question about {concept}", answer.question_id),
+            )),
+            Some(question) => match score(question, &answer.answer) {
+                Some(result) => checked.push(result),
+                None => fields.push(FieldError::new(
+                    &field,
+                    format!("must be a {} answer", kind(&question.item)),
+                )),
+            },
+        }
+    }
+    if !fields.is_empty() {
+        return Err(AgentError::Validation { fields });
+    }
+
+    let model = host
+        .query(GetConcept {
+            concept: concept.to_string(),
+        })?
+        .and_then(|concept| concept.bkt)
+        .unwrap_or_default();
+    let scores: Vec<f64> = checked.iter().map(|r| r.score).collect();
+    let mastery_delta = evidence::record(
+        host,
+        concept,
+        student,
+        &model,
+        &scores,
+        "quiz",
+        crate::now(),
+    )?;
+    Ok(Checked {
+        // Every answer matched a question in the bank, so it is not empty.
+        concept_id: bank[0].concept_id.clone(),
+        results: checked,
+        mastery_delta,
+    })
+}
+
+fn kind(item: &QuestionItem) -> &'static str {
+    match item {
+        QuestionItem::MultipleChoice { .. } => "multiple_choice",
+        QuestionItem::Numeric { .. } => "numeric",
+        QuestionItem::Ordering { .. } => "ordering",
+        QuestionItem::ShortAnswer { .. } => "short_answer",
+    }
+}
+
+/// `None` when the answer is not of the question's type.
+fn score(question: &QuestionInfo, answer: &QuestionAnswer) -> Option<ItemResult> {
+    let (score, correct, feedback) = match (&question.item, answer) {
+        (
+            QuestionItem::MultipleChoice { choices, answer },
+            QuestionAnswer::MultipleChoice { choice },
+        ) => {
+            let correct = choice == answer;
+            let feedback = if correct {
+                "Correct".to_string()
+            } else {
+                format!("The answer is \"{}\"", choices[*answer])
+            };
+            (f64::from(u8::from(correct)), correct, feedback)
+        }
+        (
+            QuestionItem::Numeric {
+                answer,
+                tolerance,
+                unit,
+            },
+            QuestionAnswer::Numeric { value },
+        ) => {
+            let correct = (value - answer).abs() <= tolerance + EPSILON;
+            let unit = unit.as_deref().map(|u| format!(" {u}")).unwrap_or_default();
+            let feedback = if correct {
+                "Correct".to_string()
+            } else {
+                format!("The answer is {answer}{unit}, give or take {tolerance}")
+            };
+            (f64::from(u8::from(correct)), correct, feedback)
+        }
+        (QuestionItem::Ordering { items }, QuestionAnswer::Ordering { order }) => {
+            let in_place = items
+                .iter()
+                .zip(order)
+                .filter(|(item, given)| item == given)
+                .count();
+            let correct = in_place == items.len() && order.len() == items.len();
+            let feedback = if correct {
+                "Correct".to_string()
+            } else {
+                format!("{in_place} of {} in the right place", items.len())
+            };
+            (in_place as f64 / items.len() as f64, correct, feedback)
+        }
+        (
+            QuestionItem::ShortAnswer { rubric, pass_score },
+            QuestionAnswer::ShortAnswer { text },
+        ) => {
+            let text = format!(" {} ", words(text));
+            let mentions = |word: &str| text.contains(&format!(" {} ", words(word)));
+            let total: f64 = rubric.iter().map(|k| k.weight).sum();
+            let (earned, missing): (Vec<_>, Vec<_>) = rubric
+                .iter()
+                .partition(|k| mentions(&k.keyword) || k.synonyms.iter().any(|s| mentions(s)));
+            let score = earned.iter().map(|k| k.weight).sum::<f64>() / total;
+            let correct = score >= *pass_score;
+            let feedback = if missing.is_empty() {
+                "Covers every key point".to_string()
+            } else {
+                let missing: Vec<&str> = missing.iter().map(|k| k.keyword.as_str()).collect();
+                format!("Not mentioned: {}", missing.join(", "))
+            };
+            (score, correct, feedback)
+        }
+        _ => return None,
+    };
+    Some(ItemResult {
+        question_id: question.id.clone(),
+        correct,
+        score,
+        feedback,
+    })
+}
+
+/// Lowercase words separated by single spaces, punctuation dropped.
+fn words(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
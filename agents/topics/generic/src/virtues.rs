@@ -0,0 +1,170 @@
+//! Virtue deltas computed from observed behaviour by a topic's
+//! [`VirtueRules`], each with the reason it was recorded.
+
+use fot_agent_sdk::protocol::{
+    RecordVirtues, Signal, Virtue, VirtueChange, VirtueDeltas, VirtueRule, VirtueRules,
+};
+use fot_agent_sdk::{AgentError, FieldError, Host};
+
+/// What the client observed while the student worked; `None` when it was not
+/// measured, so rules on it do not apply.
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Behaviour {
+    pub time_on_task_secs: Option<u64>,
+    pub hints_used: Option<u32>,
+    pub retries: Option<u32>,
+    pub self_reported_mistakes: Option<u32>,
+}
+
+/// A recorded delta and why it was recorded.
+#[derive(Debug, serde::Serialize)]
+pub struct VirtueEvent {
+    virtue: Virtue,
+    delta: f64,
+    signal: Signal,
+    value: f64,
+    explanation: String,
+}
+
+/// Behaviour observed around one of the topic agent's operations, which is
+/// itself observed as `activity`.
+pub(crate) struct Observation {
+    pub activity: Signal,
+    pub behaviour: Behaviour,
+}
+
+impl Observation {
+    fn value(&self, signal: Signal) -> Option<f64> {
+        let behaviour = &self.behaviour;
+        match signal {
+            Signal::LessonStarted | Signal::WorkGraded | Signal::QuizAnswered => {
+                Some(f64::from(u8::from(signal == self.activity)))
+            }
+            Signal::TimeOnTaskSecs => behaviour.time_on_task_secs.map(|s| s as f64),
+            Signal::HintsUsed => behaviour.hints_used.map(f64::from),
+            Signal::Retries => behaviour.retries.map(f64::from),
+            Signal::SelfReportedMistakes => behaviour.self_reported_mistakes.map(f64::from),
+        }
+    }
+}
+
+/// The deltas `rules` give for `observed`, in rule order.
+pub(crate) fn score(rules: &VirtueRules, observed: &Observation) -> Vec<VirtueEvent> {
+    rules
+        .rules
+        .iter()
+        .filter_map(|rule| {
+            let value = observed.value(rule.signal)?;
+            let applies = match (rule.at_least, rule.below) {
+                (None, None) => value > 0.0,
+                (at_least, below) => {
+                    at_least.is_none_or(|min| value >= min) && below.is_none_or(|max| value < max)
+                }
+            };
+            if !applies {
+                return None;
+            }
+            let delta = if rule.per_unit {
+                let scaled = rule.delta * value;
+                match rule.max {
+                    Some(max) => scaled.clamp(-max, max),
+                    None => scaled,
+                }
+            } else {
+                rule.delta
+            };
+            (delta != 0.0).then(|| VirtueEvent {
+                virtue: rule.virtue,
+                delta,
+                signal: rule.signal,
+                value,
+                explanation: explain(rule, value),
+            })
+        })
+        .collect()
+}
+
+fn explain(rule: &VirtueRule, value: f64) -> String {
+    let value = if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value}")
+    };
+    match &rule.explanation {
+        Some(template) => template.replace("{value}", &value),
+        None => {
+            let signal = serde_json::to_value(rule.signal).unwrap_or_default();
+            format!("{} was {value}", signal.as_str().unwrap_or_default())
+        }
+    }
+}
+
+/// Record each event for `student_id` in the metrics and, with its
+/// explanation, on their `LEARNS` relationship with `concept` when it is in
+/// the graph; return the net deltas.
+///
+/// The metrics come first, so a failure there leaves the graph as it was.
+pub(crate) fn record(
+    host: &Host,
+    student_id: &str,
+    concept: Option<&str>,
+    events: &[VirtueEvent],
+) -> Result<VirtueDeltas, AgentError> {
+    let mut net = VirtueDeltas::default();
+    for event in events {
+        host.metrics
+            .record_virtue(student_id, event.virtue.as_str(), event.delta)
+            .map_err(|code| AgentError::host("record_virtue", code))?;
+        match event.virtue {
+            Virtue::Curiosity => net.curiosity += event.delta,
+            Virtue::Patience => net.patience += event.delta,
+            Virtue::Honesty => net.honesty += event.delta,
+        }
+    }
+    if let (Some(concept), false) = (concept, events.is_empty()) {
+        host.mutate(RecordVirtues {
+            concept: concept.to_string(),
+            student_id: Some(student_id.to_string()),
+            changes: events
+                .iter()
+                .map(|event| VirtueChange {
+                    virtue: event.virtue,
+                    delta: event.delta,
+                    explanation: event.explanation.clone(),
+                })
+                .collect(),
+            timestamp: crate::now(),
+        })?;
+    }
+    Ok(net)
+}
+
+/// Problems with `rules`, reported under `virtues.rules[i]`.
+pub(crate) fn check(rules: &VirtueRules) -> Vec<FieldError> {
+    let mut fields = Vec::new();
+    for (i, rule) in rules.rules.iter().enumerate() {
+        let field = format!("virtues.rules[{i}]");
+        if !rule.delta.is_finite() {
+            fields.push(FieldError::new(
+                &format!("{field}.delta"),
+                "must be a number",
+            ));
+        }
+        if let (Some(at_least), Some(below)) = (rule.at_least, rule.below) {
+            if at_least >= below {
+                fields.push(FieldError::new(
+                    &format!("{field}.below"),
+                    "must be greater than at_least",
+                ));
+            }
+        }
+        if rule.max.is_some_and(|max| max.is_nan() || max <= 0.0) {
+            fields.push(FieldError::new(
+                &format!("{field}.max"),
+                "must be greater than 0",
+            ));
+        }
+    }
+    fields
+}
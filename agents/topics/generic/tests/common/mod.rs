@@ -0,0 +1,22 @@
+//! Runs the topic agent for the integration tests.
+
+#![allow(dead_code)]
+
+use fot_agent_sdk::host::Graph;
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::{test_util, Host};
+use serde_json::Value;
+use topic_agent::TopicAgent;
+
+pub fn invoke(host: &MemoryHost, input: &str) -> Value {
+    test_util::invoke(&TopicAgent, &host.host(), input)
+}
+
+/// Invokes with `graph` in place of the in-memory graph.
+pub fn invoke_on(graph: &dyn Graph, others: &MemoryHost, input: &str) -> Value {
+    let host = Host {
+        graph,
+        ..others.host()
+    };
+    test_util::invoke(&TopicAgent, &host, input)
+}
@@ -0,0 +1,102 @@
+//! Runs the topic agent against the embedded graph store.
+//!
+//! Agents cannot share a test binary, since each exports the ABI's
+//! `alloc`, so reports are checked through the progress reads they are
+//! built from: `class_report` from class progress and `my_virtues` from
+//! child progress.
+
+mod common;
+
+use common::invoke_on;
+use fot_agent_sdk::host::Graph;
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::protocol::{
+    ChildProgress, ClassInfo, CreateClass, EnrollStudent, Envelope, GetChildProgress,
+    GetClassProgress, GraphRead, GraphWrite, RosterEntry,
+};
+use fot_graph_store::GraphStore;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+const PHOTOSYNTHESIS: &str = include_str!("../../biology.photosynthesis.toml");
+
+fn write<T: DeserializeOwned>(store: &GraphStore, op: impl Into<GraphWrite>) -> T {
+    serde_json::from_str(
+        &store
+            .graph_write(&Envelope::<GraphWrite>::new(op).encode())
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+fn read<T: DeserializeOwned>(store: &GraphStore, op: impl Into<GraphRead>) -> T {
+    serde_json::from_str(
+        &store
+            .graph_read(&Envelope::<GraphRead>::new(op).encode())
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn virtues_from_a_lesson_reach_the_reports() {
+    let store = GraphStore::new();
+    let others = MemoryHost::default();
+    others
+        .files
+        .add_local("topics/biology.photosynthesis.toml", PHOTOSYNTHESIS);
+    let teacher = json!({"subject": "teacher-1", "role": "teacher"});
+    store.set_auth_ctx(teacher.clone());
+    let installed = invoke_on(
+        &store,
+        &others,
+        r#"{"topic":"biology.photosynthesis","op":"install_topic"}"#,
+    );
+    assert_eq!(installed["success"], true);
+    let class: ClassInfo = write(
+        &store,
+        CreateClass {
+            name: "Biology 101".to_string(),
+            timestamp: 10,
+        },
+    );
+    write::<RosterEntry>(
+        &store,
+        EnrollStudent {
+            class_id: class.id.clone(),
+            student_id: "ada".to_string(),
+            name: None,
+            timestamp: 20,
+        },
+    );
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    let lesson = invoke_on(
+        &store,
+        &others,
+        r#"{"topic":"biology.photosynthesis","op":"start_lesson"}"#,
+    );
+    assert_eq!(lesson["virtue_deltas"]["curiosity"], 0.1);
+    let progress: ChildProgress = read(
+        &store,
+        GetChildProgress {
+            child_id: "ada".to_string(),
+            since: None,
+        },
+    );
+    assert_eq!(progress.concepts[0].concept_id, "ex:Photosynthesis");
+    assert_eq!(progress.concepts[0].curiosity, Some(0.1));
+    assert_eq!(progress.virtues.curiosity, 0.1);
+
+    store.set_auth_ctx(teacher);
+    let class: Vec<ChildProgress> = read(
+        &store,
+        GetClassProgress {
+            class_id: class.id,
+            since: None,
+        },
+    );
+    assert_eq!(class.len(), 1);
+    assert_eq!(class[0].concepts[0].curiosity, Some(0.1));
+    assert_eq!(class[0].concepts[0].patience, None);
+}
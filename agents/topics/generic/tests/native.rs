@@ -0,0 +1,412 @@
+//! Runs the topic agent natively against the in-memory host.
+
+mod common;
+
+use common::invoke;
+use fot_agent_sdk::mastery::{decay, MasteryModel};
+use fot_agent_sdk::memory::MemoryHost;
+use fot_agent_sdk::protocol::{BktParams, QuestionItem};
+use fot_agent_sdk::test_util::now;
+use fot_agent_sdk::AgentError;
+use serde_json::{json, Value};
+use topic_agent::manifest;
+
+const PHOTOSYNTHESIS: &str = include_str!("../../biology.photosynthesis.toml");
+const CARBON_CYCLE: &str = include_str!("../../chemistry.carbon_cycle.toml");
+const ENTROPY: &str = include_str!("../../physics.entropy.json");
+
+/// A student's host with biology.photosynthesis installed, and one question
+/// of each type in the Photosynthesis bank.
+fn question_bank() -> MemoryHost {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    let installed = manifest::from_toml(PHOTOSYNTHESIS).unwrap();
+    host.graph.respond("get_topic", json!(installed));
+    let question = |id: &str, item: Value| {
+        json!({
+            "id": id, "concept_id": "ex:Photosynthesis", "prompt": id,
+            "item": item, "created_at": 0
+        })
+    };
+    host.graph.respond(
+        "list_questions",
+        json!([
+            question(
+                "pigment",
+                json!({
+                    "type": "multiple_choice", "choices": ["Keratin", "Chlorophyll"], "answer": 1
+                })
+            ),
+            question(
+                "wavelength",
+                json!({
+                    "type": "numeric", "answer": 680.0, "tolerance": 5.0, "unit": "nm"
+                })
+            ),
+            question(
+                "steps",
+                json!({
+                    "type": "ordering", "items": ["Light absorbed", "Water split", "Glucose made"]
+                })
+            ),
+            question(
+                "why",
+                json!({
+                    "type": "short_answer", "pass_score": 0.7, "rubric": [
+                        {"keyword": "light energy", "synonyms": ["sunlight"], "weight": 2.0},
+                        {"keyword": "glucose", "weight": 1.0}
+                    ]
+                })
+            )
+        ]),
+    );
+    host
+}
+
+#[test]
+fn shipped_manifests_are_valid() {
+    let manifests = [
+        manifest::from_toml(PHOTOSYNTHESIS).unwrap(),
+        manifest::from_toml(CARBON_CYCLE).unwrap(),
+        manifest::from_json(ENTROPY).unwrap(),
+    ];
+    for topic in &manifests {
+        manifest::check(topic, &topic.id).unwrap();
+        assert!(!topic.lessons.is_empty());
+        assert!(!topic.questions.is_empty());
+    }
+    assert_eq!(manifests[2].virtues.rules.len(), 3);
+
+    let mut inverted = manifests[0].clone();
+    inverted.virtues.rules[2].below = Some(0.5);
+    let Err(AgentError::Validation { fields }) = manifest::check(&inverted, &inverted.id) else {
+        panic!("inverted bounds were accepted");
+    };
+    assert_eq!(fields[0].field, "virtues.rules[2].below");
+
+    let mut unanswerable = manifests[2].clone();
+    unanswerable.questions[0].item = QuestionItem::MultipleChoice {
+        choices: vec!["Order".to_string(), "Disorder".to_string()],
+        answer: 2,
+    };
+    let Err(AgentError::Validation { fields }) = manifest::check(&unanswerable, &unanswerable.id)
+    else {
+        panic!("an out-of-range answer was accepted");
+    };
+    assert_eq!(fields[0].field, "questions[0].item");
+}
+
+#[test]
+fn uninstalled_topics_load_from_their_manifest_file() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    host.files
+        .add_local("topics/chemistry.carbon_cycle.toml", CARBON_CYCLE);
+    host.files.add_local("topics/physics.entropy.json", ENTROPY);
+
+    let reply = invoke(
+        &host,
+        r#"{"topic":"chemistry.carbon_cycle","op":"start_lesson","student_id":"ada"}"#,
+    );
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["message"], "Carbon reservoirs lesson started");
+    assert_eq!(reply["lesson"]["concept"], "Carbon Cycle");
+    assert_eq!(reply["virtue_deltas"]["curiosity"], 0.1);
+    // Rules without an explanation of their own still get one.
+    assert_eq!(
+        reply["virtue_events"][0]["explanation"],
+        "lesson_started was 1"
+    );
+    assert_eq!(host.metrics.virtue("ada", "curiosity"), 0.1);
+
+    let reply = invoke(
+        &host,
+        r#"{"topic":"physics.entropy","op":"start_lesson","student_id":"ada","lesson":"disorder"}"#,
+    );
+    assert_eq!(reply["lesson"]["title"], "Counting arrangements");
+
+    let reply = invoke(
+        &host,
+        r#"{"topic":"physics.optics","op":"start_lesson","student_id":"ada"}"#,
+    );
+    assert_eq!(reply["error"]["kind"], "graph");
+    assert_eq!(reply["error"]["error"], "not_found");
+    let reply = invoke(
+        &host,
+        r#"{"topic":"../secrets","op":"start_lesson","student_id":"ada"}"#,
+    );
+    assert_eq!(reply["error"]["fields"][0]["field"], "topic");
+
+    // Quizzes trace mastery in the graph, so they need the topic installed.
+    let reply = invoke(
+        &host,
+        r#"{"topic":"chemistry.carbon_cycle","op":"start_quiz"}"#,
+    );
+    assert_eq!(
+        reply["error"]["message"],
+        "install chemistry.carbon_cycle before quizzing on it"
+    );
+}
+
+#[test]
+fn installing_a_topic_writes_its_manifest_to_the_graph() {
+    let host = MemoryHost::default();
+    host.graph
+        .set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    host.files
+        .add_local("topics/chemistry.carbon_cycle.toml", CARBON_CYCLE);
+    host.graph.respond(
+        "install_topic",
+        json!({
+            "topic_id": "chemistry.carbon_cycle", "concepts": ["ex:Carbon_Cycle"],
+            "questions": [], "links": []
+        }),
+    );
+    let reply = invoke(
+        &host,
+        r#"{"topic":"chemistry.carbon_cycle","op":"install_topic"}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    assert_eq!(reply["topic_id"], "chemistry.carbon_cycle");
+    let writes = host.graph.writes();
+    assert_eq!(writes[0]["operation"], "install_topic");
+    assert_eq!(
+        writes[0]["manifest"]["questions"].as_array().unwrap().len(),
+        3
+    );
+
+    // An inline manifest must be the one for the named topic.
+    let mut entropy = manifest::from_json(ENTROPY).unwrap();
+    entropy.lessons[0].concept = "Heat".to_string();
+    let install =
+        json!({"topic": "chemistry.carbon_cycle", "op": "install_topic", "manifest": entropy});
+    let reply = invoke(&host, &install.to_string());
+    let fields: Vec<&str> = reply["error"]["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["id", "lessons[0].concept"]);
+}
+
+#[test]
+fn quizzes_are_presented_without_their_answer_keys() {
+    let host = question_bank();
+    let reply = invoke(
+        &host,
+        r#"{"topic":"biology.photosynthesis","op":"start_quiz","count":3}"#,
+    );
+
+    assert_eq!(reply["success"], true);
+    let questions = reply["quiz"]["questions"].as_array().unwrap();
+    assert_eq!(questions.len(), 3);
+    assert_eq!(questions[0]["choices"], json!(["Keratin", "Chlorophyll"]));
+    assert_eq!(questions[0].get("answer"), None);
+    assert_eq!(questions[1]["unit"], "nm");
+    assert_eq!(
+        questions[2]["items"],
+        json!(["Glucose made", "Light absorbed", "Water split"])
+    );
+
+    let reply = invoke(
+        &host,
+        r#"{"topic":"biology.photosynthesis","op":"start_quiz","concept":"Entropy"}"#,
+    );
+    assert_eq!(
+        reply["error"]["message"],
+        "Entropy is not a concept of biology.photosynthesis"
+    );
+}
+
+#[test]
+fn answers_are_checked_per_item_and_traced_into_mastery() {
+    let host = question_bank();
+    host.graph.respond("get_concept", json!(null));
+    let updated_at = now() - 24 * 60 * 60;
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "ex:Photosynthesis", "mastery": 0.4, "updated_at": updated_at, "revision": 2}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "ex:Photosynthesis", "mastery": 0.5}),
+    );
+    let answers = json!({"topic": "biology.photosynthesis", "op": "answer_quiz", "answers": [
+        {"question_id": "pigment", "answer": {"type": "multiple_choice", "choice": 1}},
+        {"question_id": "wavelength", "answer": {"type": "numeric", "value": 684.5}},
+        {"question_id": "steps", "answer": {"type": "ordering",
+            "order": ["Light absorbed", "Glucose made", "Water split"]}},
+        {"question_id": "why", "answer": {"type": "short_answer",
+            "text": "Plants turn Sunlight into chemical energy."}}
+    ]});
+    let reply = invoke(&host, &answers.to_string());
+
+    assert_eq!(reply["success"], true);
+    let results: Vec<(&str, bool, f64)> = reply["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["question_id"].as_str().unwrap(),
+                r["correct"].as_bool().unwrap(),
+                r["score"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        [
+            ("pigment", true, 1.0),
+            ("wavelength", true, 1.0),
+            ("steps", false, 1.0 / 3.0),
+            ("why", false, 2.0 / 3.0)
+        ]
+    );
+    assert_eq!(reply["results"][3]["feedback"], "Not mentioned: glucose");
+    assert_eq!(reply["message"], "2 of 4 correct on ex:Photosynthesis");
+    // Every score is traced, partial credit included, from a day's decay.
+    let decayed = decay(0.4, Some(updated_at), None, now());
+    let traced = results.iter().fold(decayed, |mastery, (_, _, score)| {
+        BktParams::default().observe_score(mastery, *score)
+    });
+    assert!((reply["mastery_delta"].as_f64().unwrap() - (traced - decayed)).abs() < 1e-6);
+    // ...and stored, unless someone updated it since it was read.
+    let writes = host.graph.writes();
+    let stored: Vec<_> = writes
+        .iter()
+        .filter(|w| w["operation"] == "update_mastery")
+        .collect();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0]["concept"], "ex:Photosynthesis");
+    assert_eq!(stored[0]["student_id"], "ada");
+    assert_eq!(stored[0]["if_revision"], 2);
+    assert!((stored[0]["mastery"].as_f64().unwrap() - traced).abs() < 1e-6);
+
+    let mismatched = json!({"topic": "biology.photosynthesis", "op": "answer_quiz", "answers": [
+        {"question_id": "pigment", "answer": {"type": "numeric", "value": 1.0}},
+        {"question_id": "unknown", "answer": {"type": "numeric", "value": 1.0}}
+    ]});
+    let reply = invoke(&host, &mismatched.to_string());
+    assert_eq!(reply["error"]["kind"], "validation");
+    assert_eq!(
+        reply["error"]["fields"][0]["message"],
+        "must be a multiple_choice answer"
+    );
+    assert_eq!(reply["error"]["fields"][1]["field"], "answers[1]");
+
+    // A student cannot answer, and earn virtues, for someone else.
+    let curiosity = host.metrics.virtue("ben", "curiosity");
+    let mut for_ben = answers.clone();
+    for_ben["student_id"] = json!("ben");
+    let reply = invoke(&host, &for_ben.to_string());
+    assert_eq!(reply["error"]["kind"], "forbidden");
+    let reply = invoke(
+        &host,
+        r#"{"topic":"biology.photosynthesis","op":"start_lesson","student_id":"ben"}"#,
+    );
+    assert_eq!(reply["error"]["kind"], "forbidden");
+    assert_eq!(host.metrics.virtue("ben", "curiosity"), curiosity);
+}
+
+#[test]
+fn graded_work_earns_virtues_from_observed_behaviour() {
+    let host = question_bank();
+    host.graph
+        .set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let submission = json!({
+        "id": "submission-1", "assignment_id": "assignment-1", "student_id": "ada",
+        "content": "Light makes sugar", "time_spent": 420, "submitted_at": 100, "days_late": 0
+    });
+    host.graph.respond("get_submission", submission.clone());
+    host.graph.respond(
+        "get_assignment",
+        json!({
+            "id": "assignment-1", "class_id": "class-1", "concept": "Photosynthesis",
+            "title": "Leaf lab", "due_at": 1000,
+            "late_policy": {}, "created_by": "teacher-1", "created_at": 0
+        }),
+    );
+    let mut graded = submission;
+    graded["grade"] = json!({"raw_score": 0.8, "score": 0.8, "graded_at": 200});
+    host.graph.respond("record_grade", graded);
+    host.graph.respond("get_concept", json!(null));
+    host.graph.respond(
+        "record_virtues",
+        json!({"concept_id": "ex:Photosynthesis", "patience": 0.3, "honesty": 0.1}),
+    );
+    // Untouched for decades: next to nothing is left of it.
+    host.graph.respond(
+        "get_mastery",
+        json!({"concept_id": "ex:Photosynthesis", "mastery": 0.8, "updated_at": 0, "revision": 4}),
+    );
+    host.graph.respond(
+        "update_mastery",
+        json!({"concept_id": "ex:Photosynthesis", "mastery": 0.5}),
+    );
+    let grade = json!({
+        "topic": "biology.photosynthesis", "op": "grade_submission",
+        "submission_id": "submission-1", "score": 0.8,
+        "behaviour": {"retries": 5, "self_reported_mistakes": 1, "hints_used": 2}
+    });
+    let reply = invoke(&host, &grade.to_string());
+
+    assert_eq!(reply["success"], true);
+    let decayed = decay(0.8, Some(0), None, now());
+    let traced = BktParams::default().observe_score(decayed, 0.8);
+    assert!((reply["mastery_delta"].as_f64().unwrap() - (traced - decayed)).abs() < 1e-9);
+    // The traced mastery is stored, unless someone updated it since it was read.
+    let writes = host.graph.writes();
+    let stored = writes
+        .iter()
+        .find(|w| w["operation"] == "update_mastery")
+        .unwrap();
+    assert_eq!(stored["concept"], "Photosynthesis");
+    assert_eq!(stored["student_id"], "ada");
+    assert_eq!(stored["if_revision"], 4);
+    assert!((stored["mastery"].as_f64().unwrap() - traced).abs() < 1e-9);
+    let events: Vec<(&str, f64, &str)> = reply["virtue_events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["virtue"].as_str().unwrap(),
+                e["delta"].as_f64().unwrap(),
+                e["explanation"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    // Time on task comes from the submission; retries are capped at 0.15.
+    assert_eq!(
+        events,
+        [
+            ("patience", 0.15, "Stayed with the task for 420 seconds"),
+            (
+                "patience",
+                0.15,
+                "Tried again 5 times rather than giving up"
+            ),
+            ("honesty", 0.1, "Owned up to 1 mistakes"),
+        ]
+    );
+    assert!((reply["virtue_deltas"]["patience"].as_f64().unwrap() - 0.3).abs() < 1e-9);
+    assert_eq!(reply["virtue_deltas"]["curiosity"], 0.0);
+    assert!((host.metrics.virtue("ada", "patience") - 0.3).abs() < 1e-9);
+    assert_eq!(host.metrics.virtue("ada", "honesty"), 0.1);
+    // Each delta is kept in the graph with its explanation.
+    let recorded = writes
+        .iter()
+        .find(|w| w["operation"] == "record_virtues")
+        .unwrap();
+    assert_eq!(recorded["concept"], "Photosynthesis");
+    assert_eq!(recorded["student_id"], "ada");
+    assert_eq!(
+        recorded["changes"][2],
+        json!({"virtue": "honesty", "delta": 0.1, "explanation": "Owned up to 1 mistakes"})
+    );
+}
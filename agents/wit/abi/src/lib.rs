@@ -0,0 +1,136 @@
+//! Core-wasm calling convention shared by the `fot-*` guest bindings.
+//!
+//! The WIT imports are lowered the way the canonical ABI lowers them for a
+//! core module:
+//!
+//! - a `string` argument is passed as `(ptr, len)`;
+//! - `u64`, `f64` and other scalars are passed and returned as-is;
+//! - a `string` or `result<..>` return value is written by the host into a
+//!   return area whose address the guest passes as the last argument.
+//!
+//! Return area layout: a `u8` discriminant at offset 0 (`0` = ok, `1` = err)
+//! followed by the payload at the payload's alignment, i.e. offset 4 for
+//! strings (`ptr: u32, len: u32`) and `u32` error codes, offset 8 for `u64`
+//! and `f64`. Strings handed back by the host live in memory the host
+//! obtained from the agent's `alloc` export, so the guest takes ownership of
+//! them.
+
+/// Scratch space the host writes a lowered return value into.
+#[derive(Default)]
+#[repr(C, align(8))]
+pub struct RetArea([u8; 16]);
+
+impl RetArea {
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.0.as_mut_ptr()
+    }
+
+    fn is_err(&self) -> bool {
+        self.0[0] != 0
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&self.0[offset..offset + 4]);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn u64_at(&self, offset: usize) -> u64 {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&self.0[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Take ownership of the `(ptr, len)` string stored at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The host must have written a pointer to `len` UTF-8 bytes allocated
+    /// through the agent's `alloc` export.
+    unsafe fn string_at(&self, offset: usize) -> String {
+        let ptr = self.u32_at(offset) as usize as *mut u8;
+        let len = self.u32_at(offset + 4) as usize;
+        if len == 0 {
+            return String::new();
+        }
+        String::from_raw_parts(ptr, len, len)
+    }
+
+    /// Lift a lowered `string`.
+    ///
+    /// # Safety
+    ///
+    /// See [`RetArea::string_at`].
+    pub unsafe fn string(&self) -> String {
+        self.string_at(0)
+    }
+
+    /// Lift a lowered `result<string, u32>`.
+    ///
+    /// # Safety
+    ///
+    /// See [`RetArea::string_at`].
+    pub unsafe fn result_string(&self) -> Result<String, u32> {
+        if self.is_err() {
+            Err(self.u32_at(4))
+        } else {
+            Ok(self.string_at(4))
+        }
+    }
+
+    /// Lift a lowered `result<_, u32>`.
+    pub fn result_unit(&self) -> Result<(), u32> {
+        if self.is_err() {
+            Err(self.u32_at(4))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Lift a lowered `result<u64, u32>`.
+    pub fn result_u64(&self) -> Result<u64, u32> {
+        if self.is_err() {
+            Err(self.u32_at(8))
+        } else {
+            Ok(self.u64_at(8))
+        }
+    }
+
+    /// Lift a lowered `result<f64, u32>`.
+    pub fn result_f64(&self) -> Result<f64, u32> {
+        if self.is_err() {
+            Err(self.u32_at(8))
+        } else {
+            Ok(f64::from_bits(self.u64_at(8)))
+        }
+    }
+}
+
+/// Declare the raw imports of a WIT interface.
+///
+/// On `wasm32` this is an `extern` block linked against `$module`; elsewhere
+/// each import is a stub that panics, so crates using the bindings still
+/// build natively.
+#[macro_export]
+macro_rules! wasm_imports {
+    ($module:literal { $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)* }) => {
+        #[cfg(target_arch = "wasm32")]
+        #[link(wasm_import_module = $module)]
+        extern "C" {
+            $(pub fn $name($($arg: $ty),*) $(-> $ret)?;)*
+        }
+
+        $(
+            #[cfg(not(target_arch = "wasm32"))]
+            #[allow(unused_variables, clippy::missing_safety_doc)]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                unreachable!(concat!(
+                    $module,
+                    "#",
+                    stringify!($name),
+                    " is only available inside a wasm host"
+                ))
+            }
+        )*
+    };
+}
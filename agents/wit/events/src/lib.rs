@@ -0,0 +1,124 @@
+//! Guest bindings for `events.wit` (`fot:events/events`).
+
+use fot_wit_abi::RetArea;
+
+/// Import module the host registers these functions under.
+pub const MODULE: &str = "fot:events/events";
+
+pub mod raw {
+    fot_wit_abi::wasm_imports!("fot:events/events" {
+        fn publish(topic_ptr: *const u8, topic_len: usize, message_ptr: *const u8, message_len: usize, ret: *mut u8);
+        fn subscribe(topic_ptr: *const u8, topic_len: usize, ret: *mut u8);
+        fn unsubscribe(subscription: u64, ret: *mut u8);
+        fn notify(user_id_ptr: *const u8, user_id_len: usize, message_ptr: *const u8, message_len: usize, ret: *mut u8);
+        fn emit_resonance(
+            agent_id_ptr: *const u8,
+            agent_id_len: usize,
+            frequency: f64,
+            amplitude: f64,
+            context_ptr: *const u8,
+            context_len: usize,
+            ret: *mut u8,
+        );
+        fn broadcast_harmonic(resonance_data_ptr: *const u8, resonance_data_len: usize, ret: *mut u8);
+        fn subscribe_resonance(frequency_range_ptr: *const u8, frequency_range_len: usize, ret: *mut u8);
+        fn measure_collective_resonance(agent_cluster_ptr: *const u8, agent_cluster_len: usize, ret: *mut u8);
+    });
+}
+
+pub fn publish(topic: &str, message: &str) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::publish(
+            topic.as_ptr(),
+            topic.len(),
+            message.as_ptr(),
+            message.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn subscribe(topic: &str) -> Result<u64, u32> {
+    let mut ret = RetArea::default();
+    unsafe { raw::subscribe(topic.as_ptr(), topic.len(), ret.as_mut_ptr()) };
+    ret.result_u64()
+}
+
+pub fn unsubscribe(subscription: u64) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe { raw::unsubscribe(subscription, ret.as_mut_ptr()) };
+    ret.result_unit()
+}
+
+pub fn notify(user_id: &str, message: &str) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::notify(
+            user_id.as_ptr(),
+            user_id.len(),
+            message.as_ptr(),
+            message.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn emit_resonance(
+    agent_id: &str,
+    frequency: f64,
+    amplitude: f64,
+    context: &str,
+) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::emit_resonance(
+            agent_id.as_ptr(),
+            agent_id.len(),
+            frequency,
+            amplitude,
+            context.as_ptr(),
+            context.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn broadcast_harmonic(resonance_data: &str) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::broadcast_harmonic(
+            resonance_data.as_ptr(),
+            resonance_data.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn subscribe_resonance(frequency_range: &str) -> Result<u64, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::subscribe_resonance(
+            frequency_range.as_ptr(),
+            frequency_range.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_u64()
+}
+
+pub fn measure_collective_resonance(agent_cluster: &str) -> Result<f64, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::measure_collective_resonance(
+            agent_cluster.as_ptr(),
+            agent_cluster.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_f64()
+}
@@ -0,0 +1,53 @@
+//! Guest bindings for `files.wit` (`fot:files/files`).
+
+use fot_wit_abi::RetArea;
+
+/// Import module the host registers these functions under.
+pub const MODULE: &str = "fot:files/files";
+
+pub mod raw {
+    fot_wit_abi::wasm_imports!("fot:files/files" {
+        fn fetch_dataset(url_ptr: *const u8, url_len: usize, ret: *mut u8);
+        fn cache_get(key_ptr: *const u8, key_len: usize, ret: *mut u8);
+        fn cache_set(key_ptr: *const u8, key_len: usize, value_ptr: *const u8, value_len: usize, ret: *mut u8);
+        fn open_local(path_ptr: *const u8, path_len: usize, ret: *mut u8);
+    });
+}
+
+pub fn fetch_dataset(url: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::fetch_dataset(url.as_ptr(), url.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+pub fn cache_get(key: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::cache_get(key.as_ptr(), key.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+pub fn cache_set(key: &str, value: &str) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::cache_set(
+            key.as_ptr(),
+            key.len(),
+            value.as_ptr(),
+            value.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn open_local(path: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::open_local(path.as_ptr(), path.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
@@ -0,0 +1,61 @@
+//! Guest bindings for `graph.wit` (`fot:graph/graph`).
+
+use fot_wit_abi::RetArea;
+
+/// Import module the host registers these functions under.
+pub const MODULE: &str = "fot:graph/graph";
+
+pub mod raw {
+    fot_wit_abi::wasm_imports!("fot:graph/graph" {
+        fn graph_read(query_ptr: *const u8, query_len: usize, ret: *mut u8);
+        fn graph_write(mutation_ptr: *const u8, mutation_len: usize, ret: *mut u8);
+        fn begin_txn() -> u64;
+        fn commit_txn(txn: u64, ret: *mut u8);
+        fn rollback_txn(txn: u64, ret: *mut u8);
+        fn auth_ctx(ret: *mut u8);
+    });
+}
+
+/// Run a read query; returns JSON.
+pub fn graph_read(query: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::graph_read(query.as_ptr(), query.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+/// Apply a mutation; returns JSON.
+pub fn graph_write(mutation: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::graph_write(mutation.as_ptr(), mutation.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+pub fn begin_txn() -> u64 {
+    unsafe { raw::begin_txn() }
+}
+
+pub fn commit_txn(txn: u64) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe { raw::commit_txn(txn, ret.as_mut_ptr()) };
+    ret.result_unit()
+}
+
+/// Discard the writes staged in `txn` and the transactions nested in it.
+pub fn rollback_txn(txn: u64) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe { raw::rollback_txn(txn, ret.as_mut_ptr()) };
+    ret.result_unit()
+}
+
+/// Caller identity as JSON: role, subject ids, class scopes.
+pub fn auth_ctx() -> String {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::auth_ctx(ret.as_mut_ptr());
+        ret.string()
+    }
+}
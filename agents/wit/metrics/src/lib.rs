@@ -0,0 +1,127 @@
+//! Guest bindings for `metrics.wit` (`fot:metrics/metrics`).
+
+use fot_wit_abi::RetArea;
+
+/// Import module the host registers these functions under.
+pub const MODULE: &str = "fot:metrics/metrics";
+
+pub mod raw {
+    fot_wit_abi::wasm_imports!("fot:metrics/metrics" {
+        fn record_virtue(user_id_ptr: *const u8, user_id_len: usize, virtue_ptr: *const u8, virtue_len: usize, delta: f64, ret: *mut u8);
+        fn record_usage(user_id_ptr: *const u8, user_id_len: usize, action_ptr: *const u8, action_len: usize, duration: u64, ret: *mut u8);
+        fn get_stats(user_id_ptr: *const u8, user_id_len: usize, ret: *mut u8);
+        fn export_metrics(ret: *mut u8);
+        fn record_resonance(
+            agent_id_ptr: *const u8,
+            agent_id_len: usize,
+            context_ptr: *const u8,
+            context_len: usize,
+            resonance_amplitude: f64,
+            ret: *mut u8,
+        );
+        fn measure_harmonic_coherence(agent_ids_ptr: *const u8, agent_ids_len: usize, context_ptr: *const u8, context_len: usize, ret: *mut u8);
+        fn calculate_truth_field(claim_ptr: *const u8, claim_len: usize, agent_contexts_ptr: *const u8, agent_contexts_len: usize, ret: *mut u8);
+        fn get_resonance_spectrum(domain_ptr: *const u8, domain_len: usize, ret: *mut u8);
+    });
+}
+
+pub fn record_virtue(user_id: &str, virtue: &str, delta: f64) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::record_virtue(
+            user_id.as_ptr(),
+            user_id.len(),
+            virtue.as_ptr(),
+            virtue.len(),
+            delta,
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn record_usage(user_id: &str, action: &str, duration: u64) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::record_usage(
+            user_id.as_ptr(),
+            user_id.len(),
+            action.as_ptr(),
+            action.len(),
+            duration,
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn get_stats(user_id: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::get_stats(user_id.as_ptr(), user_id.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+pub fn export_metrics() -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::export_metrics(ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
+
+pub fn record_resonance(
+    agent_id: &str,
+    context: &str,
+    resonance_amplitude: f64,
+) -> Result<(), u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::record_resonance(
+            agent_id.as_ptr(),
+            agent_id.len(),
+            context.as_ptr(),
+            context.len(),
+            resonance_amplitude,
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_unit()
+}
+
+pub fn measure_harmonic_coherence(agent_ids: &str, context: &str) -> Result<f64, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::measure_harmonic_coherence(
+            agent_ids.as_ptr(),
+            agent_ids.len(),
+            context.as_ptr(),
+            context.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_f64()
+}
+
+pub fn calculate_truth_field(claim: &str, agent_contexts: &str) -> Result<f64, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::calculate_truth_field(
+            claim.as_ptr(),
+            claim.len(),
+            agent_contexts.as_ptr(),
+            agent_contexts.len(),
+            ret.as_mut_ptr(),
+        )
+    };
+    ret.result_f64()
+}
+
+pub fn get_resonance_spectrum(domain: &str) -> Result<String, u32> {
+    let mut ret = RetArea::default();
+    unsafe {
+        raw::get_resonance_spectrum(domain.as_ptr(), domain.len(), ret.as_mut_ptr());
+        ret.result_string()
+    }
+}
@@ -0,0 +1,106 @@
+//! A student's activity history, gathered from the relationships that
+//! record it: `LEARNS` mastery and virtue history, `SUBMITTED` work,
+//! `FOLLOWED` links and `GOAL`s.
+
+use fot_protocol::{
+    Activity, ActivityHistory, ActivityPage, ErrorCode, LinkType, MasterySample, SubmissionInfo,
+    VirtueSample,
+};
+use serde_json::Value;
+
+use crate::goals::goal;
+use crate::model::GraphData;
+use crate::ops::{acting_for, caller};
+use crate::records;
+
+pub(crate) fn history(
+    data: &GraphData,
+    auth: &Value,
+    query: ActivityHistory,
+) -> Result<ActivityPage, ErrorCode> {
+    let caller = caller(auth)?;
+    let student = acting_for(data, &caller, query.student_id.as_deref())?;
+    if query.limit == 0 {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    let mut activities = Vec::new();
+    for rel in data.outgoing(student, "LEARNS") {
+        let samples: Vec<MasterySample> = rel
+            .properties
+            .get("history")
+            .and_then(|h| serde_json::from_value(h.clone()).ok())
+            .unwrap_or_default();
+        activities.extend(samples.into_iter().map(|sample| Activity::Mastery {
+            at: sample.at,
+            concept_id: rel.to.clone(),
+            mastery: sample.mastery,
+        }));
+        let virtues: Vec<VirtueSample> = rel
+            .properties
+            .get("virtue_history")
+            .and_then(|h| serde_json::from_value(h.clone()).ok())
+            .unwrap_or_default();
+        activities.extend(virtues.into_iter().map(|sample| Activity::Virtue {
+            at: sample.at,
+            concept_id: rel.to.clone(),
+            change: sample.change,
+        }));
+    }
+    for rel in data.outgoing(student, "SUBMITTED") {
+        let submission: SubmissionInfo = records::load(data, "Submission", &rel.to)?;
+        if let Some(grade) = submission.grade {
+            activities.push(Activity::Graded {
+                at: grade.graded_at,
+                submission_id: submission.id.clone(),
+                assignment_id: submission.assignment_id.clone(),
+                score: grade.score,
+            });
+        }
+        activities.push(Activity::Submitted {
+            at: submission.submitted_at,
+            submission_id: submission.id,
+            assignment_id: submission.assignment_id,
+            days_late: submission.days_late,
+        });
+    }
+    for rel in data.outgoing(student, "FOLLOWED") {
+        let properties = &rel.properties;
+        let followed = (|| {
+            Some(Activity::FollowedLink {
+                at: properties.get("followed_at")?.as_u64()?,
+                source: properties.get("source")?.as_str()?.to_string(),
+                target: rel.to.clone(),
+                link_type: serde_json::from_value::<LinkType>(properties.get("link_type")?.clone())
+                    .ok()?,
+            })
+        })();
+        activities.extend(followed);
+    }
+    for goal in data
+        .outgoing(student, "GOAL")
+        .filter_map(|rel| goal(data, rel))
+    {
+        activities.push(Activity::GoalSet {
+            at: goal.set_at,
+            concept_id: goal.concept_id,
+            target_mastery: goal.target_mastery,
+        });
+    }
+
+    // Newest first; the sort is stable, so same-time activities keep the
+    // order they were gathered in.
+    activities.sort_by_key(|a| std::cmp::Reverse(a.at()));
+    let total = activities.len();
+    let activities: Vec<Activity> = activities
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit)
+        .collect();
+    let end = query.offset.saturating_add(activities.len());
+    Ok(ActivityPage {
+        activities,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
@@ -0,0 +1,241 @@
+//! Assignments and the submissions handed in for them.
+//!
+//! Assignment and Submission nodes store the fields of [`AssignmentInfo`]
+//! and [`SubmissionInfo`] as properties, like lessons do. A Class
+//! `HAS_ASSIGNMENT`, an Assignment `ASSESSES` its Concept, and a student
+//! `SUBMITTED` a Submission that is `FOR` an Assignment.
+
+use fot_protocol::{
+    AssignmentInfo, CalibrateAssignment, CreateAssignment, ErrorCode, GetAssignment, GetSubmission,
+    Grade, ItemResponse, ListResponses, RecordGrade, Role, SubmissionInfo, Submit,
+};
+use serde_json::Value;
+
+use crate::lessons;
+use crate::model::{GraphData, Node, Properties};
+use crate::ops::{caller, require_teacher};
+use crate::records::{load, save};
+use crate::roster::enrolled;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Started days between `due_at` and `at`.
+fn days_late(due_at: u64, at: u64) -> u32 {
+    let late = at.saturating_sub(due_at);
+    u32::try_from(late.div_ceil(DAY)).unwrap_or(u32::MAX)
+}
+
+pub(crate) fn create(
+    data: &mut GraphData,
+    auth: &Value,
+    op: CreateAssignment,
+) -> Result<AssignmentInfo, ErrorCode> {
+    require_teacher(data, auth, &op.class_id)?;
+    let concept = lessons::concept_id(data, &op.concept)?;
+    let lesson_version = match &op.lesson_id {
+        Some(lesson_id) => {
+            let lesson = lessons::load(data, lesson_id)?;
+            if lesson.class_id != op.class_id {
+                return Err(ErrorCode::InvalidPayload);
+            }
+            Some(lesson.version)
+        }
+        None => None,
+    };
+    let penalty = op.late_policy.penalty_per_day;
+    if !(0.0..=1.0).contains(&penalty) {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    let id = data.fresh_id("assignment");
+    let assignment = AssignmentInfo {
+        id: id.clone(),
+        class_id: op.class_id,
+        concept: op.concept,
+        title: op.title,
+        lesson_id: op.lesson_id,
+        lesson_version,
+        due_at: op.due_at,
+        late_policy: op.late_policy,
+        created_by: caller(auth)?.subject,
+        created_at: op.timestamp,
+        calibration: None,
+    };
+    save(data, "Assignment", &id, &assignment);
+    data.relate(
+        &assignment.class_id,
+        "HAS_ASSIGNMENT",
+        &id,
+        Properties::new(),
+    );
+    data.relate(&id, "ASSESSES", &concept, Properties::new());
+    Ok(assignment)
+}
+
+/// Teachers of the class and the students enrolled in it.
+pub(crate) fn get(
+    data: &GraphData,
+    auth: &Value,
+    query: GetAssignment,
+) -> Result<AssignmentInfo, ErrorCode> {
+    let assignment: AssignmentInfo = load(data, "Assignment", &query.assignment_id)?;
+    let caller = caller(auth)?;
+    if caller.role == Role::Student {
+        enrolled(data, &caller.subject, &assignment.class_id).map_err(|_| ErrorCode::Forbidden)?;
+    } else {
+        require_teacher(data, auth, &assignment.class_id)?;
+    }
+    Ok(assignment)
+}
+
+pub(crate) fn submit(
+    data: &mut GraphData,
+    auth: &Value,
+    op: Submit,
+) -> Result<SubmissionInfo, ErrorCode> {
+    let caller = caller(auth)?;
+    if caller.role != Role::Student {
+        return Err(ErrorCode::Forbidden);
+    }
+    let assignment: AssignmentInfo = load(data, "Assignment", &op.assignment_id)?;
+    enrolled(data, &caller.subject, &assignment.class_id).map_err(|_| ErrorCode::Forbidden)?;
+
+    let days_late = days_late(assignment.due_at, op.timestamp);
+    let lesson_version = match &assignment.lesson_id {
+        Some(lesson_id) => Some(lessons::load(data, lesson_id)?.version),
+        None => None,
+    };
+    if let Some(limit) = assignment.late_policy.accept_days_late {
+        if days_late > limit {
+            return Err(ErrorCode::InvalidState);
+        }
+    }
+
+    let previous = data
+        .outgoing(&caller.subject, "SUBMITTED")
+        .map(|r| r.to.clone())
+        .find(|id| data.outgoing(id, "FOR").any(|r| r.to == assignment.id));
+    let id = match previous {
+        Some(id) => {
+            let existing: SubmissionInfo = load(data, "Submission", &id)?;
+            if existing.grade.is_some() {
+                return Err(ErrorCode::InvalidState);
+            }
+            id
+        }
+        None => {
+            let id = data.fresh_id("submission");
+            data.put_node(Node {
+                id: id.clone(),
+                label: "Submission".to_string(),
+                properties: Properties::new(),
+            });
+            data.relate(&caller.subject, "SUBMITTED", &id, Properties::new());
+            data.relate(&id, "FOR", &assignment.id, Properties::new());
+            id
+        }
+    };
+
+    let submission = SubmissionInfo {
+        id: id.clone(),
+        assignment_id: assignment.id,
+        student_id: caller.subject,
+        content: op.content,
+        time_spent: op.time_spent,
+        submitted_at: op.timestamp,
+        days_late,
+        lesson_version,
+        grade: None,
+    };
+    save(data, "Submission", &id, &submission);
+    Ok(submission)
+}
+
+/// The student who handed it in, and teachers of the class.
+pub(crate) fn get_submission(
+    data: &GraphData,
+    auth: &Value,
+    query: GetSubmission,
+) -> Result<SubmissionInfo, ErrorCode> {
+    let submission: SubmissionInfo = load(data, "Submission", &query.submission_id)?;
+    if caller(auth)?.subject != submission.student_id {
+        let assignment: AssignmentInfo = load(data, "Assignment", &submission.assignment_id)?;
+        require_teacher(data, auth, &assignment.class_id)?;
+    }
+    Ok(submission)
+}
+
+pub(crate) fn grade(
+    data: &mut GraphData,
+    auth: &Value,
+    op: RecordGrade,
+) -> Result<SubmissionInfo, ErrorCode> {
+    let mut submission: SubmissionInfo = load(data, "Submission", &op.submission_id)?;
+    let assignment: AssignmentInfo = load(data, "Assignment", &submission.assignment_id)?;
+    require_teacher(data, auth, &assignment.class_id)?;
+    if !(0.0..=1.0).contains(&op.raw_score) {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    submission.grade = Some(Grade {
+        raw_score: op.raw_score,
+        score: op.raw_score * assignment.late_policy.retained(submission.days_late),
+        graded_at: op.timestamp,
+    });
+    save(data, "Submission", &submission.id, &submission);
+    Ok(submission)
+}
+
+/// Raw scores of every graded submission for the class's assignments, in
+/// assignment order.
+pub(crate) fn responses(
+    data: &GraphData,
+    auth: &Value,
+    query: ListResponses,
+) -> Result<Vec<ItemResponse>, ErrorCode> {
+    if data
+        .node(&query.class_id)
+        .is_none_or(|n| n.label != "Class")
+    {
+        return Err(ErrorCode::NotFound);
+    }
+    require_teacher(data, auth, &query.class_id)?;
+
+    let mut responses = Vec::new();
+    for assignment in data.outgoing(&query.class_id, "HAS_ASSIGNMENT") {
+        let submissions = data
+            .relationships()
+            .filter(|r| r.kind == "FOR" && r.to == assignment.to);
+        for rel in submissions {
+            let submission: SubmissionInfo = load(data, "Submission", &rel.from)?;
+            if let Some(grade) = submission.grade {
+                responses.push(ItemResponse {
+                    assignment_id: submission.assignment_id,
+                    student_id: submission.student_id,
+                    score: grade.raw_score,
+                });
+            }
+        }
+    }
+    Ok(responses)
+}
+
+pub(crate) fn calibrate(
+    data: &mut GraphData,
+    auth: &Value,
+    op: CalibrateAssignment,
+) -> Result<AssignmentInfo, ErrorCode> {
+    let mut assignment: AssignmentInfo = load(data, "Assignment", &op.assignment_id)?;
+    require_teacher(data, auth, &assignment.class_id)?;
+    let calibration = op.calibration;
+    if !calibration.difficulty.is_finite()
+        || !calibration.discrimination.is_finite()
+        || calibration.discrimination <= 0.0
+    {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    assignment.calibration = Some(calibration);
+    save(data, "Assignment", &assignment.id, &assignment);
+    Ok(assignment)
+}
@@ -0,0 +1,110 @@
+//! Concept nodes and the knowledge-tracing parameters stored on them.
+
+use fot_protocol::{BktParams, ConceptInfo, ErrorCode, Role, SetConceptModel, TopicConcept};
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Node, Properties};
+use crate::ops::caller;
+
+/// The Concept properties holding [`BktParams`], in field order.
+const BKT_KEYS: [&str; 4] = ["bkt_prior", "bkt_learn", "bkt_slip", "bkt_guess"];
+
+pub(crate) fn info(node: &Node) -> ConceptInfo {
+    let text = |key: &str| node.properties.get(key).and_then(Value::as_str);
+    ConceptInfo {
+        id: node.id.clone(),
+        label: text("label").unwrap_or(&node.id).to_string(),
+        domain: text("domain").map(str::to_string),
+        bkt: bkt(node),
+    }
+}
+
+/// The node's BKT parameters, when all four are set.
+fn bkt(node: &Node) -> Option<BktParams> {
+    let number = |key: &str| node.properties.get(key).and_then(Value::as_f64);
+    let [prior, learn, slip, guess] = BKT_KEYS.map(number);
+    Some(BktParams {
+        prior: prior?,
+        learn: learn?,
+        slip: slip?,
+        guess: guess?,
+    })
+}
+
+/// Out-of-range parameters fail `concept_bkt_range` at commit.
+pub(crate) fn set_model(
+    data: &mut GraphData,
+    auth: &Value,
+    op: SetConceptModel,
+) -> Result<ConceptInfo, ErrorCode> {
+    let caller = caller(auth)?;
+    if !matches!(caller.role, Role::Teacher | Role::Admin) {
+        return Err(ErrorCode::Forbidden);
+    }
+    let id = data
+        .find("Concept", &op.concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)?;
+
+    let node = data.node_mut(&id).expect("concept exists");
+    set_bkt(&mut node.properties, op.bkt);
+    Ok(info(node))
+}
+
+fn set_bkt(properties: &mut Properties, bkt: BktParams) {
+    let BktParams {
+        prior,
+        learn,
+        slip,
+        guess,
+    } = bkt;
+    for (key, value) in BKT_KEYS.into_iter().zip([prior, learn, slip, guess]) {
+        properties.insert(key.to_string(), json!(value));
+    }
+}
+
+/// Create the Concept node `concept.id` for `topic`, which then owns it, or
+/// update it, keeping properties the manifest does not set. Other topics may
+/// cover a concept but not change its label, domain or parameters.
+pub(crate) fn put(
+    data: &mut GraphData,
+    topic: &str,
+    concept: &TopicConcept,
+) -> Result<(), ErrorCode> {
+    if concept.id.trim().is_empty() || concept.label.trim().is_empty() {
+        return Err(ErrorCode::InvalidPayload);
+    }
+    let mut properties = match data.node(&concept.id) {
+        Some(node) if node.label != "Concept" => return Err(ErrorCode::InvalidPayload),
+        Some(node) if node.properties.get("topic").and_then(Value::as_str) != Some(topic) => {
+            let current = info(node);
+            let changed = current.label != concept.label
+                || concept.domain.is_some() && concept.domain != current.domain
+                || concept.bkt.is_some() && concept.bkt != current.bkt;
+            return if changed {
+                Err(ErrorCode::Forbidden)
+            } else {
+                Ok(())
+            };
+        }
+        Some(node) => node.properties.clone(),
+        None => {
+            let mut properties = Properties::new();
+            properties.insert("topic".to_string(), json!(topic));
+            properties
+        }
+    };
+    properties.insert("label".to_string(), json!(concept.label));
+    if let Some(domain) = &concept.domain {
+        properties.insert("domain".to_string(), json!(domain));
+    }
+    if let Some(bkt) = concept.bkt {
+        set_bkt(&mut properties, bkt);
+    }
+    data.put_node(Node {
+        id: concept.id.clone(),
+        label: "Concept".to_string(),
+        properties,
+    });
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Students' mastery goals, kept as `GOAL` relationships to Concepts with
+//! the fields of [`Goal`] as properties.
+
+use fot_agent_sdk::mastery::decay;
+use fot_protocol::{ErrorCode, Goal, GoalProgress, ListGoals, SetGoal};
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Properties, Relationship};
+use crate::ops::{acting_for, agent_node, caller, stored_mastery};
+
+pub(crate) fn goal(data: &GraphData, rel: &Relationship) -> Option<Goal> {
+    let properties = &rel.properties;
+    let number = |key: &str| properties.get(key).and_then(Value::as_f64);
+    Some(Goal {
+        concept_id: rel.to.clone(),
+        concept_name: data
+            .node(&rel.to)
+            .and_then(|n| n.properties.get("label"))
+            .and_then(Value::as_str)
+            .unwrap_or(&rel.to)
+            .to_string(),
+        target_mastery: number("target_mastery")?,
+        due_at: properties.get("due_at")?.as_u64()?,
+        set_at: properties.get("set_at")?.as_u64()?,
+        starting_mastery: number("starting_mastery")?,
+    })
+}
+
+pub(crate) fn set(data: &mut GraphData, auth: &Value, op: SetGoal) -> Result<Goal, ErrorCode> {
+    let caller = caller(auth)?;
+    let concept = data
+        .find("Concept", &op.concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)?;
+    if !(op.target_mastery > 0.0 && op.target_mastery <= 1.0) || op.due_at <= op.timestamp {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    agent_node(data, &caller);
+    let student = caller.subject.as_str();
+    let mut properties = Properties::new();
+    properties.insert("target_mastery".to_string(), json!(op.target_mastery));
+    properties.insert("due_at".to_string(), json!(op.due_at));
+    properties.insert("set_at".to_string(), json!(op.timestamp));
+    let (mastery, updated_at, review) = stored_mastery(data, student, &concept);
+    properties.insert(
+        "starting_mastery".to_string(),
+        json!(decay(mastery, updated_at, review, op.timestamp)),
+    );
+    let rel = data.merge_relationship(student, "GOAL", &concept);
+    data.relationship_mut(rel).expect("just merged").properties = properties;
+
+    let rel = data
+        .outgoing(student, "GOAL")
+        .find(|r| r.to == concept)
+        .expect("just merged");
+    goal(data, rel).ok_or(ErrorCode::Internal)
+}
+
+pub(crate) fn list(
+    data: &GraphData,
+    auth: &Value,
+    query: ListGoals,
+) -> Result<Vec<GoalProgress>, ErrorCode> {
+    let caller = caller(auth)?;
+    let student = acting_for(data, &caller, query.student_id.as_deref())?;
+    let mut goals: Vec<GoalProgress> = data
+        .outgoing(student, "GOAL")
+        .filter_map(|rel| goal(data, rel))
+        .map(|goal| {
+            let (mastery, updated_at, review) = stored_mastery(data, student, &goal.concept_id);
+            GoalProgress {
+                goal,
+                mastery,
+                updated_at,
+                review,
+            }
+        })
+        .collect();
+    goals.sort_by(|a, b| {
+        (a.goal.due_at, &a.goal.concept_name).cmp(&(b.goal.due_at, &b.goal.concept_name))
+    });
+    Ok(goals)
+}
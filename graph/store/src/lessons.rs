@@ -0,0 +1,161 @@
+//! Lesson nodes and their lifecycle.
+//!
+//! A Lesson node stores the fields of [`LessonInfo`] as properties. Edits
+//! push the replaced content onto `history` and bump `version`.
+
+use fot_protocol::{
+    ArchiveLesson, CreateLesson, ErrorCode, LessonCreated, LessonInfo, LessonStatus, LessonVersion,
+    ListLessons, PublishLesson, ScheduleLesson, UpdateLesson,
+};
+use serde_json::Value;
+
+use crate::model::{GraphData, Properties};
+use crate::ops::require_teacher;
+use crate::records;
+
+pub(crate) fn load(data: &GraphData, id: &str) -> Result<LessonInfo, ErrorCode> {
+    records::load(data, "Lesson", id)
+}
+
+fn save(data: &mut GraphData, lesson: &LessonInfo) {
+    records::save(data, "Lesson", &lesson.id, lesson);
+}
+
+/// Load a lesson the caller may change.
+fn editable(data: &GraphData, auth: &Value, id: &str) -> Result<LessonInfo, ErrorCode> {
+    let lesson = load(data, id)?;
+    require_teacher(data, auth, &lesson.class_id)?;
+    if lesson.status == LessonStatus::Archived {
+        return Err(ErrorCode::InvalidState);
+    }
+    Ok(lesson)
+}
+
+pub(crate) fn concept_id(data: &GraphData, concept: &str) -> Result<String, ErrorCode> {
+    data.find("Concept", concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)
+}
+
+/// Creates a draft Lesson node linked to its Concept and Class, and returns
+/// the new id.
+pub(crate) fn create(
+    data: &mut GraphData,
+    auth: &Value,
+    op: CreateLesson,
+) -> Result<LessonCreated, ErrorCode> {
+    require_teacher(data, auth, &op.class_id)?;
+    let concept = concept_id(data, &op.concept)?;
+
+    let id = data.fresh_id("lesson");
+    save(
+        data,
+        &LessonInfo {
+            id: id.clone(),
+            class_id: op.class_id.clone(),
+            concept: op.concept,
+            content: op.content,
+            status: LessonStatus::Draft,
+            version: 1,
+            opens_at: None,
+            closes_at: None,
+            created_at: op.timestamp,
+            updated_at: op.timestamp,
+            history: Vec::new(),
+        },
+    );
+    data.relate(&id, "COVERS", &concept, Properties::new());
+    data.relate(&op.class_id, "HAS_LESSON", &id, Properties::new());
+
+    Ok(LessonCreated { id })
+}
+
+pub(crate) fn update(
+    data: &mut GraphData,
+    auth: &Value,
+    op: UpdateLesson,
+) -> Result<LessonInfo, ErrorCode> {
+    let mut lesson = editable(data, auth, &op.lesson_id)?;
+    lesson.history.push(LessonVersion {
+        version: lesson.version,
+        concept: lesson.concept.clone(),
+        content: lesson.content.clone(),
+        replaced_at: op.timestamp,
+    });
+    lesson.version += 1;
+    lesson.updated_at = op.timestamp;
+
+    if let Some(concept) = op.concept {
+        let id = concept_id(data, &concept)?;
+        data.remove_outgoing(&lesson.id, "COVERS");
+        data.relate(&lesson.id, "COVERS", &id, Properties::new());
+        lesson.concept = concept;
+    }
+    if let Some(content) = op.content {
+        lesson.content = content;
+    }
+
+    save(data, &lesson);
+    Ok(lesson)
+}
+
+pub(crate) fn publish(
+    data: &mut GraphData,
+    auth: &Value,
+    op: PublishLesson,
+) -> Result<LessonInfo, ErrorCode> {
+    let mut lesson = editable(data, auth, &op.lesson_id)?;
+    if lesson.status != LessonStatus::Draft {
+        return Err(ErrorCode::InvalidState);
+    }
+    lesson.status = LessonStatus::Published;
+    lesson.updated_at = op.timestamp;
+    save(data, &lesson);
+    Ok(lesson)
+}
+
+pub(crate) fn schedule(
+    data: &mut GraphData,
+    auth: &Value,
+    op: ScheduleLesson,
+) -> Result<LessonInfo, ErrorCode> {
+    let mut lesson = editable(data, auth, &op.lesson_id)?;
+    if let (Some(opens), Some(closes)) = (op.opens_at, op.closes_at) {
+        if closes <= opens {
+            return Err(ErrorCode::InvalidPayload);
+        }
+    }
+    lesson.opens_at = op.opens_at;
+    lesson.closes_at = op.closes_at;
+    lesson.updated_at = op.timestamp;
+    save(data, &lesson);
+    Ok(lesson)
+}
+
+pub(crate) fn archive(
+    data: &mut GraphData,
+    auth: &Value,
+    op: ArchiveLesson,
+) -> Result<LessonInfo, ErrorCode> {
+    let mut lesson = editable(data, auth, &op.lesson_id)?;
+    lesson.status = LessonStatus::Archived;
+    lesson.updated_at = op.timestamp;
+    save(data, &lesson);
+    Ok(lesson)
+}
+
+/// Lessons of a class, oldest first.
+pub(crate) fn list(
+    data: &GraphData,
+    auth: &Value,
+    query: ListLessons,
+) -> Result<Vec<LessonInfo>, ErrorCode> {
+    require_teacher(data, auth, &query.class_id)?;
+    let mut lessons = data
+        .outgoing(&query.class_id, "HAS_LESSON")
+        .map(|r| load(data, &r.to))
+        .collect::<Result<Vec<_>, _>>()?;
+    lessons.retain(|l| query.status.is_none_or(|s| l.status == s));
+    lessons.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+    Ok(lessons)
+}
@@ -0,0 +1,252 @@
+//! Embedded property-graph store implementing the `graph.wit` contract.
+//!
+//! `GraphStore` stands in for Neo4j during development and tests. It models
+//! the Concept/Claim/Agent/Virtue nodes and LEARNS relationships from
+//! `graph/cypher/schema.cql`, answers the [`fot_protocol`] operations agents
+//! send through `graph_read`/`graph_write`, and enforces the schema
+//! constraints when a transaction commits.
+//!
+//! Every `begin_txn` opens its own transaction. Reads and writes join it by
+//! naming it in the envelope's `txn` field; its writes are staged on a
+//! private copy of the graph that only it reads, and become visible to
+//! everyone else on commit. If other writes committed in the meantime, the
+//! transaction's writes are replayed on top of them. A commit that breaks a
+//! constraint rolls the whole transaction back, as does `rollback_txn`, and
+//! neither touches any other transaction. Writes outside a transaction
+//! commit immediately, one mutation at a time.
+
+mod activity;
+mod assignments;
+mod concepts;
+mod goals;
+mod lessons;
+mod links;
+pub mod model;
+mod ops;
+mod questions;
+mod records;
+mod roster;
+mod topics;
+mod virtues;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use fot_agent_sdk::host::Graph;
+use fot_protocol::ErrorCode;
+use serde::Deserialize;
+use serde_json::Value;
+
+pub use model::{GraphData, Node, Properties, Relationship, Violation};
+
+#[derive(Default)]
+pub struct GraphStore {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    committed: GraphData,
+    /// Bumped on every commit, so a transaction can tell whether its copy
+    /// is still current.
+    version: u64,
+    txns: HashMap<u64, Txn>,
+    next_txn: u64,
+    auth: Value,
+}
+
+/// An open transaction: the committed version it started from, its staged
+/// copy of the graph, and the writes that made it, with the identity each
+/// ran as, to replay if the graph moved on.
+struct Txn {
+    base: u64,
+    data: GraphData,
+    writes: Vec<(String, Value)>,
+}
+
+impl State {
+    /// The graph a payload sees: its transaction's copy, or the committed
+    /// graph when it names none.
+    fn visible(&self, txn: Option<u64>) -> Result<&GraphData, ErrorCode> {
+        match txn {
+            None => Ok(&self.committed),
+            Some(id) => self
+                .txns
+                .get(&id)
+                .map(|t| &t.data)
+                .ok_or(ErrorCode::InvalidHandle),
+        }
+    }
+}
+
+/// The transaction a payload's envelope names.
+fn txn_of(payload: &str) -> Result<Option<u64>, ErrorCode> {
+    #[derive(Deserialize)]
+    struct Scope {
+        #[serde(default)]
+        txn: Option<u64>,
+    }
+    serde_json::from_str::<Scope>(payload)
+        .map(|scope| scope.txn)
+        .map_err(|_| ErrorCode::InvalidPayload)
+}
+
+impl GraphStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the caller identity returned by `auth_ctx` and used by writes.
+    pub fn set_auth_ctx(&self, ctx: Value) {
+        self.state.lock().unwrap().auth = ctx;
+    }
+
+    /// A copy of the committed graph.
+    pub fn snapshot(&self) -> GraphData {
+        self.state.lock().unwrap().committed.clone()
+    }
+
+    /// Add a node directly to the committed graph, e.g. when seeding.
+    pub fn add_node(&self, id: &str, label: &str, properties: Value) -> Result<(), Violation> {
+        let node = Node {
+            id: id.to_string(),
+            label: label.to_string(),
+            properties: into_properties(properties),
+        };
+        self.commit_with(|data| data.create_node(node))
+    }
+
+    /// Add a relationship directly to the committed graph.
+    pub fn relate(
+        &self,
+        from: &str,
+        kind: &str,
+        to: &str,
+        properties: Value,
+    ) -> Result<u64, Violation> {
+        self.commit_with(|data| Ok(data.relate(from, kind, to, into_properties(properties))))
+    }
+
+    /// Whether any transaction is still open.
+    pub fn in_txn(&self) -> bool {
+        !self.state.lock().unwrap().txns.is_empty()
+    }
+
+    /// Apply `change` to a copy of the committed graph and keep it only if
+    /// it succeeds and passes validation.
+    fn commit_with<T>(
+        &self,
+        change: impl FnOnce(&mut GraphData) -> Result<T, Violation>,
+    ) -> Result<T, Violation> {
+        let mut state = self.state.lock().unwrap();
+        let mut data = state.committed.clone();
+        let out = change(&mut data)?;
+        data.validate()?;
+        state.committed = data;
+        state.version += 1;
+        Ok(out)
+    }
+}
+
+fn into_properties(value: Value) -> Properties {
+    match value {
+        Value::Object(map) => map,
+        _ => Properties::new(),
+    }
+}
+
+impl Graph for GraphStore {
+    fn graph_read(&self, query: &str) -> Result<String, u32> {
+        let state = self.state.lock().unwrap();
+        let data = state.visible(txn_of(query)?)?;
+        Ok(ops::read(data, &state.auth, query)?)
+    }
+
+    fn graph_write(&self, mutation: &str) -> Result<String, u32> {
+        let txn = txn_of(mutation)?;
+        let mut state = self.state.lock().unwrap();
+        let State {
+            committed,
+            version,
+            txns,
+            auth,
+            ..
+        } = &mut *state;
+
+        // Each mutation is atomic: it runs on a scratch copy that replaces
+        // the target only if the operation succeeds. Outside a transaction
+        // it also has to pass validation on its own.
+        match txn {
+            Some(id) => {
+                let open = txns.get_mut(&id).ok_or(ErrorCode::InvalidHandle)?;
+                let mut scratch = open.data.clone();
+                let reply = ops::write(&mut scratch, auth, mutation)?;
+                open.data = scratch;
+                open.writes.push((mutation.to_string(), auth.clone()));
+                Ok(reply)
+            }
+            None => {
+                let mut scratch = committed.clone();
+                let reply = ops::write(&mut scratch, auth, mutation)?;
+                scratch
+                    .validate()
+                    .map_err(|_| ErrorCode::ConstraintViolation)?;
+                *committed = scratch;
+                *version += 1;
+                Ok(reply)
+            }
+        }
+    }
+
+    fn begin_txn(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_txn += 1;
+        let id = state.next_txn;
+        let txn = Txn {
+            base: state.version,
+            data: state.committed.clone(),
+            writes: Vec::new(),
+        };
+        state.txns.insert(id, txn);
+        id
+    }
+
+    fn commit_txn(&self, txn: u64) -> Result<(), u32> {
+        let mut state = self.state.lock().unwrap();
+        let open = state.txns.remove(&txn).ok_or(ErrorCode::InvalidHandle)?;
+
+        let data = if open.base == state.version {
+            open.data
+        } else {
+            let mut data = state.committed.clone();
+            for (mutation, auth) in &open.writes {
+                ops::write(&mut data, auth, mutation)
+                    .map_err(|_| ErrorCode::ConstraintViolation)?;
+            }
+            data
+        };
+        data.validate()
+            .map_err(|_| ErrorCode::ConstraintViolation)?;
+        state.committed = data;
+        state.version += 1;
+        Ok(())
+    }
+
+    /// Discards transaction `txn`; other open transactions are untouched.
+    fn rollback_txn(&self, txn: u64) -> Result<(), u32> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .txns
+            .remove(&txn)
+            .map(drop)
+            .ok_or(ErrorCode::InvalidHandle.code())
+    }
+
+    fn auth_ctx(&self) -> String {
+        let state = self.state.lock().unwrap();
+        match &state.auth {
+            Value::Null => "{}".to_string(),
+            auth => auth.to_string(),
+        }
+    }
+}
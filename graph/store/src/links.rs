@@ -0,0 +1,170 @@
+//! Typed links between Concepts.
+//!
+//! A `fot:DomainLink` from the seeds becomes a `LINKS` relationship from its
+//! source Concept to its target, with `link_type` and `comment` properties.
+//! A student following a link is a `FOLLOWED` relationship to the Concept
+//! they went to.
+
+use std::collections::BTreeSet;
+
+use fot_protocol::{
+    ConceptLink, ConceptMap, ErrorCode, FollowLink, FollowedLink, LinkConcepts, LinkType,
+    ListConceptLinks, ListFollowedLinks, Role,
+};
+use serde_json::{json, Value};
+
+use crate::concepts;
+use crate::model::{GraphData, Properties, Relationship};
+use crate::ops::{acting_for, agent_node, caller, stored_mastery};
+
+fn link(rel: &Relationship) -> Option<ConceptLink> {
+    let properties = &rel.properties;
+    Some(ConceptLink {
+        source: rel.from.clone(),
+        target: rel.to.clone(),
+        link_type: serde_json::from_value(properties.get("link_type")?.clone()).ok()?,
+        comment: properties
+            .get("comment")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    })
+}
+
+pub(crate) fn create(
+    data: &mut GraphData,
+    auth: &Value,
+    op: LinkConcepts,
+) -> Result<ConceptLink, ErrorCode> {
+    let caller = caller(auth)?;
+    if !matches!(caller.role, Role::Teacher | Role::Admin) {
+        return Err(ErrorCode::Forbidden);
+    }
+    let concept = |key: &str| {
+        data.find("Concept", key)
+            .map(|n| n.id.clone())
+            .ok_or(ErrorCode::NotFound)
+    };
+    let (source, target) = (concept(&op.source)?, concept(&op.target)?);
+    if source == target {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    let existing = data
+        .outgoing(&source, "LINKS")
+        .find(|r| r.to == target && link(r).is_some_and(|l| l.link_type == op.link_type))
+        .map(|r| r.id);
+    let id = existing.unwrap_or_else(|| data.relate(&source, "LINKS", &target, Default::default()));
+    let properties = &mut data.relationship_mut(id).expect("just found").properties;
+    properties.insert("link_type".to_string(), json!(op.link_type));
+    match &op.comment {
+        Some(comment) => properties.insert("comment".to_string(), json!(comment)),
+        None => properties.remove("comment"),
+    };
+    Ok(ConceptLink {
+        source,
+        target,
+        link_type: op.link_type,
+        comment: op.comment,
+    })
+}
+
+/// Readable by anyone signed in: the concept map is not personal data.
+pub(crate) fn list(
+    data: &GraphData,
+    auth: &Value,
+    query: ListConceptLinks,
+) -> Result<ConceptMap, ErrorCode> {
+    caller(auth)?;
+    let wanted = |t: &LinkType| query.link_types.is_empty() || query.link_types.contains(t);
+    let mut links: Vec<ConceptLink> = data
+        .relationships()
+        .filter(|r| r.kind == "LINKS")
+        .filter_map(link)
+        .filter(|l| wanted(&l.link_type))
+        .collect();
+    links.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+
+    let ids: BTreeSet<&str> = links
+        .iter()
+        .flat_map(|l| [l.source.as_str(), l.target.as_str()])
+        .collect();
+    let concepts = ids
+        .into_iter()
+        .filter_map(|id| data.node(id))
+        .map(concepts::info)
+        .collect();
+    Ok(ConceptMap { concepts, links })
+}
+
+/// Fails with `NotFound` unless a link joins the two Concepts.
+pub(crate) fn follow(
+    data: &mut GraphData,
+    auth: &Value,
+    op: FollowLink,
+) -> Result<FollowedLink, ErrorCode> {
+    let caller = caller(auth)?;
+    let concept = |key: &str| {
+        data.find("Concept", key)
+            .map(|n| n.id.clone())
+            .ok_or(ErrorCode::NotFound)
+    };
+    let (source, target) = (concept(&op.source)?, concept(&op.target)?);
+    let joining = |from: &str, to: &str| {
+        data.outgoing(from, "LINKS")
+            .filter(|r| r.to == to)
+            .find_map(link)
+    };
+    let link = joining(&source, &target)
+        .or_else(|| joining(&target, &source))
+        .ok_or(ErrorCode::NotFound)?;
+
+    let student = caller.subject.as_str();
+    let followed = FollowedLink {
+        source_mastery: stored_mastery(data, student, &source).0,
+        target_mastery_before: stored_mastery(data, student, &target).0,
+        target_mastery: stored_mastery(data, student, &target).0,
+        source,
+        target,
+        link_type: link.link_type,
+        followed_at: op.timestamp,
+    };
+    agent_node(data, &caller);
+    let mut properties = Properties::new();
+    properties.insert("source".to_string(), json!(followed.source));
+    properties.insert("link_type".to_string(), json!(followed.link_type));
+    properties.insert("followed_at".to_string(), json!(followed.followed_at));
+    properties.insert("source_mastery".to_string(), json!(followed.source_mastery));
+    properties.insert(
+        "target_mastery_before".to_string(),
+        json!(followed.target_mastery_before),
+    );
+    data.relate(student, "FOLLOWED", &followed.target, properties);
+    Ok(followed)
+}
+
+pub(crate) fn followed(
+    data: &GraphData,
+    auth: &Value,
+    query: ListFollowedLinks,
+) -> Result<Vec<FollowedLink>, ErrorCode> {
+    let caller = caller(auth)?;
+    let student = acting_for(data, &caller, query.student_id.as_deref())?;
+    let mut followed: Vec<FollowedLink> = data
+        .outgoing(student, "FOLLOWED")
+        .filter_map(|rel| {
+            let properties = &rel.properties;
+            let number = |key: &str| properties.get(key).and_then(Value::as_f64);
+            Some(FollowedLink {
+                source: properties.get("source")?.as_str()?.to_string(),
+                target: rel.to.clone(),
+                link_type: serde_json::from_value(properties.get("link_type")?.clone()).ok()?,
+                followed_at: properties.get("followed_at")?.as_u64()?,
+                source_mastery: number("source_mastery")?,
+                target_mastery_before: number("target_mastery_before")?,
+                target_mastery: stored_mastery(data, student, &rel.to).0,
+            })
+        })
+        .collect();
+    followed.sort_by_key(|f| f.followed_at);
+    Ok(followed)
+}
@@ -0,0 +1,229 @@
+//! Property-graph data and the constraints from `graph/cypher/schema.cql`.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+pub type Properties = Map<String, Value>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Node {
+    pub id: String,
+    pub label: String,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Relationship {
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub properties: Properties,
+}
+
+/// A constraint from `schema.cql` that a write would break.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub constraint: &'static str,
+    pub subject: String,
+}
+
+/// One consistent snapshot of the graph.
+#[derive(Debug, Clone, Default)]
+pub struct GraphData {
+    nodes: BTreeMap<String, Node>,
+    relationships: BTreeMap<u64, Relationship>,
+    next_relationship: u64,
+    next_node: u64,
+}
+
+impl GraphData {
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    pub fn node_mut(&mut self, id: &str) -> Option<&mut Node> {
+        self.nodes.get_mut(id)
+    }
+
+    pub fn nodes_with_label<'a>(&'a self, label: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.nodes.values().filter(move |n| n.label == label)
+    }
+
+    /// Find a node of `label` by id or, failing that, by its `label` property.
+    pub fn find<'a>(&'a self, label: &'a str, key: &str) -> Option<&'a Node> {
+        self.nodes
+            .get(key)
+            .filter(|n| n.label == label)
+            .or_else(|| {
+                self.nodes_with_label(label)
+                    .find(|n| n.properties.get("label").and_then(Value::as_str) == Some(key))
+            })
+    }
+
+    /// Insert or replace a node.
+    pub fn put_node(&mut self, node: Node) {
+        self.nodes.insert(node.id.clone(), node);
+    }
+
+    /// Insert a node, enforcing the `*_id_unique` constraints.
+    pub fn create_node(&mut self, node: Node) -> Result<(), Violation> {
+        if self.nodes.contains_key(&node.id) {
+            let constraint = match node.label.as_str() {
+                "Concept" => "concept_id_unique",
+                "Claim" => "claim_id_unique",
+                "Agent" => "agent_id_unique",
+                "Virtue" => "virtue_id_unique",
+                _ => "node_id_unique",
+            };
+            return Err(violation(constraint, &node.id));
+        }
+        self.put_node(node);
+        Ok(())
+    }
+
+    /// Delete a node and every relationship to or from it.
+    pub fn remove_node(&mut self, id: &str) {
+        self.nodes.remove(id);
+        self.relationships.retain(|_, r| r.from != id && r.to != id);
+    }
+
+    /// Allocate an id of the form `{prefix}-{n}` that no node uses yet.
+    pub fn fresh_id(&mut self, prefix: &str) -> String {
+        loop {
+            self.next_node += 1;
+            let id = format!("{prefix}-{}", self.next_node);
+            if !self.nodes.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    pub fn relationships(&self) -> impl Iterator<Item = &Relationship> {
+        self.relationships.values()
+    }
+
+    /// Relationships of `kind` leaving `from`.
+    pub fn outgoing<'a>(
+        &'a self,
+        from: &'a str,
+        kind: &'a str,
+    ) -> impl Iterator<Item = &'a Relationship> {
+        self.relationships
+            .values()
+            .filter(move |r| r.from == from && r.kind == kind)
+    }
+
+    pub fn relationship_mut(&mut self, id: u64) -> Option<&mut Relationship> {
+        self.relationships.get_mut(&id)
+    }
+
+    pub fn relate(&mut self, from: &str, kind: &str, to: &str, properties: Properties) -> u64 {
+        self.next_relationship += 1;
+        let id = self.next_relationship;
+        self.relationships.insert(
+            id,
+            Relationship {
+                id,
+                kind: kind.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                properties,
+            },
+        );
+        id
+    }
+
+    /// Delete every `kind` relationship leaving `from`.
+    pub fn remove_outgoing(&mut self, from: &str, kind: &str) {
+        self.relationships
+            .retain(|_, r| !(r.from == from && r.kind == kind));
+    }
+
+    /// The single `kind` relationship from `from` to `to`, created if absent.
+    pub fn merge_relationship(&mut self, from: &str, kind: &str, to: &str) -> u64 {
+        let existing = self
+            .relationships
+            .values()
+            .find(|r| r.from == from && r.kind == kind && r.to == to)
+            .map(|r| r.id);
+        existing.unwrap_or_else(|| self.relate(from, kind, to, Properties::new()))
+    }
+
+    /// Check the schema constraints; used before a transaction commits.
+    pub fn validate(&self) -> Result<(), Violation> {
+        for node in self.nodes.values() {
+            let has = |key: &str| node.properties.get(key).is_some_and(|v| !v.is_null());
+            match node.label.as_str() {
+                "Concept" if !has("label") => {
+                    return Err(violation("concept_label_exists", &node.id))
+                }
+                "Concept" if !bkt_in_range(node) => {
+                    return Err(violation("concept_bkt_range", &node.id))
+                }
+                "Claim" if !has("state") => return Err(violation("claim_state_exists", &node.id)),
+                "Claim" if !in_unit_range(node.properties.get("confidence")) => {
+                    return Err(violation("claim_confidence_range", &node.id))
+                }
+                // (role, class_id): a class_id names exactly one enrollment.
+                "Agent" if has("class_id") => {
+                    let enrollments = self.outgoing(&node.id, "ENROLLED_IN").count();
+                    if enrollments != 1 {
+                        return Err(violation("enrollment_matches_class_id", &node.id));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for rel in self.relationships.values() {
+            if !self.nodes.contains_key(&rel.from) || !self.nodes.contains_key(&rel.to) {
+                return Err(violation(
+                    "relationship_endpoints_exist",
+                    &rel.id.to_string(),
+                ));
+            }
+            if rel.kind == "ENROLLED_IN" {
+                let class_id = self.nodes[&rel.from].properties.get("class_id");
+                if class_id.and_then(Value::as_str) != Some(rel.to.as_str()) {
+                    return Err(violation("enrollment_matches_class_id", &rel.from));
+                }
+            }
+            if rel.kind == "LEARNS" {
+                for key in ["mastery", "curiosity", "patience", "honesty"] {
+                    if rel.properties.contains_key(key) && !in_unit_range(rel.properties.get(key)) {
+                        return Err(violation("learns_unit_range", &rel.id.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn violation(constraint: &'static str, subject: &str) -> Violation {
+    Violation {
+        constraint,
+        subject: subject.to_string(),
+    }
+}
+
+/// Knowledge-tracing parameters are probabilities, and a correct answer must
+/// stay evidence of mastery: `slip + guess < 1`.
+fn bkt_in_range(node: &Node) -> bool {
+    let number = |key: &str| node.properties.get(key).and_then(Value::as_f64);
+    ["bkt_prior", "bkt_learn", "bkt_slip", "bkt_guess"]
+        .iter()
+        .all(|key| in_unit_range(node.properties.get(*key)))
+        && number("bkt_slip").unwrap_or(0.0) + number("bkt_guess").unwrap_or(0.0) < 1.0
+}
+
+/// Absent or a number in `[0, 1]`.
+fn in_unit_range(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(v) => v.as_f64().is_some_and(|x| (0.0..=1.0).contains(&x)),
+    }
+}
@@ -0,0 +1,393 @@
+//! Handlers for the typed operations agents send through `graph_read` and
+//! `graph_write`.
+
+use fot_protocol::{
+    AuthContext, ChildProgress, ConceptProgress, DueReview, DueReviews, Envelope, ErrorCode,
+    GetChildProgress, GetClassProgress, GetMastery, GraphRead, GraphWrite, Guardianship,
+    MasterySample, MasteryState, MasteryUpdated, ReviewSchedule, Role, Teaching, UpdateMastery,
+    Virtues,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Node, Properties};
+use crate::{
+    activity, assignments, concepts, goals, lessons, links, questions, roster, topics, virtues,
+};
+
+pub(crate) fn read(data: &GraphData, auth: &Value, query: &str) -> Result<String, ErrorCode> {
+    match Envelope::<GraphRead>::decode(query)?.op {
+        GraphRead::GetMastery(query) => encode(get_mastery(data, auth, query)?),
+        GraphRead::DueReviews(query) => encode(due_reviews(data, auth, query)?),
+        GraphRead::GetChildProgress(query) => encode(get_child_progress(data, auth, query)?),
+        GraphRead::GetClassProgress(query) => encode(get_class_progress(data, auth, query)?),
+        GraphRead::GetConcept(query) => {
+            encode(data.find("Concept", &query.concept).map(concepts::info))
+        }
+        GraphRead::TeachesClass(query) => {
+            let caller = caller(auth)?;
+            encode(Teaching {
+                teaches: teaches(data, &caller.subject, &query.class_id),
+            })
+        }
+        GraphRead::ListLessons(query) => encode(lessons::list(data, auth, query)?),
+        GraphRead::ListRoster(query) => encode(roster::list(data, auth, query)?),
+        GraphRead::GetAssignment(query) => encode(assignments::get(data, auth, query)?),
+        GraphRead::GetSubmission(query) => encode(assignments::get_submission(data, auth, query)?),
+        GraphRead::ListResponses(query) => encode(assignments::responses(data, auth, query)?),
+        GraphRead::ListConceptLinks(query) => encode(links::list(data, auth, query)?),
+        GraphRead::ListFollowedLinks(query) => encode(links::followed(data, auth, query)?),
+        GraphRead::ActivityHistory(query) => encode(activity::history(data, auth, query)?),
+        GraphRead::ListGoals(query) => encode(goals::list(data, auth, query)?),
+        GraphRead::ListQuestions(query) => encode(questions::list(data, auth, query)?),
+        GraphRead::GetTopic(query) => encode(topics::get(data, auth, query)?),
+        GraphRead::IsGuardian(query) => {
+            let caller = caller(auth)?;
+            encode(Guardianship {
+                guardian: is_guardian(data, &caller.subject, &query.child_id),
+            })
+        }
+    }
+}
+
+pub(crate) fn write(
+    data: &mut GraphData,
+    auth: &Value,
+    mutation: &str,
+) -> Result<String, ErrorCode> {
+    match Envelope::<GraphWrite>::decode(mutation)?.op {
+        GraphWrite::CreateLesson(op) => encode(lessons::create(data, auth, op)?),
+        GraphWrite::UpdateLesson(op) => encode(lessons::update(data, auth, op)?),
+        GraphWrite::PublishLesson(op) => encode(lessons::publish(data, auth, op)?),
+        GraphWrite::ScheduleLesson(op) => encode(lessons::schedule(data, auth, op)?),
+        GraphWrite::ArchiveLesson(op) => encode(lessons::archive(data, auth, op)?),
+        GraphWrite::CreateClass(op) => encode(roster::create_class(data, auth, op)?),
+        GraphWrite::EnrollStudent(op) => encode(roster::enroll(data, auth, op)?),
+        GraphWrite::RemoveStudent(op) => encode(roster::remove(data, auth, op)?),
+        GraphWrite::TransferStudent(op) => encode(roster::transfer(data, auth, op)?),
+        GraphWrite::CreateAssignment(op) => encode(assignments::create(data, auth, op)?),
+        GraphWrite::Submit(op) => encode(assignments::submit(data, auth, op)?),
+        GraphWrite::RecordGrade(op) => encode(assignments::grade(data, auth, op)?),
+        GraphWrite::CalibrateAssignment(op) => encode(assignments::calibrate(data, auth, op)?),
+        GraphWrite::UpdateMastery(op) => encode(update_mastery(data, auth, op)?),
+        GraphWrite::SetConceptModel(op) => encode(concepts::set_model(data, auth, op)?),
+        GraphWrite::LinkConcepts(op) => encode(links::create(data, auth, op)?),
+        GraphWrite::FollowLink(op) => encode(links::follow(data, auth, op)?),
+        GraphWrite::SetGoal(op) => encode(goals::set(data, auth, op)?),
+        GraphWrite::AddQuestion(op) => encode(questions::add(data, auth, op)?),
+        GraphWrite::InstallTopic(op) => encode(topics::install(data, auth, op)?),
+        GraphWrite::RecordVirtues(op) => encode(virtues::record(data, auth, op)?),
+    }
+}
+
+fn encode(output: impl Serialize) -> Result<String, ErrorCode> {
+    serde_json::to_string(&output).map_err(|_| ErrorCode::Internal)
+}
+
+/// The caller, as set through `GraphStore::set_auth_ctx`.
+pub(crate) fn caller(auth: &Value) -> Result<AuthContext, ErrorCode> {
+    AuthContext::deserialize(auth).map_err(|_| ErrorCode::Unauthenticated)
+}
+
+fn teaches(data: &GraphData, teacher: &str, class_id: &str) -> bool {
+    data.outgoing(teacher, "TEACHES").any(|r| r.to == class_id)
+}
+
+/// Fail unless the caller teaches `class_id` or is an admin.
+pub(crate) fn require_teacher(
+    data: &GraphData,
+    auth: &Value,
+    class_id: &str,
+) -> Result<(), ErrorCode> {
+    let caller = caller(auth)?;
+    if caller.role == Role::Admin || teaches(data, &caller.subject, class_id) {
+        Ok(())
+    } else {
+        Err(ErrorCode::Forbidden)
+    }
+}
+
+/// `student_id`, or the caller when `None`, if the caller may read and write
+/// their learning records: themselves, an admin, or a teacher of their class.
+pub(crate) fn acting_for<'a>(
+    data: &GraphData,
+    caller: &'a AuthContext,
+    student_id: Option<&'a str>,
+) -> Result<&'a str, ErrorCode> {
+    let student = student_id.unwrap_or(&caller.subject);
+    if student == caller.subject
+        || caller.role == Role::Admin
+        || teaches_student(data, &caller.subject, student)
+    {
+        Ok(student)
+    } else {
+        Err(ErrorCode::Forbidden)
+    }
+}
+
+/// Whether `teacher` teaches the class `student` is enrolled in.
+fn teaches_student(data: &GraphData, teacher: &str, student: &str) -> bool {
+    data.outgoing(student, "ENROLLED_IN")
+        .any(|r| teaches(data, teacher, &r.to))
+}
+
+fn is_guardian(data: &GraphData, guardian: &str, child: &str) -> bool {
+    data.outgoing(guardian, "GUARDIAN_OF")
+        .any(|r| r.to == child)
+}
+
+/// Create the caller's Agent node if it is not in the graph yet.
+pub(crate) fn agent_node(data: &mut GraphData, caller: &AuthContext) {
+    if data.node(&caller.subject).is_none() {
+        let mut properties = Properties::new();
+        properties.insert("role".to_string(), json!(caller.role));
+        data.put_node(Node {
+            id: caller.subject.clone(),
+            label: "Agent".to_string(),
+            properties,
+        });
+    }
+}
+
+/// The stored `LEARNS` mastery of `student` for `concept_id`, 0 if none,
+/// with when it was last updated and its review schedule.
+pub(crate) fn stored_mastery(
+    data: &GraphData,
+    student: &str,
+    concept_id: &str,
+) -> (f64, Option<u64>, Option<ReviewSchedule>) {
+    let learns = data
+        .outgoing(student, "LEARNS")
+        .find(|r| r.to == concept_id)
+        .map(|r| &r.properties);
+    (
+        learns
+            .and_then(|p| p.get("mastery"))
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0),
+        learns
+            .and_then(|p| p.get("updated_at"))
+            .and_then(Value::as_u64),
+        learns.and_then(review),
+    )
+}
+
+/// Sets the student's `LEARNS` mastery for `concept`. The caller's own Agent
+/// node is created on first use.
+fn update_mastery(
+    data: &mut GraphData,
+    auth: &Value,
+    op: UpdateMastery,
+) -> Result<MasteryUpdated, ErrorCode> {
+    let caller = caller(auth)?;
+    let subject = acting_for(data, &caller, op.student_id.as_deref())?;
+    let concept = data
+        .find("Concept", &op.concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)?;
+
+    if subject == caller.subject {
+        agent_node(data, &caller);
+    } else if data.node(subject).is_none() {
+        return Err(ErrorCode::NotFound);
+    }
+
+    let rel = data.merge_relationship(subject, "LEARNS", &concept);
+    let learns = &mut data.relationship_mut(rel).expect("just merged").properties;
+    let revision = revision(learns);
+    if op.if_revision.is_some_and(|expected| expected != revision) {
+        return Err(ErrorCode::InvalidState);
+    }
+    learns.insert("revision".to_string(), json!(revision + 1));
+    let mastery = op.mastery;
+    learns.insert("mastery".to_string(), json!(mastery));
+    learns.insert(
+        "truth_field_strength".to_string(),
+        json!(op.truth_field_strength),
+    );
+    learns.insert(
+        "harmonic_coherence".to_string(),
+        json!(op.harmonic_coherence),
+    );
+    learns.insert("context".to_string(), json!(op.context));
+    learns.insert("updated_at".to_string(), json!(op.timestamp));
+    if let Some(review) = op.review {
+        learns.insert("review".to_string(), json!(review));
+    }
+    let sample = json!(MasterySample {
+        at: op.timestamp,
+        mastery,
+    });
+    match learns.get_mut("history").and_then(Value::as_array_mut) {
+        Some(history) => history.push(sample),
+        None => {
+            learns.insert("history".to_string(), json!([sample]));
+        }
+    }
+
+    Ok(MasteryUpdated {
+        concept_id: concept,
+        mastery,
+    })
+}
+
+fn get_mastery(
+    data: &GraphData,
+    auth: &Value,
+    query: GetMastery,
+) -> Result<MasteryState, ErrorCode> {
+    let caller = caller(auth)?;
+    let student = acting_for(data, &caller, query.student_id.as_deref())?;
+    let concept = data
+        .find("Concept", &query.concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)?;
+    let learns = data
+        .outgoing(student, "LEARNS")
+        .find(|r| r.to == concept)
+        .map(|r| &r.properties);
+    Ok(MasteryState {
+        concept_id: concept,
+        mastery: learns
+            .and_then(|p| p.get("mastery"))
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0),
+        updated_at: learns
+            .and_then(|p| p.get("updated_at"))
+            .and_then(Value::as_u64),
+        review: learns.and_then(review),
+        revision: learns.map_or(0, revision),
+    })
+}
+
+/// Updates made to a `LEARNS` relationship so far.
+fn revision(learns: &Properties) -> u64 {
+    learns.get("revision").and_then(Value::as_u64).unwrap_or(0)
+}
+
+fn review(learns: &Properties) -> Option<ReviewSchedule> {
+    serde_json::from_value(learns.get("review")?.clone()).ok()
+}
+
+fn due_reviews(
+    data: &GraphData,
+    auth: &Value,
+    query: DueReviews,
+) -> Result<Vec<DueReview>, ErrorCode> {
+    let caller = caller(auth)?;
+    let mut due: Vec<DueReview> = data
+        .outgoing(&caller.subject, "LEARNS")
+        .filter_map(|rel| {
+            let review = review(&rel.properties).filter(|r| r.due_at <= query.until)?;
+            let props = &rel.properties;
+            Some(DueReview {
+                concept_id: rel.to.clone(),
+                concept_name: data
+                    .node(&rel.to)
+                    .and_then(|n| n.properties.get("label"))
+                    .and_then(Value::as_str)
+                    .unwrap_or(&rel.to)
+                    .to_string(),
+                mastery: props.get("mastery").and_then(Value::as_f64).unwrap_or(0.0),
+                updated_at: props.get("updated_at").and_then(Value::as_u64),
+                review,
+            })
+        })
+        .collect();
+    due.sort_by_key(|d| d.review.due_at);
+    Ok(due)
+}
+
+/// The child's `LEARNS` relationships plus virtue averages across them.
+/// Mastery history is cut at `since`, with the last earlier sample reported
+/// as the baseline.
+fn get_child_progress(
+    data: &GraphData,
+    auth: &Value,
+    query: GetChildProgress,
+) -> Result<ChildProgress, ErrorCode> {
+    let caller = caller(auth)?;
+    let allowed = caller.role == Role::Admin
+        || caller.subject == query.child_id
+        || is_guardian(data, &caller.subject, &query.child_id);
+    if !allowed {
+        return Err(ErrorCode::Forbidden);
+    }
+    if data.node(&query.child_id).is_none() {
+        return Err(ErrorCode::NotFound);
+    }
+    Ok(progress(data, &query.child_id, query.since))
+}
+
+/// Progress of every student on the roster of `class_id`, for its teachers.
+fn get_class_progress(
+    data: &GraphData,
+    auth: &Value,
+    query: GetClassProgress,
+) -> Result<Vec<ChildProgress>, ErrorCode> {
+    if data
+        .node(&query.class_id)
+        .is_none_or(|n| n.label != "Class")
+    {
+        return Err(ErrorCode::NotFound);
+    }
+    require_teacher(data, auth, &query.class_id)?;
+    Ok(roster::students(data, &query.class_id)
+        .map(|student| progress(data, &student.student_id, query.since))
+        .collect())
+}
+
+fn progress(data: &GraphData, child_id: &str, since: Option<u64>) -> ChildProgress {
+    let concepts: Vec<ConceptProgress> = data
+        .outgoing(child_id, "LEARNS")
+        .map(|rel| {
+            let props = &rel.properties;
+            let number = |key: &str| props.get(key).and_then(Value::as_f64);
+            let concept_name = data
+                .node(&rel.to)
+                .and_then(|n| n.properties.get("label"))
+                .and_then(Value::as_str)
+                .unwrap_or(&rel.to)
+                .to_string();
+            let samples: Vec<MasterySample> = props
+                .get("history")
+                .and_then(|h| serde_json::from_value(h.clone()).ok())
+                .unwrap_or_default();
+            let since = since.unwrap_or(0);
+            let (before, history): (Vec<_>, Vec<_>) =
+                samples.into_iter().partition(|s| s.at < since);
+            ConceptProgress {
+                concept_id: rel.to.clone(),
+                concept_name,
+                mastery: number("mastery").unwrap_or(0.0),
+                last_updated: props.get("updated_at").and_then(Value::as_u64),
+                curiosity: number("curiosity"),
+                patience: number("patience"),
+                honesty: number("honesty"),
+                baseline: before.last().map(|s| s.mastery),
+                history,
+                review: review(props),
+            }
+        })
+        .collect();
+
+    let average = |virtue: fn(&ConceptProgress) -> Option<f64>| {
+        let values: Vec<f64> = concepts.iter().filter_map(virtue).collect();
+        if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f64>() / values.len() as f64
+        }
+    };
+    let virtues = Virtues {
+        honesty: average(|c| c.honesty),
+        curiosity: average(|c| c.curiosity),
+        patience: average(|c| c.patience),
+    };
+
+    ChildProgress {
+        child_id: child_id.to_string(),
+        concepts,
+        virtues,
+    }
+}
@@ -0,0 +1,68 @@
+//! Question banks: Question nodes storing the fields of [`QuestionInfo`] as
+//! properties, which their Concept `HAS_QUESTION`.
+
+use fot_protocol::{AddQuestion, ErrorCode, ListQuestions, QuestionInfo, QuestionItem, Role};
+use serde_json::Value;
+
+use crate::lessons::concept_id;
+use crate::model::GraphData;
+use crate::ops::caller;
+use crate::records::{load, save};
+
+pub(crate) fn add(
+    data: &mut GraphData,
+    auth: &Value,
+    op: AddQuestion,
+) -> Result<QuestionInfo, ErrorCode> {
+    let caller = caller(auth)?;
+    if !matches!(caller.role, Role::Teacher | Role::Admin) {
+        return Err(ErrorCode::Forbidden);
+    }
+    let concept = concept_id(data, &op.concept)?;
+    let id = data.fresh_id("question");
+    put(data, id, concept, op.prompt, op.item, op.timestamp)
+}
+
+/// Store the Question node `id` in the bank of `concept_id`, replacing its
+/// content but keeping when it was first added.
+pub(crate) fn put(
+    data: &mut GraphData,
+    id: String,
+    concept_id: String,
+    prompt: String,
+    item: QuestionItem,
+    timestamp: u64,
+) -> Result<QuestionInfo, ErrorCode> {
+    if prompt.trim().is_empty() || !item.is_well_formed() {
+        return Err(ErrorCode::InvalidPayload);
+    }
+    let created_at = load::<QuestionInfo>(data, "Question", &id)
+        .map(|q| q.created_at)
+        .unwrap_or(timestamp);
+    let question = QuestionInfo {
+        id,
+        concept_id,
+        prompt,
+        item,
+        created_at,
+    };
+    save(data, "Question", &question.id, &question);
+    data.merge_relationship(&question.concept_id, "HAS_QUESTION", &question.id);
+    Ok(question)
+}
+
+/// Answer keys included: agents must not hand them to students.
+pub(crate) fn list(
+    data: &GraphData,
+    auth: &Value,
+    query: ListQuestions,
+) -> Result<Vec<QuestionInfo>, ErrorCode> {
+    caller(auth)?;
+    let concept = concept_id(data, &query.concept)?;
+    let mut questions = data
+        .outgoing(&concept, "HAS_QUESTION")
+        .map(|r| load::<QuestionInfo>(data, "Question", &r.to))
+        .collect::<Result<Vec<_>, _>>()?;
+    questions.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+    Ok(questions)
+}
@@ -0,0 +1,38 @@
+//! Nodes that store a protocol record's fields as properties, keyed by the
+//! record's `id`, as lessons, assignments, submissions and questions do.
+
+use fot_protocol::ErrorCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Node};
+
+/// Read the `label` node `id` back into its record.
+pub(crate) fn load<T: DeserializeOwned>(
+    data: &GraphData,
+    label: &str,
+    id: &str,
+) -> Result<T, ErrorCode> {
+    let node = data
+        .node(id)
+        .filter(|n| n.label == label)
+        .ok_or(ErrorCode::NotFound)?;
+    let mut properties = node.properties.clone();
+    properties.insert("id".to_string(), json!(node.id));
+    serde_json::from_value(Value::Object(properties)).map_err(|_| ErrorCode::Internal)
+}
+
+/// Create or replace the `label` node `id` with `record`'s fields.
+pub(crate) fn save(data: &mut GraphData, label: &str, id: &str, record: &impl Serialize) {
+    let mut properties = match json!(record) {
+        Value::Object(properties) => properties,
+        _ => unreachable!("records serialize to objects"),
+    };
+    properties.remove("id");
+    data.put_node(Node {
+        id: id.to_string(),
+        label: label.to_string(),
+        properties,
+    });
+}
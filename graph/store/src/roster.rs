@@ -0,0 +1,211 @@
+//! Classes and their rosters.
+//!
+//! Membership follows the `agent_role_class_idx (role, class_id)` model: a
+//! student is an Agent with `role: "student"` whose `class_id` property names
+//! its class. The `ENROLLED_IN` relationship mirrors that property, and
+//! [`GraphData::validate`] keeps the two in step.
+
+use fot_protocol::{
+    ClassInfo, CreateClass, EnrollStudent, ErrorCode, ListRoster, RemoveStudent, Role, Roster,
+    RosterEntry, TransferStudent,
+};
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Node, Properties};
+use crate::ops::{caller, require_teacher};
+
+/// The class id `fot-cli create-class` derives from a class name.
+fn class_id(name: &str) -> String {
+    format!("class_{}", name.trim().to_lowercase().replace(' ', "_"))
+}
+
+fn class_info(data: &GraphData, id: &str) -> Result<ClassInfo, ErrorCode> {
+    let node = data
+        .node(id)
+        .filter(|n| n.label == "Class")
+        .ok_or(ErrorCode::NotFound)?;
+    let text = |key: &str| {
+        node.properties
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    Ok(ClassInfo {
+        id: node.id.clone(),
+        name: text("name"),
+        created_by: text("created_by"),
+        created_at: node
+            .properties
+            .get("created_at")
+            .and_then(Value::as_u64)
+            .unwrap_or_default(),
+    })
+}
+
+/// The roster entry of a student Agent node, if it is enrolled anywhere.
+fn entry(node: &Node) -> Option<RosterEntry> {
+    let properties = &node.properties;
+    Some(RosterEntry {
+        student_id: node.id.clone(),
+        name: properties
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        class_id: properties.get("class_id")?.as_str()?.to_string(),
+        enrolled_at: properties
+            .get("enrolled_at")
+            .and_then(Value::as_u64)
+            .unwrap_or_default(),
+    })
+}
+
+fn is_student(node: &Node) -> bool {
+    node.label == "Agent" && node.properties.get("role") == Some(&json!(Role::Student))
+}
+
+/// The student's current enrollment, which must be in `class_id`.
+pub(crate) fn enrolled(
+    data: &GraphData,
+    student_id: &str,
+    class_id: &str,
+) -> Result<RosterEntry, ErrorCode> {
+    data.node(student_id)
+        .filter(|n| is_student(n))
+        .and_then(entry)
+        .filter(|e| e.class_id == class_id)
+        .ok_or(ErrorCode::NotFound)
+}
+
+fn set_class(data: &mut GraphData, student_id: &str, class_id: &str, at: u64) {
+    data.remove_outgoing(student_id, "ENROLLED_IN");
+    let mut properties = Properties::new();
+    properties.insert("enrolled_at".to_string(), json!(at));
+    data.relate(student_id, "ENROLLED_IN", class_id, properties);
+
+    let node = data.node_mut(student_id).expect("student exists");
+    node.properties
+        .insert("class_id".to_string(), json!(class_id));
+    node.properties.insert("enrolled_at".to_string(), json!(at));
+}
+
+pub(crate) fn create_class(
+    data: &mut GraphData,
+    auth: &Value,
+    op: CreateClass,
+) -> Result<ClassInfo, ErrorCode> {
+    let caller = caller(auth)?;
+    if !matches!(caller.role, Role::Teacher | Role::Admin) {
+        return Err(ErrorCode::Forbidden);
+    }
+    let id = class_id(&op.name);
+    if data.node(&id).is_some() {
+        return Err(ErrorCode::ConstraintViolation);
+    }
+
+    if data.node(&caller.subject).is_none() {
+        let mut properties = Properties::new();
+        properties.insert("role".to_string(), json!(caller.role));
+        data.put_node(Node {
+            id: caller.subject.clone(),
+            label: "Agent".to_string(),
+            properties,
+        });
+    }
+    let mut properties = Properties::new();
+    properties.insert("name".to_string(), json!(op.name));
+    properties.insert("created_by".to_string(), json!(caller.subject));
+    properties.insert("created_at".to_string(), json!(op.timestamp));
+    data.put_node(Node {
+        id: id.clone(),
+        label: "Class".to_string(),
+        properties,
+    });
+    data.relate(&caller.subject, "TEACHES", &id, Properties::new());
+
+    class_info(data, &id)
+}
+
+/// Enrolling a student already in the class is a no-op.
+pub(crate) fn enroll(
+    data: &mut GraphData,
+    auth: &Value,
+    op: EnrollStudent,
+) -> Result<RosterEntry, ErrorCode> {
+    class_info(data, &op.class_id)?;
+    require_teacher(data, auth, &op.class_id)?;
+
+    match data.node(&op.student_id) {
+        Some(node) if !is_student(node) => return Err(ErrorCode::InvalidState),
+        Some(node) => match entry(node) {
+            Some(current) if current.class_id == op.class_id => return Ok(current),
+            Some(_) => return Err(ErrorCode::InvalidState),
+            None => {}
+        },
+        None => {
+            let mut properties = Properties::new();
+            properties.insert("role".to_string(), json!(Role::Student));
+            data.put_node(Node {
+                id: op.student_id.clone(),
+                label: "Agent".to_string(),
+                properties,
+            });
+        }
+    }
+
+    if let Some(name) = op.name {
+        let node = data.node_mut(&op.student_id).expect("student exists");
+        node.properties.insert("name".to_string(), json!(name));
+    }
+    set_class(data, &op.student_id, &op.class_id, op.timestamp);
+    enrolled(data, &op.student_id, &op.class_id)
+}
+
+/// Returns the enrollment that was removed.
+pub(crate) fn remove(
+    data: &mut GraphData,
+    auth: &Value,
+    op: RemoveStudent,
+) -> Result<RosterEntry, ErrorCode> {
+    require_teacher(data, auth, &op.class_id)?;
+    let removed = enrolled(data, &op.student_id, &op.class_id)?;
+
+    data.remove_outgoing(&op.student_id, "ENROLLED_IN");
+    let node = data.node_mut(&op.student_id).expect("student exists");
+    node.properties.remove("class_id");
+    node.properties.remove("enrolled_at");
+    Ok(removed)
+}
+
+pub(crate) fn transfer(
+    data: &mut GraphData,
+    auth: &Value,
+    op: TransferStudent,
+) -> Result<RosterEntry, ErrorCode> {
+    class_info(data, &op.to_class_id)?;
+    require_teacher(data, auth, &op.from_class_id)?;
+    require_teacher(data, auth, &op.to_class_id)?;
+    enrolled(data, &op.student_id, &op.from_class_id)?;
+
+    set_class(data, &op.student_id, &op.to_class_id, op.timestamp);
+    enrolled(data, &op.student_id, &op.to_class_id)
+}
+
+pub(crate) fn list(data: &GraphData, auth: &Value, query: ListRoster) -> Result<Roster, ErrorCode> {
+    let class = class_info(data, &query.class_id)?;
+    require_teacher(data, auth, &query.class_id)?;
+
+    let students = students(data, &query.class_id).collect();
+    Ok(Roster { class, students })
+}
+
+/// The enrolled students of `class_id`, by id.
+pub(crate) fn students<'a>(
+    data: &'a GraphData,
+    class_id: &'a str,
+) -> impl Iterator<Item = RosterEntry> + 'a {
+    data.nodes_with_label("Agent")
+        .filter(|n| is_student(n))
+        .filter_map(entry)
+        .filter(move |e| e.class_id == class_id)
+}
@@ -0,0 +1,152 @@
+//! Installed topics: a Topic node keeps its [`TopicManifest`] under
+//! `manifest` and `COVERS` the Concepts it teaches. Installing also puts the
+//! manifest's question bank and concept links into the graph.
+//!
+//! A Concept belongs to the topic that first installed it, named by its
+//! `topic` property. Links to concepts not installed yet wait under the
+//! Topic's `pending_links` until a topic brings those concepts.
+
+use fot_protocol::{
+    ConceptLink, ErrorCode, GetTopic, InstallTopic, LinkConcepts, Role, TopicInstalled,
+    TopicManifest,
+};
+use serde_json::{json, Value};
+
+use crate::model::{GraphData, Node, Properties};
+use crate::ops::caller;
+use crate::{concepts, links, questions};
+
+pub(crate) fn install(
+    data: &mut GraphData,
+    auth: &Value,
+    op: InstallTopic,
+) -> Result<TopicInstalled, ErrorCode> {
+    let caller = caller(auth)?;
+    if !matches!(caller.role, Role::Teacher | Role::Admin) {
+        return Err(ErrorCode::Forbidden);
+    }
+    let manifest = op.manifest;
+    if manifest.id.trim().is_empty() || manifest.concepts.is_empty() {
+        return Err(ErrorCode::InvalidPayload);
+    }
+    let previous = match data.node(&manifest.id) {
+        Some(node) if node.label != "Topic" => return Err(ErrorCode::InvalidPayload),
+        Some(node) => stored_manifest(node.properties.get("manifest"))?,
+        None => None,
+    };
+
+    // Questions the manifest no longer lists leave the bank.
+    for question in previous.iter().flat_map(|p| &p.questions) {
+        if !manifest.questions.iter().any(|q| q.id == question.id) {
+            data.remove_node(&format!("{}:{}", manifest.id, question.id));
+        }
+    }
+
+    let mut properties = Properties::new();
+    properties.insert("title".to_string(), json!(manifest.title));
+    properties.insert("manifest".to_string(), json!(manifest));
+    data.put_node(Node {
+        id: manifest.id.clone(),
+        label: "Topic".to_string(),
+        properties,
+    });
+    data.remove_outgoing(&manifest.id, "COVERS");
+    for concept in &manifest.concepts {
+        concepts::put(data, &manifest.id, concept)?;
+        data.relate(&manifest.id, "COVERS", &concept.id, Properties::new());
+    }
+
+    let mut installed = TopicInstalled {
+        topic_id: manifest.id.clone(),
+        concepts: manifest.concepts.iter().map(|c| c.id.clone()).collect(),
+        questions: Vec::new(),
+        links: Vec::new(),
+        pending_links: Vec::new(),
+    };
+    for question in manifest.questions {
+        let concept = covered(data, &manifest.id, &question.concept)?;
+        let id = format!("{}:{}", manifest.id, question.id);
+        let question = questions::put(
+            data,
+            id,
+            concept,
+            question.prompt,
+            question.item,
+            op.timestamp,
+        )?;
+        installed.questions.push(question.id);
+    }
+    let (created, pending) = resolve(data, auth, manifest.links)?;
+    installed.links = created;
+    installed.pending_links = pending;
+    let properties = &mut data.node_mut(&manifest.id).expect("just put").properties;
+    properties.insert("pending_links".to_string(), json!(installed.pending_links));
+
+    // Other topics may have been waiting for these concepts.
+    let waiting: Vec<String> = data
+        .nodes_with_label("Topic")
+        .filter(|n| n.id != manifest.id)
+        .filter(|n| {
+            n.properties
+                .get("pending_links")
+                .and_then(Value::as_array)
+                .is_some_and(|links| !links.is_empty())
+        })
+        .map(|n| n.id.clone())
+        .collect();
+    for topic in waiting {
+        let properties = &data.node(&topic).expect("listed above").properties;
+        let links: Vec<LinkConcepts> = serde_json::from_value(properties["pending_links"].clone())
+            .map_err(|_| ErrorCode::Internal)?;
+        let (created, pending) = resolve(data, auth, links)?;
+        installed.links.extend(created);
+        let properties = &mut data.node_mut(&topic).expect("listed above").properties;
+        properties.insert("pending_links".to_string(), json!(pending));
+    }
+    Ok(installed)
+}
+
+/// Create the links whose concepts are both in the graph; the rest are
+/// returned to wait.
+fn resolve(
+    data: &mut GraphData,
+    auth: &Value,
+    links: Vec<LinkConcepts>,
+) -> Result<(Vec<ConceptLink>, Vec<LinkConcepts>), ErrorCode> {
+    let mut created = Vec::new();
+    let mut pending = Vec::new();
+    for link in links {
+        let exists = |key: &str| data.find("Concept", key).is_some();
+        if exists(&link.source) && exists(&link.target) {
+            created.push(links::create(data, auth, link)?);
+        } else {
+            pending.push(link);
+        }
+    }
+    Ok((created, pending))
+}
+
+fn stored_manifest(manifest: Option<&Value>) -> Result<Option<TopicManifest>, ErrorCode> {
+    manifest
+        .map(|manifest| serde_json::from_value(manifest.clone()).map_err(|_| ErrorCode::Internal))
+        .transpose()
+}
+
+/// The id of one of the concepts `topic` covers, by id or label.
+fn covered(data: &GraphData, topic: &str, concept: &str) -> Result<String, ErrorCode> {
+    data.find("Concept", concept)
+        .map(|n| n.id.clone())
+        .filter(|id| data.outgoing(topic, "COVERS").any(|r| &r.to == id))
+        .ok_or(ErrorCode::InvalidPayload)
+}
+
+/// Answer keys included, like the question bank.
+pub(crate) fn get(
+    data: &GraphData,
+    auth: &Value,
+    query: GetTopic,
+) -> Result<Option<TopicManifest>, ErrorCode> {
+    caller(auth)?;
+    let topic = data.node(&query.topic_id).filter(|n| n.label == "Topic");
+    stored_manifest(topic.and_then(|n| n.properties.get("manifest")))
+}
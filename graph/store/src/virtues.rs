@@ -0,0 +1,77 @@
+//! Virtues recorded on students' `LEARNS` relationships: the current value
+//! of each as a property, and every delta with its explanation under
+//! `virtue_history`.
+
+use fot_protocol::{ErrorCode, RecordVirtues, Virtue, VirtueSample, VirtuesRecorded};
+use serde_json::{json, Value};
+
+use crate::model::GraphData;
+use crate::ops::{acting_for, agent_node, caller};
+
+pub(crate) fn record(
+    data: &mut GraphData,
+    auth: &Value,
+    op: RecordVirtues,
+) -> Result<VirtuesRecorded, ErrorCode> {
+    let caller = caller(auth)?;
+    let subject = acting_for(data, &caller, op.student_id.as_deref())?.to_string();
+    let concept = data
+        .find("Concept", &op.concept)
+        .map(|n| n.id.clone())
+        .ok_or(ErrorCode::NotFound)?;
+    if op.changes.is_empty() || op.changes.iter().any(|c| !c.delta.is_finite()) {
+        return Err(ErrorCode::InvalidPayload);
+    }
+
+    if subject == caller.subject {
+        agent_node(data, &caller);
+    } else if data.node(&subject).is_none() {
+        return Err(ErrorCode::NotFound);
+    }
+
+    let rel = data.merge_relationship(&subject, "LEARNS", &concept);
+    let learns = &mut data.relationship_mut(rel).expect("just merged").properties;
+    let mut net: Vec<(Virtue, f64)> = Vec::new();
+    for change in &op.changes {
+        match net.iter_mut().find(|(virtue, _)| *virtue == change.virtue) {
+            Some((_, delta)) => *delta += change.delta,
+            None => net.push((change.virtue, change.delta)),
+        }
+    }
+    for (virtue, delta) in net {
+        let stored = learns
+            .get(virtue.as_str())
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+        learns.insert(
+            virtue.as_str().to_string(),
+            json!((stored + delta).clamp(0.0, 1.0)),
+        );
+    }
+    let samples = op.changes.into_iter().map(|change| {
+        json!(VirtueSample {
+            at: op.timestamp,
+            change,
+        })
+    });
+    match learns
+        .get_mut("virtue_history")
+        .and_then(Value::as_array_mut)
+    {
+        Some(history) => history.extend(samples),
+        None => {
+            learns.insert(
+                "virtue_history".to_string(),
+                Value::Array(samples.collect()),
+            );
+        }
+    }
+
+    let number = |key: &str| learns.get(key).and_then(Value::as_f64);
+    Ok(VirtuesRecorded {
+        curiosity: number("curiosity"),
+        patience: number("patience"),
+        honesty: number("honesty"),
+        concept_id: concept,
+    })
+}
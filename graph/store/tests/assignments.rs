@@ -0,0 +1,280 @@
+//! Assignments, submissions and late grading against the embedded graph store.
+
+mod common;
+
+use common::write;
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    AssignmentInfo, CalibrateAssignment, CreateAssignment, CreateLesson, EnrollStudent, Envelope,
+    ErrorCode, GetSubmission, GraphRead, IrtModel, ItemCalibration, ItemResponse, LatePolicy,
+    LessonCreated, LessonInfo, ListResponses, RecordGrade, RosterEntry, SubmissionInfo, Submit,
+    UpdateLesson,
+};
+use serde_json::json;
+
+const DAY: u64 = 24 * 60 * 60;
+const TEACHER: &str = r#"{"subject": "teacher-1", "role": "teacher"}"#;
+const ADA: &str = r#"{"subject": "ada", "role": "student"}"#;
+
+fn act_as(store: &GraphStore, auth: &str) {
+    store.set_auth_ctx(serde_json::from_str(auth).unwrap());
+}
+
+/// A class with `ada` enrolled and one assignment due at day 10 that loses
+/// a quarter per day late and closes two days after the due date.
+fn classroom() -> (GraphStore, AssignmentInfo) {
+    let store = GraphStore::new();
+    store
+        .add_node(
+            "fot:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    store.add_node("class-1", "Class", json!({})).unwrap();
+    store
+        .add_node("teacher-1", "Agent", json!({"role": "teacher"}))
+        .unwrap();
+    store
+        .relate("teacher-1", "TEACHES", "class-1", json!({}))
+        .unwrap();
+    act_as(&store, TEACHER);
+    write::<RosterEntry>(
+        &store,
+        EnrollStudent {
+            class_id: "class-1".to_string(),
+            student_id: "ada".to_string(),
+            name: None,
+            timestamp: 0,
+        },
+    )
+    .unwrap();
+    let assignment = write(
+        &store,
+        CreateAssignment {
+            class_id: "class-1".to_string(),
+            concept: "Photosynthesis".to_string(),
+            title: "Leaf pigments".to_string(),
+            lesson_id: None,
+            due_at: 10 * DAY,
+            late_policy: LatePolicy {
+                penalty_per_day: 0.25,
+                accept_days_late: Some(2),
+            },
+            timestamp: 0,
+        },
+    )
+    .unwrap();
+    (store, assignment)
+}
+
+fn submit(
+    store: &GraphStore,
+    assignment: &AssignmentInfo,
+    at: u64,
+) -> Result<SubmissionInfo, ErrorCode> {
+    act_as(store, ADA);
+    write(
+        store,
+        Submit {
+            assignment_id: assignment.id.clone(),
+            content: "Chlorophyll absorbs red and blue light".to_string(),
+            time_spent: 600,
+            timestamp: at,
+        },
+    )
+}
+
+#[test]
+fn late_submissions_are_graded_with_the_penalty() {
+    let (store, assignment) = classroom();
+    let submission = submit(&store, &assignment, 11 * DAY + 1).unwrap();
+    assert_eq!(submission.student_id, "ada");
+    assert_eq!(submission.days_late, 2);
+
+    act_as(&store, TEACHER);
+    let graded: SubmissionInfo = write(
+        &store,
+        RecordGrade {
+            submission_id: submission.id.clone(),
+            raw_score: 0.8,
+            timestamp: 12 * DAY,
+        },
+    )
+    .unwrap();
+    let grade = graded.grade.unwrap();
+    assert_eq!(grade.raw_score, 0.8);
+    assert!((grade.score - 0.4).abs() < 1e-9);
+
+    // The grade is stored with the submission, and the student can read it.
+    act_as(&store, ADA);
+    let query = Envelope::<GraphRead>::new(GetSubmission {
+        submission_id: submission.id,
+    });
+    let stored: SubmissionInfo =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(stored.grade, Some(grade));
+}
+
+#[test]
+fn assignments_and_submissions_record_the_lesson_version() {
+    let (store, _) = classroom();
+    let lesson: LessonCreated = write(
+        &store,
+        CreateLesson {
+            concept: "Photosynthesis".to_string(),
+            class_id: "class-1".to_string(),
+            content: "Light reactions".to_string(),
+            timestamp: 0,
+        },
+    )
+    .unwrap();
+    let assignment: AssignmentInfo = write(
+        &store,
+        CreateAssignment {
+            class_id: "class-1".to_string(),
+            concept: "Photosynthesis".to_string(),
+            title: "Light reactions".to_string(),
+            lesson_id: Some(lesson.id.clone()),
+            due_at: 10 * DAY,
+            late_policy: LatePolicy::default(),
+            timestamp: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(assignment.lesson_version, Some(1));
+
+    let edited: LessonInfo = write(
+        &store,
+        UpdateLesson {
+            lesson_id: lesson.id,
+            concept: None,
+            content: Some("Light and dark reactions".to_string()),
+            timestamp: DAY,
+        },
+    )
+    .unwrap();
+    assert_eq!(edited.version, 2);
+
+    let submission = submit(&store, &assignment, 2 * DAY).unwrap();
+    assert_eq!(submission.lesson_version, Some(2));
+}
+
+#[test]
+fn graded_and_closed_submissions_are_refused() {
+    let (store, assignment) = classroom();
+    let first = submit(&store, &assignment, DAY).unwrap();
+    // Handing in again before grading replaces the submission.
+    let second = submit(&store, &assignment, 2 * DAY).unwrap();
+    assert_eq!(first.id, second.id);
+    assert_eq!(second.submitted_at, 2 * DAY);
+
+    act_as(&store, TEACHER);
+    write::<SubmissionInfo>(
+        &store,
+        RecordGrade {
+            submission_id: second.id,
+            raw_score: 1.0,
+            timestamp: 3 * DAY,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        submit(&store, &assignment, 4 * DAY),
+        Err(ErrorCode::InvalidState)
+    );
+
+    let (store, assignment) = classroom();
+    assert_eq!(
+        submit(&store, &assignment, 13 * DAY),
+        Err(ErrorCode::InvalidState)
+    );
+}
+
+#[test]
+fn only_enrolled_students_submit_and_only_teachers_grade() {
+    let (store, assignment) = classroom();
+    store.set_auth_ctx(json!({"subject": "ben", "role": "student"}));
+    let stranger = write::<SubmissionInfo>(
+        &store,
+        Submit {
+            assignment_id: assignment.id.clone(),
+            content: "Copied".to_string(),
+            time_spent: 1,
+            timestamp: DAY,
+        },
+    );
+    assert_eq!(stranger, Err(ErrorCode::Forbidden));
+
+    let submission = submit(&store, &assignment, DAY).unwrap();
+    let self_grade = write::<SubmissionInfo>(
+        &store,
+        RecordGrade {
+            submission_id: submission.id,
+            raw_score: 1.0,
+            timestamp: DAY,
+        },
+    );
+    assert_eq!(self_grade, Err(ErrorCode::Forbidden));
+}
+
+#[test]
+fn graded_work_is_listed_and_calibration_stored() {
+    let (store, assignment) = classroom();
+    let submission = submit(&store, &assignment, DAY).unwrap();
+    let list = Envelope::<GraphRead>::new(ListResponses {
+        class_id: "class-1".to_string(),
+    })
+    .encode();
+    assert_eq!(store.graph_read(&list), Err(ErrorCode::Forbidden.code()));
+
+    act_as(&store, TEACHER);
+    let listed: Vec<ItemResponse> =
+        serde_json::from_str(&store.graph_read(&list).unwrap()).unwrap();
+    assert!(listed.is_empty(), "ungraded work is not a response");
+    write::<SubmissionInfo>(
+        &store,
+        RecordGrade {
+            submission_id: submission.id,
+            raw_score: 0.7,
+            timestamp: 2 * DAY,
+        },
+    )
+    .unwrap();
+    let listed: Vec<ItemResponse> =
+        serde_json::from_str(&store.graph_read(&list).unwrap()).unwrap();
+    assert_eq!(
+        listed,
+        [ItemResponse {
+            assignment_id: assignment.id.clone(),
+            student_id: "ada".to_string(),
+            score: 0.7,
+        }]
+    );
+
+    let calibration = ItemCalibration {
+        model: IrtModel::TwoPl,
+        difficulty: 0.4,
+        discrimination: 1.3,
+        responses: 1,
+        calibrated_at: 3 * DAY,
+    };
+    let calibrate = |calibration| CalibrateAssignment {
+        assignment_id: assignment.id.clone(),
+        calibration,
+    };
+    let stored: AssignmentInfo = write(&store, calibrate(calibration)).unwrap();
+    assert_eq!(stored.calibration, Some(calibration));
+    assert_eq!(stored.title, "Leaf pigments");
+    assert_eq!(
+        write::<AssignmentInfo>(
+            &store,
+            calibrate(ItemCalibration {
+                discrimination: 0.0,
+                ..calibration
+            })
+        ),
+        Err(ErrorCode::InvalidPayload)
+    );
+}
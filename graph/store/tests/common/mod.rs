@@ -0,0 +1,50 @@
+//! Envelope round trips shared by the store's integration tests.
+
+#![allow(dead_code)]
+
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{Envelope, ErrorCode, GraphRead, GraphWrite};
+use serde::de::DeserializeOwned;
+
+pub fn write<T: DeserializeOwned>(
+    store: &GraphStore,
+    op: impl Into<GraphWrite>,
+) -> Result<T, ErrorCode> {
+    send(store, Envelope::new(op))
+}
+
+pub fn write_in<T: DeserializeOwned>(
+    store: &GraphStore,
+    txn: u64,
+    op: impl Into<GraphWrite>,
+) -> Result<T, ErrorCode> {
+    send(store, Envelope::new(op).in_txn(txn))
+}
+
+pub fn send<T: DeserializeOwned>(
+    store: &GraphStore,
+    envelope: Envelope<GraphWrite>,
+) -> Result<T, ErrorCode> {
+    store
+        .graph_write(&envelope.encode())
+        .map(|reply| serde_json::from_str(&reply).unwrap())
+        .map_err(|code| ErrorCode::from_code(code).unwrap())
+}
+
+pub fn read<T: DeserializeOwned>(
+    store: &GraphStore,
+    op: impl Into<GraphRead>,
+) -> Result<T, ErrorCode> {
+    query(store, Envelope::new(op))
+}
+
+pub fn query<T: DeserializeOwned>(
+    store: &GraphStore,
+    envelope: Envelope<GraphRead>,
+) -> Result<T, ErrorCode> {
+    store
+        .graph_read(&envelope.encode())
+        .map(|reply| serde_json::from_str(&reply).unwrap())
+        .map_err(|code| ErrorCode::from_code(code).unwrap())
+}
@@ -0,0 +1,143 @@
+//! Students' goals and activity history against the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_agent_sdk::mastery::decay;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    Activity, ActivityHistory, ActivityPage, ErrorCode, Goal, GoalProgress, ListGoals,
+    MasteryUpdated, SetGoal, UpdateMastery,
+};
+use serde_json::json;
+
+fn learn(concept: &str, mastery: f64, timestamp: u64) -> UpdateMastery {
+    UpdateMastery {
+        concept: concept.to_string(),
+        student_id: None,
+        mastery,
+        truth_field_strength: 0.5,
+        harmonic_coherence: 0.5,
+        context: "general_learning".to_string(),
+        timestamp,
+        review: None,
+        if_revision: None,
+    }
+}
+
+fn goal(concept: &str, target_mastery: f64, due_at: u64, timestamp: u64) -> SetGoal {
+    SetGoal {
+        concept: concept.to_string(),
+        target_mastery,
+        due_at,
+        timestamp,
+    }
+}
+
+fn student() -> GraphStore {
+    let store = GraphStore::new();
+    for (id, label) in [
+        ("ex:Photosynthesis", "Photosynthesis"),
+        ("ex:Entropy", "Entropy"),
+    ] {
+        store
+            .add_node(id, "Concept", json!({"label": label}))
+            .unwrap();
+    }
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    store
+}
+
+#[test]
+fn goals_track_mastery_towards_a_target() {
+    let store = student();
+    write::<MasteryUpdated>(&store, learn("Photosynthesis", 0.3, 10)).unwrap();
+
+    let set: Goal = write(&store, goal("Photosynthesis", 0.8, 1_000, 100)).unwrap();
+    assert_eq!(set.concept_id, "ex:Photosynthesis");
+    // Mastery has decayed a little between learning and setting the goal.
+    assert_eq!(set.starting_mastery, decay(0.3, Some(10), None, 100));
+    assert!(set.starting_mastery < 0.3);
+    write::<Goal>(&store, goal("Entropy", 0.5, 500, 100)).unwrap();
+    // Setting a goal again replaces it rather than adding a second one.
+    write::<Goal>(&store, goal("Entropy", 0.6, 600, 120)).unwrap();
+    assert_eq!(
+        write::<Goal>(&store, goal("Entropy", 1.5, 600, 120)),
+        Err(ErrorCode::InvalidPayload)
+    );
+    assert_eq!(
+        write::<Goal>(&store, goal("Entropy", 0.6, 100, 120)),
+        Err(ErrorCode::InvalidPayload)
+    );
+    assert_eq!(
+        write::<Goal>(&store, goal("Optics", 0.6, 600, 120)),
+        Err(ErrorCode::NotFound)
+    );
+
+    write::<MasteryUpdated>(&store, learn("Photosynthesis", 0.6, 200)).unwrap();
+    let goals: Vec<GoalProgress> = read(&store, ListGoals::default()).unwrap();
+    let summary: Vec<(&str, f64, f64)> = goals
+        .iter()
+        .map(|g| {
+            (
+                g.goal.concept_name.as_str(),
+                g.goal.target_mastery,
+                g.mastery,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [("Entropy", 0.6, 0.0), ("Photosynthesis", 0.8, 0.6)]
+    );
+    assert_eq!(goals[1].updated_at, Some(200));
+
+    store.set_auth_ctx(json!({"subject": "ben", "role": "student"}));
+    assert_eq!(
+        read::<Vec<GoalProgress>>(
+            &store,
+            ListGoals {
+                student_id: Some("ada".to_string())
+            }
+        ),
+        Err(ErrorCode::Forbidden)
+    );
+}
+
+#[test]
+fn activity_history_pages_newest_first() {
+    let store = student();
+    for (timestamp, mastery) in [(10, 0.2), (20, 0.4), (30, 0.6)] {
+        write::<MasteryUpdated>(&store, learn("Photosynthesis", mastery, timestamp)).unwrap();
+    }
+    write::<Goal>(&store, goal("Entropy", 0.7, 1_000, 25)).unwrap();
+
+    let page = |offset: usize, limit: usize| {
+        read::<ActivityPage>(
+            &store,
+            ActivityHistory {
+                student_id: None,
+                offset,
+                limit,
+            },
+        )
+    };
+    let first = page(0, 3).unwrap();
+    assert_eq!(first.total, 4);
+    assert_eq!(first.next_offset, Some(3));
+    let times: Vec<u64> = first.activities.iter().map(Activity::at).collect();
+    assert_eq!(times, [30, 25, 20]);
+    assert!(matches!(
+        first.activities[1],
+        Activity::GoalSet { ref concept_id, .. } if concept_id == "ex:Entropy"
+    ));
+
+    let last = page(3, 3).unwrap();
+    assert_eq!(last.next_offset, None);
+    assert!(matches!(
+        last.activities[..],
+        [Activity::Mastery { at: 10, mastery, .. }] if mastery == 0.2
+    ));
+    assert!(page(10, 3).unwrap().activities.is_empty());
+    assert_eq!(page(0, 0), Err(ErrorCode::InvalidPayload));
+}
@@ -0,0 +1,142 @@
+//! Lesson lifecycle against the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    ArchiveLesson, CreateLesson, ErrorCode, LessonCreated, LessonInfo, LessonStatus, ListLessons,
+    PublishLesson, UpdateLesson,
+};
+use serde_json::json;
+
+fn classroom() -> GraphStore {
+    let store = GraphStore::new();
+    for (id, label) in [
+        ("fot:Photosynthesis", "Photosynthesis"),
+        ("fot:Metabolism", "Metabolism"),
+    ] {
+        store
+            .add_node(id, "Concept", json!({ "label": label }))
+            .unwrap();
+    }
+    store.add_node("class-1", "Class", json!({})).unwrap();
+    store
+        .add_node("teacher-1", "Agent", json!({"role": "teacher"}))
+        .unwrap();
+    store
+        .relate("teacher-1", "TEACHES", "class-1", json!({}))
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    store
+}
+
+fn list(store: &GraphStore, status: Option<LessonStatus>) -> Vec<LessonInfo> {
+    read(
+        store,
+        ListLessons {
+            class_id: "class-1".to_string(),
+            status,
+        },
+    )
+    .unwrap()
+}
+
+fn create(store: &GraphStore) -> String {
+    let created: LessonCreated = write(
+        store,
+        CreateLesson {
+            concept: "Photosynthesis".to_string(),
+            class_id: "class-1".to_string(),
+            content: "Light reactions".to_string(),
+            timestamp: 100,
+        },
+    )
+    .unwrap();
+    created.id
+}
+
+#[test]
+fn edits_to_published_lessons_keep_earlier_versions() {
+    let store = classroom();
+    let id = create(&store);
+    let published: LessonInfo = write(
+        &store,
+        PublishLesson {
+            lesson_id: id.clone(),
+            timestamp: 200,
+        },
+    )
+    .unwrap();
+    assert_eq!(published.status, LessonStatus::Published);
+
+    let edited: LessonInfo = write(
+        &store,
+        UpdateLesson {
+            lesson_id: id.clone(),
+            concept: Some("Metabolism".to_string()),
+            content: Some("Calvin cycle".to_string()),
+            timestamp: 300,
+        },
+    )
+    .unwrap();
+    assert_eq!(edited.version, 2);
+    assert_eq!(edited.status, LessonStatus::Published);
+    assert_eq!(edited.history[0].version, 1);
+    assert_eq!(edited.history[0].content, "Light reactions");
+    assert_eq!(edited.history[0].replaced_at, 300);
+
+    let covers: Vec<String> = store
+        .snapshot()
+        .outgoing(&id, "COVERS")
+        .map(|r| r.to.clone())
+        .collect();
+    assert_eq!(covers, ["fot:Metabolism"]);
+}
+
+#[test]
+fn archived_lessons_are_frozen_and_filtered() {
+    let store = classroom();
+    let archived = create(&store);
+    let draft = create(&store);
+    write::<LessonInfo>(
+        &store,
+        ArchiveLesson {
+            lesson_id: archived.clone(),
+            timestamp: 200,
+        },
+    )
+    .unwrap();
+
+    let edit = UpdateLesson {
+        lesson_id: archived,
+        concept: None,
+        content: Some("Too late".to_string()),
+        timestamp: 300,
+    };
+    assert_eq!(
+        write::<LessonInfo>(&store, edit),
+        Err(ErrorCode::InvalidState)
+    );
+
+    assert_eq!(list(&store, None).len(), 2);
+    let drafts = list(&store, Some(LessonStatus::Draft));
+    assert_eq!(drafts.len(), 1);
+    assert_eq!(drafts[0].id, draft);
+}
+
+#[test]
+fn other_teachers_cannot_touch_the_lesson() {
+    let store = classroom();
+    let id = create(&store);
+    store.set_auth_ctx(json!({"subject": "teacher-2", "role": "teacher"}));
+
+    let publish = PublishLesson {
+        lesson_id: id,
+        timestamp: 200,
+    };
+    assert_eq!(
+        write::<LessonInfo>(&store, publish),
+        Err(ErrorCode::Forbidden)
+    );
+}
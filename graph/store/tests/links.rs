@@ -0,0 +1,170 @@
+//! Typed links between Concepts against the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    ConceptLink, ConceptMap, Envelope, ErrorCode, FollowLink, FollowedLink, GraphRead,
+    LinkConcepts, LinkType, ListConceptLinks, ListFollowedLinks, MasteryUpdated, UpdateMastery,
+};
+use serde_json::json;
+
+fn list(store: &GraphStore, link_types: Vec<LinkType>) -> ConceptMap {
+    read(store, ListConceptLinks { link_types }).unwrap()
+}
+
+fn link(source: &str, target: &str, link_type: LinkType, comment: &str) -> LinkConcepts {
+    LinkConcepts {
+        source: source.to_string(),
+        target: target.to_string(),
+        link_type,
+        comment: Some(comment.to_string()),
+    }
+}
+
+/// The Biology, Chemistry and Climate concepts from the seeds, curated by a
+/// teacher.
+fn concept_map() -> GraphStore {
+    let store = GraphStore::new();
+    for (id, label, domain) in [
+        ("ex:Ecosystem", "Ecosystem", "Biology"),
+        ("ex:Resource_Use", "Resource Use", "Economics"),
+        ("ex:Carbon_Cycle", "Carbon Cycle", "Chemistry"),
+        ("ex:Climate_Model", "Climate Model", "Climate"),
+    ] {
+        store
+            .add_node(id, "Concept", json!({"label": label, "domain": domain}))
+            .unwrap();
+    }
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    store
+}
+
+#[test]
+fn links_are_typed_and_listed_with_their_concepts() {
+    let store = concept_map();
+    let entails = link(
+        "Carbon Cycle",
+        "Climate Model",
+        LinkType::Entails,
+        "Understanding carbon cycles is essential for climate modeling",
+    );
+    let created: ConceptLink = write(&store, entails.clone()).unwrap();
+    assert_eq!(created.source, "ex:Carbon_Cycle");
+    assert_eq!(created.target, "ex:Climate_Model");
+    write::<ConceptLink>(
+        &store,
+        link(
+            "ex:Ecosystem",
+            "ex:Resource_Use",
+            LinkType::Analogy,
+            "Sustainability and balance",
+        ),
+    )
+    .unwrap();
+    // Linking again updates the comment rather than adding a second link.
+    write::<ConceptLink>(
+        &store,
+        LinkConcepts {
+            comment: None,
+            ..entails
+        },
+    )
+    .unwrap();
+
+    let all = list(&store, Vec::new());
+    assert_eq!(all.links.len(), 2);
+    assert_eq!(all.concepts.len(), 4);
+
+    let prerequisites = list(&store, vec![LinkType::Prerequisite, LinkType::Entails]);
+    assert_eq!(prerequisites.links.len(), 1);
+    assert_eq!(prerequisites.links[0].comment, None);
+    let names: Vec<_> = prerequisites.concepts.iter().map(|c| &c.label).collect();
+    assert_eq!(names, ["Carbon Cycle", "Climate Model"]);
+    assert_eq!(prerequisites.concepts[1].domain.as_deref(), Some("Climate"));
+}
+
+#[test]
+fn only_teachers_link_existing_distinct_concepts() {
+    let store = concept_map();
+    let analogy = |source: &str, target: &str| link(source, target, LinkType::Analogy, "");
+    assert_eq!(
+        write::<ConceptLink>(&store, analogy("Ecosystem", "Missing")),
+        Err(ErrorCode::NotFound)
+    );
+    assert_eq!(
+        write::<ConceptLink>(&store, analogy("Ecosystem", "ex:Ecosystem")),
+        Err(ErrorCode::InvalidPayload)
+    );
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    assert_eq!(
+        write::<ConceptLink>(&store, analogy("Ecosystem", "Resource Use")),
+        Err(ErrorCode::Forbidden)
+    );
+    assert!(list(&store, Vec::new()).links.is_empty());
+}
+
+#[test]
+fn followed_links_record_mastery_for_measuring_transfer() {
+    let store = concept_map();
+    write::<ConceptLink>(
+        &store,
+        link(
+            "Ecosystem",
+            "Resource Use",
+            LinkType::Analogy,
+            "Sustainability and balance",
+        ),
+    )
+    .unwrap();
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    let learn = |concept: &str, mastery: f64| UpdateMastery {
+        concept: concept.to_string(),
+        student_id: None,
+        mastery,
+        truth_field_strength: 0.5,
+        harmonic_coherence: 0.5,
+        context: "general_learning".to_string(),
+        timestamp: 10,
+        review: None,
+        if_revision: None,
+    };
+    write::<MasteryUpdated>(&store, learn("Ecosystem", 0.9)).unwrap();
+    let follow = |source: &str, target: &str| FollowLink {
+        source: source.to_string(),
+        target: target.to_string(),
+        timestamp: 20,
+    };
+    // Analogies can be followed either way; unrelated concepts cannot.
+    let followed: FollowedLink = write(&store, follow("Resource Use", "Ecosystem")).unwrap();
+    assert_eq!(followed.source, "ex:Resource_Use");
+    assert_eq!(
+        write::<FollowedLink>(&store, follow("Ecosystem", "Carbon Cycle")),
+        Err(ErrorCode::NotFound)
+    );
+    let followed: FollowedLink = write(&store, follow("Ecosystem", "Resource Use")).unwrap();
+    assert_eq!(followed.link_type, LinkType::Analogy);
+    assert_eq!(followed.source_mastery, 0.9);
+    assert_eq!(followed.target_mastery_before, 0.0);
+
+    write::<MasteryUpdated>(&store, learn("Resource Use", 0.4)).unwrap();
+    let query = Envelope::<GraphRead>::new(ListFollowedLinks::default());
+    let history: Vec<FollowedLink> =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].target, "ex:Resource_Use");
+    assert_eq!(history[1].target_mastery, 0.4);
+
+    store.set_auth_ctx(json!({"subject": "ben", "role": "student"}));
+    let query = Envelope::<GraphRead>::new(ListFollowedLinks {
+        student_id: Some("ada".to_string()),
+    });
+    assert_eq!(
+        store.graph_read(&query.encode()),
+        Err(ErrorCode::Forbidden.code())
+    );
+}
@@ -0,0 +1,99 @@
+//! Question banks against the embedded graph store.
+
+mod common;
+
+use common::write;
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    AddQuestion, Envelope, ErrorCode, GraphRead, ListQuestions, QuestionInfo, QuestionItem,
+    RubricKeyword,
+};
+use serde_json::json;
+
+fn add(store: &GraphStore, prompt: &str, item: QuestionItem) -> Result<QuestionInfo, ErrorCode> {
+    write(
+        store,
+        AddQuestion {
+            concept: "Photosynthesis".to_string(),
+            prompt: prompt.to_string(),
+            item,
+            timestamp: 10,
+        },
+    )
+}
+
+#[test]
+fn questions_are_banked_per_concept_by_teachers() {
+    let store = GraphStore::new();
+    store
+        .add_node(
+            "fot:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+
+    let choice = add(
+        &store,
+        "Which pigment absorbs light?",
+        QuestionItem::MultipleChoice {
+            choices: vec!["Chlorophyll".to_string(), "Keratin".to_string()],
+            answer: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(choice.concept_id, "fot:Photosynthesis");
+    add(
+        &store,
+        "Why do plants need light?",
+        QuestionItem::ShortAnswer {
+            rubric: vec![RubricKeyword {
+                keyword: "energy".to_string(),
+                synonyms: Vec::new(),
+                weight: 1.0,
+            }],
+            pass_score: 1.0,
+        },
+    )
+    .unwrap();
+    // Answer keys that cannot be met are refused.
+    let unmet = [
+        QuestionItem::MultipleChoice {
+            choices: vec!["Chlorophyll".to_string(), "Keratin".to_string()],
+            answer: 2,
+        },
+        QuestionItem::Numeric {
+            answer: 6.0,
+            tolerance: -1.0,
+            unit: None,
+        },
+        QuestionItem::Ordering {
+            items: vec!["Light".to_string(), "Light".to_string()],
+        },
+        QuestionItem::ShortAnswer {
+            rubric: Vec::new(),
+            pass_score: 0.5,
+        },
+    ];
+    for item in unmet {
+        assert_eq!(add(&store, "?", item), Err(ErrorCode::InvalidPayload));
+    }
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    let ordering = QuestionItem::Ordering {
+        items: vec!["Light".to_string(), "Glucose".to_string()],
+    };
+    assert_eq!(
+        add(&store, "Order the steps", ordering),
+        Err(ErrorCode::Forbidden)
+    );
+    let query = Envelope::<GraphRead>::new(ListQuestions {
+        concept: "Photosynthesis".to_string(),
+    });
+    let bank: Vec<QuestionInfo> =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(bank.len(), 2);
+    assert_eq!(bank[0], choice);
+}
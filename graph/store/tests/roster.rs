@@ -0,0 +1,211 @@
+//! Class rosters against the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    ChildProgress, ClassInfo, CreateClass, EnrollStudent, Envelope, ErrorCode, GetClassProgress,
+    GraphRead, ListRoster, RemoveStudent, Roster, RosterEntry, TransferStudent,
+};
+use serde_json::json;
+
+fn roster(store: &GraphStore, class_id: &str) -> Result<Roster, ErrorCode> {
+    read(
+        store,
+        ListRoster {
+            class_id: class_id.to_string(),
+        },
+    )
+}
+
+fn class(store: &GraphStore, name: &str) -> ClassInfo {
+    write(
+        store,
+        CreateClass {
+            name: name.to_string(),
+            timestamp: 10,
+        },
+    )
+    .unwrap()
+}
+
+fn enroll(store: &GraphStore, class_id: &str, student_id: &str) -> Result<RosterEntry, ErrorCode> {
+    write(
+        store,
+        EnrollStudent {
+            class_id: class_id.to_string(),
+            student_id: student_id.to_string(),
+            name: None,
+            timestamp: 20,
+        },
+    )
+}
+
+fn teacher() -> GraphStore {
+    let store = GraphStore::new();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    store
+}
+
+#[test]
+fn teachers_build_rosters_for_their_classes() {
+    let store = teacher();
+    let biology = class(&store, "Biology 101");
+    assert_eq!(biology.id, "class_biology_101");
+    assert_eq!(biology.created_by, "teacher-1");
+
+    let ada = write::<RosterEntry>(
+        &store,
+        EnrollStudent {
+            class_id: biology.id.clone(),
+            student_id: "ada".to_string(),
+            name: Some("Ada".to_string()),
+            timestamp: 20,
+        },
+    )
+    .unwrap();
+    assert_eq!(ada.name.as_deref(), Some("Ada"));
+    enroll(&store, &biology.id, "ben").unwrap();
+    // Enrolling twice is a no-op.
+    enroll(&store, &biology.id, "ben").unwrap();
+
+    let listed = roster(&store, &biology.id).unwrap();
+    let ids: Vec<_> = listed
+        .students
+        .iter()
+        .map(|s| s.student_id.as_str())
+        .collect();
+    assert_eq!(ids, ["ada", "ben"]);
+
+    let removed: RosterEntry = write(
+        &store,
+        RemoveStudent {
+            class_id: biology.id.clone(),
+            student_id: "ben".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(removed.student_id, "ben");
+    assert_eq!(roster(&store, &biology.id).unwrap().students.len(), 1);
+}
+
+#[test]
+fn students_belong_to_one_class_at_a_time() {
+    let store = teacher();
+    let biology = class(&store, "Biology");
+    let chemistry = class(&store, "Chemistry");
+    enroll(&store, &biology.id, "ada").unwrap();
+
+    assert_eq!(
+        enroll(&store, &chemistry.id, "ada"),
+        Err(ErrorCode::InvalidState)
+    );
+
+    let moved: RosterEntry = write(
+        &store,
+        TransferStudent {
+            student_id: "ada".to_string(),
+            from_class_id: biology.id.clone(),
+            to_class_id: chemistry.id.clone(),
+            timestamp: 30,
+        },
+    )
+    .unwrap();
+    assert_eq!(moved.class_id, chemistry.id);
+    assert_eq!(moved.enrolled_at, 30);
+    assert!(roster(&store, &biology.id).unwrap().students.is_empty());
+    assert_eq!(roster(&store, &chemistry.id).unwrap().students.len(), 1);
+
+    let enrollments: Vec<_> = store
+        .snapshot()
+        .outgoing("ada", "ENROLLED_IN")
+        .map(|r| r.to.clone())
+        .collect();
+    assert_eq!(enrollments, [chemistry.id]);
+}
+
+#[test]
+fn only_the_class_teacher_manages_its_roster() {
+    let store = teacher();
+    let biology = class(&store, "Biology");
+    assert_eq!(
+        write::<ClassInfo>(
+            &store,
+            CreateClass {
+                name: "biology".to_string(),
+                timestamp: 11,
+            }
+        ),
+        Err(ErrorCode::ConstraintViolation)
+    );
+
+    store.set_auth_ctx(json!({"subject": "teacher-2", "role": "teacher"}));
+    assert_eq!(
+        enroll(&store, &biology.id, "ada"),
+        Err(ErrorCode::Forbidden)
+    );
+    assert_eq!(roster(&store, &biology.id), Err(ErrorCode::Forbidden));
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    assert_eq!(
+        write::<ClassInfo>(
+            &store,
+            CreateClass {
+                name: "Art".to_string(),
+                timestamp: 12,
+            }
+        ),
+        Err(ErrorCode::Forbidden)
+    );
+    assert_eq!(roster(&store, "class_missing"), Err(ErrorCode::NotFound));
+}
+
+#[test]
+fn class_progress_covers_the_roster_only() {
+    let store = teacher();
+    let biology = class(&store, "Biology");
+    store
+        .add_node(
+            "fot:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    for student in ["ada", "ben"] {
+        enroll(&store, &biology.id, student).unwrap();
+        store
+            .relate(
+                student,
+                "LEARNS",
+                "fot:Photosynthesis",
+                json!({"mastery": 0.4}),
+            )
+            .unwrap();
+    }
+    write::<RosterEntry>(
+        &store,
+        RemoveStudent {
+            class_id: biology.id.clone(),
+            student_id: "ben".to_string(),
+        },
+    )
+    .unwrap();
+
+    let query = Envelope::<GraphRead>::new(GetClassProgress {
+        class_id: biology.id.clone(),
+        since: None,
+    });
+    let progress: Vec<ChildProgress> =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(progress.len(), 1);
+    assert_eq!(progress[0].child_id, "ada");
+    assert_eq!(progress[0].concepts[0].mastery, 0.4);
+
+    store.set_auth_ctx(json!({"subject": "teacher-2", "role": "teacher"}));
+    assert_eq!(
+        store.graph_read(&query.encode()),
+        Err(ErrorCode::Forbidden.code())
+    );
+}
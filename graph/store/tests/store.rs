@@ -0,0 +1,474 @@
+//! Operations and transactions against the embedded graph store.
+
+mod common;
+
+use common::{query, read, write, write_in};
+use fot_agent_sdk::host::Graph;
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    BktParams, ChildProgress, ClassInfo, ConceptInfo, CreateClass, CreateLesson, DueReview,
+    DueReviews, EnrollStudent, Envelope, ErrorCode, GetChildProgress, GetConcept, GetMastery,
+    GraphRead, LessonCreated, MasteryState, MasteryUpdated, ReviewSchedule, RosterEntry,
+    SetConceptModel, UpdateMastery,
+};
+use serde_json::json;
+
+fn seeded() -> GraphStore {
+    let store = GraphStore::new();
+    store
+        .add_node(
+            "fot:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "student-1", "role": "student"}));
+    store
+}
+
+fn mastery(concept: &str, mastery: f64) -> UpdateMastery {
+    UpdateMastery {
+        concept: concept.to_string(),
+        student_id: None,
+        mastery,
+        truth_field_strength: 0.5,
+        harmonic_coherence: 0.5,
+        context: "Biology lab".to_string(),
+        timestamp: 1_700_000_000,
+        review: None,
+        if_revision: None,
+    }
+}
+
+fn mastery_in(store: &GraphStore, txn: Option<u64>) -> Result<MasteryState, ErrorCode> {
+    let mut envelope = Envelope::<GraphRead>::new(GetMastery {
+        concept: "Photosynthesis".to_string(),
+        student_id: None,
+    });
+    envelope.txn = txn;
+    query(store, envelope)
+}
+
+fn progress(store: &GraphStore) -> ChildProgress {
+    read(
+        store,
+        GetChildProgress {
+            child_id: "student-1".to_string(),
+            since: None,
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn update_mastery_is_visible_to_get_child_progress() {
+    let store = seeded();
+    write::<MasteryUpdated>(&store, mastery("Photosynthesis", 0.25)).unwrap();
+    let reply: MasteryUpdated = write(&store, mastery("Photosynthesis", 0.5)).unwrap();
+    assert_eq!(reply.mastery, 0.5);
+
+    let progress = progress(&store);
+    assert_eq!(progress.concepts[0].concept_id, "fot:Photosynthesis");
+    assert_eq!(progress.concepts[0].concept_name, "Photosynthesis");
+    assert_eq!(progress.concepts[0].mastery, 0.5);
+    assert_eq!(progress.concepts[0].last_updated, Some(1_700_000_000));
+}
+
+#[test]
+fn stale_revisions_are_refused() {
+    let store = seeded();
+    let read = || {
+        let query = Envelope::<GraphRead>::new(GetMastery {
+            concept: "Photosynthesis".to_string(),
+            student_id: None,
+        });
+        serde_json::from_str::<MasteryState>(&store.graph_read(&query.encode()).unwrap()).unwrap()
+    };
+    let first = read();
+    assert_eq!(first.revision, 0);
+
+    // Two writers read revision 0; only the first write lands.
+    let based_on = |revision, value| UpdateMastery {
+        if_revision: Some(revision),
+        ..mastery("Photosynthesis", value)
+    };
+    write::<MasteryUpdated>(&store, based_on(first.revision, 0.2)).unwrap();
+    assert_eq!(
+        write::<MasteryUpdated>(&store, based_on(first.revision, 0.1)),
+        Err(ErrorCode::InvalidState)
+    );
+
+    let second = read();
+    assert_eq!((second.mastery, second.revision), (0.2, 1));
+    write::<MasteryUpdated>(&store, based_on(second.revision, 0.3)).unwrap();
+    assert_eq!(read().revision, 2);
+}
+
+#[test]
+fn progress_history_is_cut_at_since() {
+    let store = seeded();
+    for (at, value) in [(100, 0.2), (200, 0.4), (300, 0.3)] {
+        let update = UpdateMastery {
+            timestamp: at,
+            ..mastery("Photosynthesis", value)
+        };
+        write::<MasteryUpdated>(&store, update).unwrap();
+    }
+
+    let query = Envelope::<GraphRead>::new(GetChildProgress {
+        child_id: "student-1".to_string(),
+        since: Some(150),
+    });
+    let progress: ChildProgress =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    let concept = &progress.concepts[0];
+    assert_eq!(concept.baseline, Some(0.2));
+    let times: Vec<u64> = concept.history.iter().map(|s| s.at).collect();
+    assert_eq!(times, [200, 300]);
+}
+
+#[test]
+fn only_guardians_read_child_progress() {
+    let store = seeded();
+    write::<MasteryUpdated>(&store, mastery("Photosynthesis", 0.1)).unwrap();
+    store
+        .add_node("parent-1", "Agent", json!({"role": "parent"}))
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "parent-1", "role": "parent"}));
+
+    let query = Envelope::<GraphRead>::new(GetChildProgress {
+        child_id: "student-1".to_string(),
+        since: None,
+    })
+    .encode();
+    assert_eq!(store.graph_read(&query), Err(ErrorCode::Forbidden.code()));
+
+    store
+        .relate("parent-1", "GUARDIAN_OF", "student-1", json!({}))
+        .unwrap();
+    assert!(store.graph_read(&query).is_ok());
+}
+
+#[test]
+fn create_lesson_returns_the_new_id() {
+    let store = seeded();
+    store.add_node("class-1", "Class", json!({})).unwrap();
+    store
+        .add_node("teacher-1", "Agent", json!({"role": "teacher"}))
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let lesson = CreateLesson {
+        concept: "Photosynthesis".to_string(),
+        class_id: "class-1".to_string(),
+        content: "Light reactions".to_string(),
+        timestamp: 1_700_000_000,
+    };
+    assert_eq!(
+        write::<LessonCreated>(&store, lesson.clone()),
+        Err(ErrorCode::Forbidden)
+    );
+
+    store
+        .relate("teacher-1", "TEACHES", "class-1", json!({}))
+        .unwrap();
+    let reply: LessonCreated = write(&store, lesson).unwrap();
+
+    let snapshot = store.snapshot();
+    assert_eq!(snapshot.node(&reply.id).unwrap().label, "Lesson");
+    assert_eq!(snapshot.outgoing(&reply.id, "COVERS").count(), 1);
+}
+
+#[test]
+fn get_mastery_reads_the_callers_stored_value() {
+    let store = seeded();
+    let query = Envelope::<GraphRead>::new(GetMastery {
+        concept: "Photosynthesis".to_string(),
+        student_id: None,
+    })
+    .encode();
+    let fresh: MasteryState = serde_json::from_str(&store.graph_read(&query).unwrap()).unwrap();
+    assert_eq!(fresh.mastery, 0.0);
+    assert_eq!(fresh.updated_at, None);
+
+    write::<MasteryUpdated>(&store, mastery("Photosynthesis", 0.7)).unwrap();
+    let stored: MasteryState = serde_json::from_str(&store.graph_read(&query).unwrap()).unwrap();
+    assert_eq!(stored.concept_id, "fot:Photosynthesis");
+    assert_eq!(stored.mastery, 0.7);
+    assert_eq!(stored.updated_at, Some(1_700_000_000));
+
+    assert_eq!(
+        write::<MasteryUpdated>(&store, mastery("Photosynthesis", -0.1)),
+        Err(ErrorCode::ConstraintViolation)
+    );
+}
+
+#[test]
+fn unknown_concept_is_not_found() {
+    let store = seeded();
+    assert_eq!(
+        write::<MasteryUpdated>(&store, mastery("Alchemy", 0.1)),
+        Err(ErrorCode::NotFound)
+    );
+}
+
+#[test]
+fn unknown_operations_and_versions_are_rejected() {
+    let store = seeded();
+    let unknown = json!({"version": 1, "operation": "drop_database"}).to_string();
+    assert_eq!(
+        store.graph_write(&unknown),
+        Err(ErrorCode::UnknownOperation.code())
+    );
+
+    let newer = json!({"version": 99, "operation": "get_child_progress", "child_id": "student-1"});
+    assert_eq!(
+        store.graph_read(&newer.to_string()),
+        Err(ErrorCode::UnsupportedVersion.code())
+    );
+
+    let misspelled = json!({"operation": "update_mastery", "concpet": "Photosynthesis"});
+    assert_eq!(
+        store.graph_write(&misspelled.to_string()),
+        Err(ErrorCode::InvalidPayload.code())
+    );
+}
+
+#[test]
+fn transaction_writes_are_staged_until_commit() {
+    let store = seeded();
+    let txn = store.begin_txn();
+    write_in::<MasteryUpdated>(&store, txn, mastery("Photosynthesis", 0.3)).unwrap();
+    assert!(store.snapshot().node("student-1").is_none());
+    // Only the transaction reads its own writes.
+    assert_eq!(mastery_in(&store, Some(txn)).unwrap().mastery, 0.3);
+    assert_eq!(mastery_in(&store, None).unwrap().mastery, 0.0);
+
+    store.commit_txn(txn).unwrap();
+    assert_eq!(progress(&store).concepts[0].mastery, 0.3);
+}
+
+#[test]
+fn constraint_violation_rolls_back_the_transaction() {
+    let store = seeded();
+    let txn = store.begin_txn();
+    write_in::<MasteryUpdated>(&store, txn, mastery("Photosynthesis", 0.5)).unwrap();
+    write_in::<MasteryUpdated>(&store, txn, mastery("Photosynthesis", 1.5)).unwrap();
+
+    assert_eq!(
+        store.commit_txn(txn),
+        Err(ErrorCode::ConstraintViolation.code())
+    );
+    assert!(!store.in_txn());
+    assert!(store.snapshot().node("student-1").is_none());
+}
+
+#[test]
+fn rollback_discards_only_its_own_transaction() {
+    let store = seeded();
+    let kept = store.begin_txn();
+    let dropped = store.begin_txn();
+    write_in::<MasteryUpdated>(&store, kept, mastery("Photosynthesis", 0.2)).unwrap();
+    write_in::<MasteryUpdated>(&store, dropped, mastery("Photosynthesis", 0.9)).unwrap();
+    assert_eq!(mastery_in(&store, Some(kept)).unwrap().mastery, 0.2);
+    assert_eq!(mastery_in(&store, Some(dropped)).unwrap().mastery, 0.9);
+
+    store.rollback_txn(dropped).unwrap();
+    assert!(store.in_txn());
+    assert_eq!(
+        store.rollback_txn(dropped),
+        Err(ErrorCode::InvalidHandle.code())
+    );
+    assert_eq!(
+        write_in::<MasteryUpdated>(&store, dropped, mastery("Photosynthesis", 0.1)),
+        Err(ErrorCode::InvalidHandle)
+    );
+
+    store.commit_txn(kept).unwrap();
+    assert!(!store.in_txn());
+    assert_eq!(progress(&store).concepts[0].mastery, 0.2);
+}
+
+#[test]
+fn commits_replay_onto_writes_made_since_the_transaction_began() {
+    let store = seeded();
+    store
+        .add_node(
+            "fot:Respiration",
+            "Concept",
+            json!({"label": "Respiration"}),
+        )
+        .unwrap();
+    let txn = store.begin_txn();
+    write_in::<MasteryUpdated>(&store, txn, mastery("Photosynthesis", 0.4)).unwrap();
+    write::<MasteryUpdated>(&store, mastery("Respiration", 0.6)).unwrap();
+
+    store.commit_txn(txn).unwrap();
+    let mut masteries: Vec<_> = progress(&store)
+        .concepts
+        .into_iter()
+        .map(|c| (c.concept_name, c.mastery))
+        .collect();
+    masteries.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        masteries,
+        [
+            ("Photosynthesis".to_string(), 0.4),
+            ("Respiration".to_string(), 0.6)
+        ]
+    );
+}
+
+#[test]
+fn concepts_carry_their_knowledge_tracing_parameters() {
+    let store = seeded();
+    let bkt = BktParams {
+        prior: 0.3,
+        learn: 0.2,
+        slip: 0.1,
+        guess: 0.25,
+    };
+    let set = |bkt| SetConceptModel {
+        concept: "Photosynthesis".to_string(),
+        bkt,
+    };
+    assert_eq!(
+        write::<ConceptInfo>(&store, set(bkt)),
+        Err(ErrorCode::Forbidden)
+    );
+
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let concept: ConceptInfo = write(&store, set(bkt)).unwrap();
+    assert_eq!(concept.bkt, Some(bkt));
+    let query = Envelope::<GraphRead>::new(GetConcept {
+        concept: "fot:Photosynthesis".to_string(),
+    });
+    let read: Option<ConceptInfo> =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(read.unwrap().bkt, Some(bkt));
+
+    // A guess and slip this likely make a correct answer meaningless.
+    let noise = BktParams { guess: 0.9, ..bkt };
+    assert_eq!(
+        write::<ConceptInfo>(&store, set(noise)),
+        Err(ErrorCode::ConstraintViolation)
+    );
+}
+
+#[test]
+fn teachers_read_the_mastery_of_their_students_only() {
+    let store = seeded();
+    write::<MasteryUpdated>(&store, mastery("Photosynthesis", 0.6)).unwrap();
+    let query = Envelope::<GraphRead>::new(GetMastery {
+        concept: "Photosynthesis".to_string(),
+        student_id: Some("student-1".to_string()),
+    })
+    .encode();
+
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    assert_eq!(store.graph_read(&query), Err(ErrorCode::Forbidden.code()));
+
+    write::<ClassInfo>(
+        &store,
+        CreateClass {
+            name: "Biology".to_string(),
+            timestamp: 10,
+        },
+    )
+    .unwrap();
+    write::<RosterEntry>(
+        &store,
+        EnrollStudent {
+            class_id: "class_biology".to_string(),
+            student_id: "student-1".to_string(),
+            name: None,
+            timestamp: 20,
+        },
+    )
+    .unwrap();
+    let state: MasteryState = serde_json::from_str(&store.graph_read(&query).unwrap()).unwrap();
+    assert_eq!(state.mastery, 0.6);
+}
+
+#[test]
+fn review_schedules_are_kept_until_replaced_and_listed_when_due() {
+    let store = seeded();
+    store
+        .add_node(
+            "fot:Respiration",
+            "Concept",
+            json!({"label": "Respiration"}),
+        )
+        .unwrap();
+    let schedule = |due_at| ReviewSchedule {
+        interval_days: 1,
+        ease: 2.5,
+        repetitions: 0,
+        due_at,
+    };
+    for (concept, due_at) in [("Photosynthesis", 500), ("Respiration", 300)] {
+        let update = UpdateMastery {
+            review: Some(schedule(due_at)),
+            ..mastery(concept, 0.5)
+        };
+        write::<MasteryUpdated>(&store, update).unwrap();
+    }
+    // Without a schedule the stored one stays.
+    write::<MasteryUpdated>(&store, mastery("Photosynthesis", 0.6)).unwrap();
+
+    let due = |until| {
+        let query = Envelope::<GraphRead>::new(DueReviews { until });
+        serde_json::from_str::<Vec<DueReview>>(&store.graph_read(&query.encode()).unwrap()).unwrap()
+    };
+    let listed: Vec<_> = due(1000).into_iter().map(|d| d.concept_name).collect();
+    assert_eq!(listed, ["Respiration", "Photosynthesis"]);
+    let only = due(400);
+    assert_eq!(only.len(), 1);
+    assert_eq!(only[0].review, schedule(300));
+
+    let query = Envelope::<GraphRead>::new(GetMastery {
+        concept: "Photosynthesis".to_string(),
+        student_id: None,
+    });
+    let state: MasteryState =
+        serde_json::from_str(&store.graph_read(&query.encode()).unwrap()).unwrap();
+    assert_eq!(state.mastery, 0.6);
+    assert_eq!(state.review, Some(schedule(500)));
+}
+
+#[test]
+fn teachers_set_mastery_for_students_of_their_class() {
+    let store = seeded();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let for_student = |student: &str| UpdateMastery {
+        student_id: Some(student.to_string()),
+        ..mastery("Photosynthesis", 0.3)
+    };
+    assert_eq!(
+        write::<MasteryUpdated>(&store, for_student("student-1")),
+        Err(ErrorCode::Forbidden)
+    );
+
+    write::<ClassInfo>(
+        &store,
+        CreateClass {
+            name: "Biology".to_string(),
+            timestamp: 10,
+        },
+    )
+    .unwrap();
+    write::<RosterEntry>(
+        &store,
+        EnrollStudent {
+            class_id: "class_biology".to_string(),
+            student_id: "student-1".to_string(),
+            name: None,
+            timestamp: 20,
+        },
+    )
+    .unwrap();
+    write::<MasteryUpdated>(&store, for_student("student-1")).unwrap();
+    store.set_auth_ctx(json!({"subject": "student-1", "role": "student"}));
+    assert_eq!(progress(&store).concepts[0].mastery, 0.3);
+    let snapshot = store.snapshot();
+    assert_eq!(snapshot.outgoing("teacher-1", "LEARNS").count(), 0);
+}
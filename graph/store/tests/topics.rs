@@ -0,0 +1,185 @@
+//! Installing topic manifests into the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    BktParams, ConceptInfo, ErrorCode, GetConcept, GetTopic, InstallTopic, ListQuestions,
+    QuestionInfo, TopicInstalled, TopicManifest,
+};
+use serde_json::json;
+
+fn install(store: &GraphStore, manifest: &TopicManifest) -> Result<TopicInstalled, ErrorCode> {
+    write(
+        store,
+        InstallTopic {
+            manifest: manifest.clone(),
+            timestamp: 10,
+        },
+    )
+}
+
+fn manifest(id: &str, concept: (&str, &str), links_to: &str) -> TopicManifest {
+    serde_json::from_value(json!({
+        "id": id,
+        "title": concept.1,
+        "concepts": [{"id": concept.0, "label": concept.1, "domain": "Science"}],
+        "questions": [{
+            "id": "q1", "concept": concept.1, "prompt": "Pick one",
+            "item": {"type": "multiple_choice", "choices": ["a", "b"], "answer": 0}
+        }],
+        "links": [{"source": concept.0, "target": links_to, "link_type": "entails"}]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn installed_topics_bring_their_concepts_questions_and_links() {
+    let store = GraphStore::new();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let photosynthesis = manifest(
+        "biology.photosynthesis",
+        ("ex:Photosynthesis", "Photosynthesis"),
+        "ex:Carbon_Cycle",
+    );
+
+    // The carbon cycle is not in the graph yet, so the link waits for it.
+    let installed = install(&store, &photosynthesis).unwrap();
+    assert_eq!(installed.concepts, ["ex:Photosynthesis"]);
+    assert_eq!(installed.questions, ["biology.photosynthesis:q1"]);
+    assert!(installed.links.is_empty());
+    assert_eq!(installed.pending_links.len(), 1);
+    let bank: Vec<QuestionInfo> = read(
+        &store,
+        ListQuestions {
+            concept: "Photosynthesis".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank.len(), 1);
+
+    // Installing the carbon cycle adds the waiting link along with its own.
+    let installed = install(
+        &store,
+        &manifest(
+            "chemistry.carbon_cycle",
+            ("ex:Carbon_Cycle", "Carbon Cycle"),
+            "ex:Photosynthesis",
+        ),
+    )
+    .unwrap();
+    let targets: Vec<&str> = installed.links.iter().map(|l| l.target.as_str()).collect();
+    assert_eq!(targets, ["ex:Photosynthesis", "ex:Carbon_Cycle"]);
+    assert!(installed.pending_links.is_empty());
+    // Installing again keeps a single copy of the bank, and nothing waits.
+    let installed = install(&store, &photosynthesis).unwrap();
+    assert_eq!(installed.links[0].target, "ex:Carbon_Cycle");
+    assert_eq!(installed.links.len(), 1);
+    assert!(installed.pending_links.is_empty());
+    let bank: Vec<QuestionInfo> = read(
+        &store,
+        ListQuestions {
+            concept: "Photosynthesis".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank.len(), 1);
+    let stored: Option<TopicManifest> = read(
+        &store,
+        GetTopic {
+            topic_id: "biology.photosynthesis".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(stored, Some(photosynthesis.clone()));
+
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+    assert_eq!(install(&store, &photosynthesis), Err(ErrorCode::Forbidden));
+}
+
+#[test]
+fn a_bad_manifest_installs_nothing() {
+    let store = GraphStore::new();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let mut entropy = manifest("physics.entropy", ("ex:Entropy", "Entropy"), "ex:Heat");
+    entropy.questions[0].concept = "Heat".to_string();
+
+    assert_eq!(install(&store, &entropy), Err(ErrorCode::InvalidPayload));
+    let concept: Option<ConceptInfo> = read(
+        &store,
+        GetConcept {
+            concept: "Entropy".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(concept, None);
+}
+
+#[test]
+fn reinstalling_prunes_dropped_questions() {
+    let store = GraphStore::new();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let mut entropy = manifest("physics.entropy", ("ex:Entropy", "Entropy"), "ex:Heat");
+    let mut q2 = entropy.questions[0].clone();
+    q2.id = "q2".to_string();
+    entropy.questions.push(q2);
+    install(&store, &entropy).unwrap();
+
+    entropy.questions.remove(0);
+    let installed = install(&store, &entropy).unwrap();
+    assert_eq!(installed.questions, ["physics.entropy:q2"]);
+    let bank: Vec<QuestionInfo> = read(
+        &store,
+        ListQuestions {
+            concept: "Entropy".to_string(),
+        },
+    )
+    .unwrap();
+    let ids: Vec<&str> = bank.iter().map(|q| q.id.as_str()).collect();
+    assert_eq!(ids, ["physics.entropy:q2"]);
+    assert!(store.snapshot().node("physics.entropy:q1").is_none());
+}
+
+#[test]
+fn only_the_owning_topic_changes_a_concept() {
+    let store = GraphStore::new();
+    store.set_auth_ctx(json!({"subject": "teacher-1", "role": "teacher"}));
+    let entropy = manifest("physics.entropy", ("ex:Entropy", "Entropy"), "ex:Heat");
+    install(&store, &entropy).unwrap();
+
+    // Another topic may cover the concept as it is...
+    let mut borrowed = manifest("chemistry.entropy", ("ex:Entropy", "Entropy"), "ex:Heat");
+    borrowed.concepts[0].domain = None;
+    install(&store, &borrowed).unwrap();
+    // ...but not relabel it or change its parameters.
+    let mut relabelled = borrowed.clone();
+    relabelled.concepts[0].label = "Disorder".to_string();
+    relabelled.questions[0].concept = "Disorder".to_string();
+    assert_eq!(install(&store, &relabelled), Err(ErrorCode::Forbidden));
+    let mut retuned = borrowed.clone();
+    retuned.concepts[0].bkt = Some(BktParams::default());
+    assert_eq!(install(&store, &retuned), Err(ErrorCode::Forbidden));
+    let concept: Option<ConceptInfo> = read(
+        &store,
+        GetConcept {
+            concept: "ex:Entropy".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(concept.as_ref().map(|c| c.label.as_str()), Some("Entropy"));
+    assert_eq!(concept.and_then(|c| c.bkt), None);
+
+    // The owner still can.
+    let mut owned = entropy.clone();
+    owned.concepts[0].bkt = Some(BktParams::default());
+    install(&store, &owned).unwrap();
+    let concept: Option<ConceptInfo> = read(
+        &store,
+        GetConcept {
+            concept: "ex:Entropy".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(concept.and_then(|c| c.bkt), Some(BktParams::default()));
+}
@@ -0,0 +1,124 @@
+//! Virtues recorded on `LEARNS` against the embedded graph store.
+
+mod common;
+
+use common::{read, write};
+use fot_graph_store::GraphStore;
+use fot_protocol::{
+    Activity, ActivityHistory, ActivityPage, ChildProgress, ErrorCode, GetChildProgress,
+    RecordVirtues, Virtue, VirtueChange, VirtuesRecorded,
+};
+use serde_json::json;
+
+fn change(virtue: Virtue, delta: f64, explanation: &str) -> VirtueChange {
+    VirtueChange {
+        virtue,
+        delta,
+        explanation: explanation.to_string(),
+    }
+}
+
+fn record(student_id: Option<&str>, changes: Vec<VirtueChange>, timestamp: u64) -> RecordVirtues {
+    RecordVirtues {
+        concept: "Photosynthesis".to_string(),
+        student_id: student_id.map(str::to_string),
+        changes,
+        timestamp,
+    }
+}
+
+#[test]
+fn virtues_are_clamped_and_kept_with_their_explanations() {
+    let store = GraphStore::new();
+    store
+        .add_node(
+            "ex:Photosynthesis",
+            "Concept",
+            json!({"label": "Photosynthesis"}),
+        )
+        .unwrap();
+    store.set_auth_ctx(json!({"subject": "ada", "role": "student"}));
+
+    let first: VirtuesRecorded = write(
+        &store,
+        record(
+            None,
+            vec![
+                change(Virtue::Patience, 0.6, "Stayed with the task"),
+                change(Virtue::Patience, 0.6, "Tried again"),
+                change(Virtue::Honesty, -0.2, "Copied an answer"),
+            ],
+            10,
+        ),
+    )
+    .unwrap();
+    // Net deltas from nothing, clamped to [0, 1].
+    assert_eq!(first.concept_id, "ex:Photosynthesis");
+    assert_eq!(first.patience, Some(1.0));
+    assert_eq!(first.honesty, Some(0.0));
+    assert_eq!(first.curiosity, None);
+
+    let second: VirtuesRecorded = write(
+        &store,
+        record(
+            Some("ada"),
+            vec![change(Virtue::Patience, -0.25, "Gave up early")],
+            20,
+        ),
+    )
+    .unwrap();
+    assert_eq!(second.patience, Some(0.75));
+
+    let progress: ChildProgress = read(
+        &store,
+        GetChildProgress {
+            child_id: "ada".to_string(),
+            since: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(progress.concepts[0].patience, Some(0.75));
+    assert_eq!(progress.virtues.patience, 0.75);
+
+    let history: ActivityPage = read(
+        &store,
+        ActivityHistory {
+            student_id: None,
+            offset: 0,
+            limit: 10,
+        },
+    )
+    .unwrap();
+    assert_eq!(history.total, 4);
+    assert_eq!(
+        history.activities[0],
+        Activity::Virtue {
+            at: 20,
+            concept_id: "ex:Photosynthesis".to_string(),
+            change: change(Virtue::Patience, -0.25, "Gave up early"),
+        }
+    );
+
+    assert_eq!(
+        write::<VirtuesRecorded>(&store, record(None, Vec::new(), 30)),
+        Err(ErrorCode::InvalidPayload)
+    );
+    assert_eq!(
+        write::<VirtuesRecorded>(
+            &store,
+            record(None, vec![change(Virtue::Honesty, f64::NAN, "?")], 30)
+        ),
+        Err(ErrorCode::InvalidPayload)
+    );
+    assert_eq!(
+        write::<VirtuesRecorded>(
+            &store,
+            record(
+                Some("ben"),
+                vec![change(Virtue::Curiosity, 0.1, "Asked")],
+                30
+            )
+        ),
+        Err(ErrorCode::Forbidden)
+    );
+}